@@ -0,0 +1,119 @@
+use crate::options::Options;
+use crate::report::Report;
+use crate::workload::{Keys, Test};
+use redis_protocol::client::{ClientError, Connection};
+use redis_protocol::types::ProtocolType;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+///
+/// Runs a test with the given options. Every client runs in its own thread with its
+/// own connection, and takes batches of `pipeline` requests until all the requests
+/// of the test were sent.
+///
+pub fn run(options: &Options, test: Test) -> Result<Report, ClientError> {
+    let remaining = Arc::new(AtomicUsize::new(options.requests));
+    let value = "x".repeat(options.data_size);
+
+    let mut connections = Vec::new();
+    for _ in 0..options.clients {
+        let mut connection = Connection::connect(&options.addr())?;
+        if let Some(password) = &options.password {
+            connection.auth(password)?;
+        }
+        connections.push(connection);
+    }
+
+    let start = Instant::now();
+    let handles: Vec<_> = connections
+        .into_iter()
+        .enumerate()
+        .map(|(i, connection)| {
+            let remaining = remaining.clone();
+            let keys = Keys::new(options.keyspace, i as u64);
+            let value = value.clone();
+            let pipeline = options.pipeline;
+            thread::spawn(move || run_client(connection, test, keys, &value, pipeline, &remaining))
+        })
+        .collect();
+
+    let mut latencies = Vec::with_capacity(options.requests);
+    let mut errors = 0;
+    for handle in handles {
+        let (client_latencies, client_errors) = handle
+            .join()
+            .map_err(|_| ClientError::Io("benchmark client panicked".to_string()))??;
+        latencies.extend(client_latencies);
+        errors += client_errors;
+    }
+    Ok(Report::new(test.name(), start.elapsed(), errors, latencies))
+}
+
+/// Sends batches of requests until none remain. Returns their latencies and the number of error replies.
+fn run_client(
+    mut connection: Connection,
+    test: Test,
+    mut keys: Keys,
+    value: &str,
+    pipeline: usize,
+    remaining: &AtomicUsize,
+) -> Result<(Vec<Duration>, u64), ClientError> {
+    let mut latencies = Vec::new();
+    let mut errors = 0;
+    loop {
+        let batch = take(remaining, pipeline);
+        if batch == 0 {
+            return Ok((latencies, errors));
+        }
+        let mut commands = connection.pipeline();
+        for _ in 0..batch {
+            commands.command(&test.command(&keys.next_key(test.key_prefix()), value));
+        }
+
+        let start = Instant::now();
+        let replies = commands.execute()?;
+        let latency = start.elapsed();
+        errors += replies
+            .iter()
+            .filter(|x| matches!(x, ProtocolType::Error(_)))
+            .count() as u64;
+        latencies.extend(std::iter::repeat_n(latency, batch));
+    }
+}
+
+/// Takes up to `count` requests from the remaining ones, returning how many were taken
+fn take(remaining: &AtomicUsize, count: usize) -> usize {
+    let mut current = remaining.load(Ordering::SeqCst);
+    loop {
+        let taken = current.min(count);
+        if taken == 0 {
+            return 0;
+        }
+        match remaining.compare_exchange(
+            current,
+            current - taken,
+            Ordering::SeqCst,
+            Ordering::SeqCst,
+        ) {
+            Ok(_) => return taken,
+            Err(actual) => current = actual,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_never_takes_more_than_remaining() {
+        let remaining = AtomicUsize::new(5);
+
+        assert_eq!(take(&remaining, 2), 2);
+        assert_eq!(take(&remaining, 2), 2);
+        assert_eq!(take(&remaining, 2), 1);
+        assert_eq!(take(&remaining, 2), 0);
+    }
+}
@@ -0,0 +1,79 @@
+mod benchmark;
+mod options;
+mod report;
+mod workload;
+
+use crate::options::{Options, USAGE};
+use redis_server::config::configuration::Configuration;
+use redis_server::logging::logger::Logger;
+use redis_server::server::Server;
+use std::env;
+use std::process;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Time to wait for the local server to start
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(msj) => {
+            eprintln!("{}\n{}", msj, USAGE);
+            process::exit(1);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let mut server = None;
+    if options.local {
+        match start_server(&options) {
+            Ok(local) => server = Some(local),
+            Err(msj) => {
+                eprintln!("{}", msj);
+                process::exit(1);
+            }
+        }
+    }
+
+    for test in &options.tests {
+        match benchmark::run(&options, *test) {
+            Ok(report) => println!(
+                "{}\n",
+                report.summary(options.clients, options.pipeline, options.keyspace)
+            ),
+            Err(error) => {
+                eprintln!("{} failed: {}", test.name(), error);
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(mut server) = server {
+        server.shutdown();
+    }
+}
+
+/// Starts a server in this process, listening on the port of the options
+fn start_server(options: &Options) -> Result<Server, String> {
+    let mut config = Configuration::new();
+    config.set_port(options.port);
+    if let Some(password) = &options.password {
+        config.set_requirepass(password.clone());
+    }
+    let logger = Arc::new(Logger::new(config.get_logfile())?);
+
+    let mut server = Server::new(config, logger);
+    server.run();
+    let start = Instant::now();
+    while !server.poll_running() {
+        if start.elapsed() > STARTUP_TIMEOUT {
+            return Err("Failed to start the local server".to_string());
+        }
+    }
+    Ok(server)
+}
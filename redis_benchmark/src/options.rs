@@ -0,0 +1,134 @@
+use crate::workload::{Test, ALL_TESTS};
+
+pub const USAGE: &str = "Usage: redis_benchmark [OPTIONS]
+  -h <hostname>      Server hostname (default 127.0.0.1)
+  -p <port>          Server port (default 6379)
+  -a <password>      Password for the server
+  -c <clients>       Number of parallel connections (default 50)
+  -n <requests>      Total number of requests of each test (default 100000)
+  -d <size>          Data size of SET/LPUSH/SADD/PUBLISH values in bytes (default 3)
+  -P <numreq>        Pipeline <numreq> requests (default 1, no pipeline)
+  -r <keyspacelen>   Use random keys among <keyspacelen> keys, instead of a single key
+  -t <tests>         Comma separated list of tests to run (default all):
+                     set,get,lpush,lpop,sadd,publish
+  --local            Start a server in this process on the given port and benchmark it
+  --help             Output this help and exit";
+
+///
+/// The command line options of the benchmark.
+///
+#[derive(Debug)]
+pub struct Options {
+    pub host: String,
+    pub port: u16,
+    pub password: Option<String>,
+    pub clients: usize,
+    pub requests: usize,
+    pub data_size: usize,
+    pub pipeline: usize,
+    pub keyspace: u64,
+    pub tests: Vec<Test>,
+    pub local: bool,
+    pub help: bool,
+}
+
+impl Options {
+    /// Parses the command line arguments, without the program name
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            host: "127.0.0.1".to_string(),
+            port: 6379,
+            password: None,
+            clients: 50,
+            requests: 100_000,
+            data_size: 3,
+            pipeline: 1,
+            keyspace: 0,
+            tests: ALL_TESTS.to_vec(),
+            local: false,
+            help: false,
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" => options.host = Self::value(&mut args, arg)?.clone(),
+                "-p" => options.port = Self::number(&mut args, arg)?,
+                "-a" => options.password = Some(Self::value(&mut args, arg)?.clone()),
+                "-c" => options.clients = Self::number(&mut args, arg)?,
+                "-n" => options.requests = Self::number(&mut args, arg)?,
+                "-d" => options.data_size = Self::number(&mut args, arg)?,
+                "-P" => options.pipeline = Self::number(&mut args, arg)?,
+                "-r" => options.keyspace = Self::number(&mut args, arg)?,
+                "-t" => {
+                    options.tests = Self::value(&mut args, arg)?
+                        .split(',')
+                        .map(Test::from_name)
+                        .collect::<Result<_, _>>()?
+                }
+                "--local" => options.local = true,
+                "--help" => options.help = true,
+                option => return Err(format!("Unrecognized option: {}", option)),
+            }
+        }
+        if options.clients == 0 || options.pipeline == 0 {
+            return Err(
+                "The number of clients and the pipeline depth must be positive".to_string(),
+            );
+        }
+        Ok(options)
+    }
+
+    /// Returns the address of the server
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    fn value<'a>(
+        args: &mut impl Iterator<Item = &'a String>,
+        option: &str,
+    ) -> Result<&'a String, String> {
+        args.next()
+            .ok_or(format!("Missing value for option {}", option))
+    }
+
+    fn number<'a, T: std::str::FromStr>(
+        args: &mut impl Iterator<Item = &'a String>,
+        option: &str,
+    ) -> Result<T, String> {
+        let value = Self::value(args, option)?;
+        value
+            .parse()
+            .ok()
+            .ok_or(format!("Invalid value for option {}: {}", option, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        let args: Vec<String> = args.split_whitespace().map(|x| x.to_string()).collect();
+        Options::parse(&args)
+    }
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse("-p 7000 -c 4 -n 1000 -P 16 -r 100 -t set,GET").unwrap();
+
+        assert_eq!(options.addr(), "127.0.0.1:7000");
+        assert_eq!((options.clients, options.requests), (4, 1000));
+        assert_eq!((options.pipeline, options.keyspace), (16, 100));
+        assert_eq!(options.tests, vec![Test::Set, Test::Get]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("-c many").unwrap_err(),
+            "Invalid value for option -c: many"
+        );
+        assert_eq!(parse("-t incr").unwrap_err(), "Unknown test: incr");
+        assert!(parse("-P 0").is_err());
+    }
+}
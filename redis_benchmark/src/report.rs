@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+///
+/// Results of running a test: the time it took and the latency of every request.
+///
+pub struct Report {
+    pub name: String,
+    pub elapsed: Duration,
+    pub errors: u64,
+    latencies: Vec<Duration>,
+}
+
+impl Report {
+    pub fn new(name: &str, elapsed: Duration, errors: u64, mut latencies: Vec<Duration>) -> Self {
+        latencies.sort();
+        Report {
+            name: name.to_string(),
+            elapsed,
+            errors,
+            latencies,
+        }
+    }
+
+    /// Returns the number of completed requests
+    pub fn requests(&self) -> usize {
+        self.latencies.len()
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.requests() as f64 / seconds
+    }
+
+    /// Returns the latency under which the given percentage of requests completed
+    pub fn percentile(&self, percentage: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::from_secs(0);
+        }
+        let rank = (percentage / 100.0 * self.latencies.len() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.latencies.len()) - 1]
+    }
+
+    pub fn average(&self) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::from_secs(0);
+        }
+        self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32
+    }
+
+    /// Formats the report like redis-benchmark does
+    pub fn summary(&self, clients: usize, pipeline: usize, keyspace: u64) -> String {
+        let millis = |x: Duration| x.as_secs_f64() * 1000.0;
+        let mut lines = vec![
+            format!("====== {} ======", self.name),
+            format!(
+                "  {} requests completed in {:.2} seconds",
+                self.requests(),
+                self.elapsed.as_secs_f64()
+            ),
+            format!("  {} parallel clients", clients),
+            format!("  pipeline depth {}", pipeline),
+            format!("  keyspace {}", keyspace),
+        ];
+        if self.errors > 0 {
+            lines.push(format!("  {} errors", self.errors));
+        }
+        lines.push(format!(
+            "  throughput: {:.2} requests per second",
+            self.requests_per_second()
+        ));
+        lines.push(format!(
+            "  latency (msec): avg {:.3} p50 {:.3} p95 {:.3} p99 {:.3} max {:.3}",
+            millis(self.average()),
+            millis(self.percentile(50.0)),
+            millis(self.percentile(95.0)),
+            millis(self.percentile(99.0)),
+            millis(self.percentile(100.0)),
+        ));
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        let latencies = (1..=100).rev().map(Duration::from_millis).collect();
+        Report::new("SET", Duration::from_secs(2), 0, latencies)
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let report = report();
+
+        assert_eq!(report.percentile(50.0), Duration::from_millis(50));
+        assert_eq!(report.percentile(99.0), Duration::from_millis(99));
+        assert_eq!(report.percentile(100.0), Duration::from_millis(100));
+        assert_eq!(report.percentile(0.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_throughput_and_average() {
+        let report = report();
+
+        assert_eq!(report.requests(), 100);
+        assert_eq!(report.requests_per_second(), 50.0);
+        assert_eq!(report.average(), Duration::from_micros(50500));
+    }
+}
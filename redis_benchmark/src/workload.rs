@@ -0,0 +1,145 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Channel used by the PUBLISH test
+const CHANNEL: &str = "channel";
+
+///
+/// The commands the benchmark can run.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Test {
+    Set,
+    Get,
+    Lpush,
+    Lpop,
+    Sadd,
+    Publish,
+}
+
+pub const ALL_TESTS: [Test; 6] = [
+    Test::Set,
+    Test::Get,
+    Test::Lpush,
+    Test::Lpop,
+    Test::Sadd,
+    Test::Publish,
+];
+
+impl Test {
+    /// Returns the test with the given name, in any case
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "set" => Ok(Test::Set),
+            "get" => Ok(Test::Get),
+            "lpush" => Ok(Test::Lpush),
+            "lpop" => Ok(Test::Lpop),
+            "sadd" => Ok(Test::Sadd),
+            "publish" => Ok(Test::Publish),
+            _ => Err(format!("Unknown test: {}", name)),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Test::Set => "SET",
+            Test::Get => "GET",
+            Test::Lpush => "LPUSH",
+            Test::Lpop => "LPOP",
+            Test::Sadd => "SADD",
+            Test::Publish => "PUBLISH",
+        }
+    }
+
+    /// Returns the prefix of the keys of the test. Tests of different types use
+    /// different keys, so a list test does not find the strings of the SET test.
+    pub fn key_prefix(&self) -> &'static str {
+        match self {
+            Test::Set | Test::Get | Test::Publish => "key",
+            Test::Lpush | Test::Lpop => "mylist",
+            Test::Sadd => "myset",
+        }
+    }
+
+    /// Builds the command of the test for the given key and value
+    pub fn command(&self, key: &str, value: &str) -> Vec<String> {
+        let arguments = match self {
+            Test::Set => vec!["SET", key, value],
+            Test::Get => vec!["GET", key],
+            Test::Lpush => vec!["LPUSH", key, value],
+            Test::Lpop => vec!["LPOP", key],
+            Test::Sadd => vec!["SADD", key, value],
+            Test::Publish => vec!["PUBLISH", CHANNEL, value],
+        };
+        arguments.into_iter().map(|x| x.to_string()).collect()
+    }
+}
+
+///
+/// Generates the keys used by a client. With a key space of 0 every command
+/// uses the same key, otherwise keys are picked at random among `keyspace` keys.
+///
+pub struct Keys {
+    keyspace: u64,
+    state: u64,
+}
+
+impl Keys {
+    pub fn new(keyspace: u64, seed: u64) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_nanos() as u64)
+            .unwrap_or(0);
+        Keys {
+            keyspace,
+            // xorshift needs a state other than 0
+            state: (time ^ seed.wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1,
+        }
+    }
+
+    /// Returns the key of the next command, formatted like redis-benchmark does
+    pub fn next_key(&mut self, prefix: &str) -> String {
+        if self.keyspace == 0 {
+            return format!("{}:__rand_int__", prefix);
+        }
+        format!("{}:{:012}", prefix, self.next_random() % self.keyspace)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Test::from_name("lpush").unwrap(), Test::Lpush);
+        assert_eq!(Test::from_name("GET").unwrap(), Test::Get);
+        assert!(Test::from_name("incr").is_err());
+    }
+
+    #[test]
+    fn test_command() {
+        assert_eq!(Test::Set.command("k", "v"), vec!["SET", "k", "v"]);
+        assert_eq!(
+            Test::Publish.command("k", "v"),
+            vec!["PUBLISH", "channel", "v"]
+        );
+    }
+
+    #[test]
+    fn test_keys_stay_in_keyspace() {
+        let mut keys = Keys::new(10, 1);
+        for _ in 0..100 {
+            let key = keys.next_key("key");
+            let index: u64 = key["key:".len()..].parse().unwrap();
+            assert!(index < 10);
+        }
+        assert_eq!(Keys::new(0, 1).next_key("mylist"), "mylist:__rand_int__");
+    }
+}
@@ -0,0 +1,207 @@
+use crate::format::{format_raw, format_reply};
+use crate::options::Options;
+use redis_protocol::client::{ClientError, Connection};
+use redis_protocol::request::{split_arguments, Request};
+use redis_protocol::types::ProtocolType;
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+use std::env;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Commands sent in each write of the pipe mode
+const PIPE_BATCH_SIZE: usize = 1000;
+/// Bytes read from stdin at a time in the pipe mode
+const PIPE_CHUNK_SIZE: usize = 64 * 1024;
+/// Name of the history file of the interactive mode, in the home directory
+const HISTORY_FILE: &str = ".redis_cli_history";
+
+///
+/// Opens a connection to the server of the options, authenticated if a password was given.
+///
+pub fn connect(options: &Options) -> Result<Connection, ClientError> {
+    let mut connection = Connection::connect(&options.addr())?;
+    match (&options.user, &options.password) {
+        (Some(user), Some(password)) => connection.auth_user(user, password)?,
+        (None, Some(password)) => connection.auth(password)?,
+        _ => {}
+    }
+    Ok(connection)
+}
+
+///
+/// Runs a single command and prints its reply.
+/// `SUBSCRIBE` and `MONITOR` keep printing what the server sends until the connection is closed.
+///
+pub fn run_command(
+    connection: &mut Connection,
+    arguments: &[String],
+    raw: bool,
+) -> Result<(), ClientError> {
+    connection.send(arguments)?;
+    if is_streaming(&arguments[0]) {
+        return stream(connection, raw);
+    }
+    print_reply(&connection.receive()?, raw);
+    Ok(())
+}
+
+///
+/// Reads commands from the terminal until `quit` or `exit` is typed, or the input ends.
+/// The commands are kept in a history file, so the arrow keys recall commands of
+/// previous sessions too.
+///
+pub fn repl(options: &Options) {
+    let mut editor = Editor::<()>::new();
+    let history = history_path();
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);
+    }
+
+    let mut connection = connect_or_report(options);
+    loop {
+        let prompt = match connection {
+            Some(_) => format!("{}> ", options.addr()),
+            None => "not connected> ".to_string(),
+        };
+        let line = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(_) => break,
+        };
+        let arguments = match split_arguments(&line) {
+            Ok(arguments) if arguments.is_empty() => continue,
+            Ok(arguments) => arguments,
+            Err(_) => {
+                println!("Invalid argument(s)");
+                continue;
+            }
+        };
+        editor.add_history_entry(line.as_str());
+        let name = arguments[0].to_lowercase();
+        if name == "quit" || name == "exit" {
+            break;
+        }
+
+        if connection.is_none() {
+            connection = connect_or_report(options);
+        }
+        if let Some(active) = connection.as_mut() {
+            if let Err(error) = run_command(active, &arguments, options.raw) {
+                report(&error);
+                if active.is_broken() {
+                    connection = None;
+                }
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        let _ = editor.save_history(path);
+    }
+}
+
+///
+/// Sends every command read from stdin, in the RESP protocol or as inline commands,
+/// and prints how many replies and errors the server sent back.
+/// Stdin is read in chunks and each batch of commands is sent as soon as it is complete,
+/// so the input does not have to fit in memory.
+///
+pub fn pipe(connection: &mut Connection) -> Result<(), ClientError> {
+    let mut stdin = std::io::stdin();
+    let mut chunk = [0; PIPE_CHUNK_SIZE];
+    let mut request = Request::new();
+    let mut batch = Vec::with_capacity(PIPE_BATCH_SIZE);
+    let (mut replies, mut errors) = (0, 0);
+    loop {
+        let read = stdin.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        request.feed(&chunk[..read]);
+        while let Some(command) = request.next_command().map_err(ClientError::Protocol)? {
+            let mut arguments = vec![command.name()];
+            arguments.extend(command.arguments().iter().map(|x| x.to_string()));
+            batch.push(arguments);
+            if batch.len() == PIPE_BATCH_SIZE {
+                send_batch(connection, &mut batch, &mut replies, &mut errors)?;
+            }
+        }
+    }
+    send_batch(connection, &mut batch, &mut replies, &mut errors)?;
+
+    println!("All data transferred. Waiting for the last reply...");
+    println!("Last reply received from server.");
+    println!("errors: {}, replies: {}", errors, replies);
+    Ok(())
+}
+
+/// Sends the commands of the batch in a single write and counts their replies
+fn send_batch(
+    connection: &mut Connection,
+    batch: &mut Vec<Vec<String>>,
+    replies: &mut usize,
+    errors: &mut usize,
+) -> Result<(), ClientError> {
+    if batch.is_empty() {
+        return Ok(());
+    }
+    let mut pipeline = connection.pipeline();
+    for command in batch.drain(..) {
+        pipeline.command(&command);
+    }
+    for reply in pipeline.execute()? {
+        if let ProtocolType::Error(error) = reply {
+            eprintln!("{}", error);
+            *errors += 1;
+        }
+        *replies += 1;
+    }
+    Ok(())
+}
+
+/// Prints every reply sent by the server until the connection is closed
+fn stream(connection: &mut Connection, raw: bool) -> Result<(), ClientError> {
+    if !raw {
+        println!("Reading messages... (press Ctrl-C to quit)");
+    }
+    loop {
+        print_reply(&connection.receive()?, raw);
+    }
+}
+
+fn is_streaming(command: &str) -> bool {
+    let command = command.to_lowercase();
+    command == "subscribe" || command == "monitor"
+}
+
+fn print_reply(reply: &ProtocolType, raw: bool) {
+    if raw {
+        println!("{}", format_raw(reply));
+    } else {
+        println!("{}", format_reply(reply));
+    }
+}
+
+fn connect_or_report(options: &Options) -> Option<Connection> {
+    match connect(options) {
+        Ok(connection) => Some(connection),
+        Err(error) => {
+            report(&error);
+            None
+        }
+    }
+}
+
+/// Prints an error of the client the way redis-cli does
+pub fn report(error: &ClientError) {
+    match error {
+        ClientError::Reply(error) => println!("(error) {}", error),
+        ClientError::Io(error) => println!("Could not connect to Redis: {}", error),
+        ClientError::Protocol(error) => println!("{}", error),
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
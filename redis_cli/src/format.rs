@@ -0,0 +1,162 @@
+use redis_protocol::types::ProtocolType;
+
+///
+/// Formats a reply the way redis-cli prints it in a terminal:
+/// `(integer) 1`, `(nil)`, quoted strings and numbered arrays,
+/// whose nested elements are indented under their number.
+///
+pub fn format_reply(reply: &ProtocolType) -> String {
+    match reply {
+        ProtocolType::String(text) => quote(text),
+        ProtocolType::SimpleString(text) => text.clone(),
+        ProtocolType::Verbatim(_, text) => text.clone(),
+        ProtocolType::Integer(value) => format!("(integer) {}", value),
+        ProtocolType::Error(error) => format!("(error) {}", error),
+        ProtocolType::Nil() | ProtocolType::NilArray() | ProtocolType::Null() => {
+            "(nil)".to_string()
+        }
+        ProtocolType::Double(_) => format!("(double) {}", reply),
+        ProtocolType::Boolean(value) => format!("({})", value),
+        ProtocolType::BigNumber(number) => format!("(big number) {}", number),
+        ProtocolType::Array(items) | ProtocolType::Set(items) | ProtocolType::Push(items) => {
+            if items.is_empty() {
+                return "(empty array)".to_string();
+            }
+            let lines: Vec<String> = items.iter().map(format_reply).collect();
+            numbered(lines, ") ")
+        }
+        ProtocolType::Map(pairs) | ProtocolType::Attribute(pairs) => {
+            if pairs.is_empty() {
+                return "(empty hash)".to_string();
+            }
+            let lines = pairs
+                .iter()
+                .map(|(key, value)| format!("{} => {}", format_reply(key), format_reply(value)))
+                .collect();
+            numbered(lines, "# ")
+        }
+    }
+}
+
+///
+/// Formats a reply without decorations, the way redis-cli prints it when
+/// its output is not a terminal: one element per line and no quotes.
+///
+pub fn format_raw(reply: &ProtocolType) -> String {
+    match reply {
+        ProtocolType::Nil() | ProtocolType::NilArray() | ProtocolType::Null() => String::new(),
+        ProtocolType::Error(error) => format!("(error) {}", error),
+        ProtocolType::Array(items) | ProtocolType::Set(items) | ProtocolType::Push(items) => {
+            items.iter().map(format_raw).collect::<Vec<_>>().join("\n")
+        }
+        ProtocolType::Map(pairs) | ProtocolType::Attribute(pairs) => pairs
+            .iter()
+            .map(|(key, value)| format!("{}\n{}", format_raw(key), format_raw(value)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => reply.to_string(),
+    }
+}
+
+/// Numbers the lines, indenting the continuation lines of each element under its number
+fn numbered(elements: Vec<String>, separator: &str) -> String {
+    let width = elements.len().to_string().len();
+    let mut output = Vec::new();
+    for (i, element) in elements.iter().enumerate() {
+        let prefix = format!("{:>width$}{}", i + 1, separator, width = width);
+        let indent = " ".repeat(prefix.len());
+        for (j, line) in element.lines().enumerate() {
+            if j == 0 {
+                output.push(format!("{}{}", prefix, line));
+            } else {
+                output.push(format!("{}{}", indent, line));
+            }
+        }
+    }
+    output.join("\n")
+}
+
+/// Quotes a string, escaping the characters that are not printable
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\x07' => quoted.push_str("\\a"),
+            '\x08' => quoted.push_str("\\b"),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> ProtocolType {
+        ProtocolType::String(text.to_string())
+    }
+
+    #[test]
+    fn test_format_simple_replies() {
+        assert_eq!(format_reply(&ProtocolType::Integer(3)), "(integer) 3");
+        assert_eq!(format_reply(&ProtocolType::Nil()), "(nil)");
+        assert_eq!(
+            format_reply(&ProtocolType::SimpleString("OK".to_string())),
+            "OK"
+        );
+        assert_eq!(
+            format_reply(&ProtocolType::Error("ERR wrong".to_string())),
+            "(error) ERR wrong"
+        );
+        assert_eq!(format_reply(&string("a \"b\"\n")), "\"a \\\"b\\\"\\n\"");
+        assert_eq!(format_reply(&ProtocolType::Double(1.5)), "(double) 1.5");
+        assert_eq!(format_reply(&ProtocolType::Boolean(true)), "(true)");
+    }
+
+    #[test]
+    fn test_format_numbered_arrays() {
+        let items: Vec<ProtocolType> = (0..10).map(|x| string(&x.to_string())).collect();
+        let reply = format_reply(&ProtocolType::Array(items));
+
+        let lines: Vec<&str> = reply.lines().collect();
+        assert_eq!(lines[0], " 1) \"0\"");
+        assert_eq!(lines[9], "10) \"9\"");
+        assert_eq!(format_reply(&ProtocolType::Array(vec![])), "(empty array)");
+    }
+
+    #[test]
+    fn test_format_nested_arrays_are_indented() {
+        let reply = ProtocolType::Array(vec![
+            string("a"),
+            ProtocolType::Array(vec![ProtocolType::Integer(1), ProtocolType::Nil()]),
+        ]);
+
+        assert_eq!(
+            format_reply(&reply),
+            "1) \"a\"\n2) 1) (integer) 1\n   2) (nil)"
+        );
+    }
+
+    #[test]
+    fn test_format_maps() {
+        let reply = ProtocolType::Map(vec![(string("proto"), ProtocolType::Integer(3))]);
+
+        assert_eq!(format_reply(&reply), "1# \"proto\" => (integer) 3");
+    }
+
+    #[test]
+    fn test_format_raw() {
+        let reply = ProtocolType::Array(vec![string("a"), ProtocolType::Integer(1)]);
+
+        assert_eq!(format_raw(&reply), "a\n1");
+        assert_eq!(format_raw(&ProtocolType::Nil()), "");
+    }
+}
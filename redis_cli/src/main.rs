@@ -0,0 +1,41 @@
+mod cli;
+mod format;
+mod options;
+
+use crate::options::{Options, USAGE};
+use std::env;
+use std::io::{stdout, IsTerminal};
+use std::process;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(msj) => {
+            eprintln!("{}\n{}", msj, USAGE);
+            process::exit(1);
+        }
+    };
+    if options.help {
+        println!("{}", USAGE);
+        return;
+    }
+    if options.command.is_empty() && !options.pipe {
+        cli::repl(&options);
+        return;
+    }
+
+    // Like redis-cli, replies written to a pipe or a file are not decorated
+    options.raw |= !stdout().is_terminal();
+    let result = cli::connect(&options).and_then(|mut connection| {
+        if options.pipe {
+            cli::pipe(&mut connection)
+        } else {
+            cli::run_command(&mut connection, &options.command, options.raw)
+        }
+    });
+    if let Err(error) = result {
+        cli::report(&error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,120 @@
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+pub const DEFAULT_PORT: u16 = 6379;
+
+pub const USAGE: &str = "Usage: redis_cli [OPTIONS] [cmd [arg [arg ...]]]
+  -h <hostname>      Server hostname (default: 127.0.0.1).
+  -p <port>          Server port (default: 6379).
+  -a <password>      Password to use when connecting to the server.
+  --user <username>  Used to send ACL style 'AUTH username pass'. Needs -a.
+  --raw              Use raw formatting for replies.
+  --pipe             Transfer raw Redis protocol from stdin to server.
+  --help             Output this help and exit.
+
+When no command is given, redis_cli starts in interactive mode.";
+
+///
+/// The command line options of the client.
+///
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub host: String,
+    pub port: u16,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub raw: bool,
+    pub pipe: bool,
+    pub help: bool,
+    /// The command to run in one-shot mode, empty for interactive mode
+    pub command: Vec<String>,
+}
+
+impl Options {
+    /// Parses the command line arguments, without the program name
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            host: DEFAULT_HOST.to_string(),
+            port: DEFAULT_PORT,
+            user: None,
+            password: None,
+            raw: false,
+            pipe: false,
+            help: false,
+            command: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" => options.host = Self::value(&mut args, arg)?.clone(),
+                "-p" => {
+                    let port = Self::value(&mut args, arg)?;
+                    options.port = port.parse().ok().ok_or(format!("Invalid port: {}", port))?;
+                }
+                "-a" => options.password = Some(Self::value(&mut args, arg)?.clone()),
+                "--user" => options.user = Some(Self::value(&mut args, arg)?.clone()),
+                "--raw" => options.raw = true,
+                "--pipe" => options.pipe = true,
+                "--help" => options.help = true,
+                option if option.starts_with('-') && options.command.is_empty() => {
+                    return Err(format!("Unrecognized option: {}", option))
+                }
+                _ => {
+                    options.command.push(arg.clone());
+                    options.command.extend(args.by_ref().cloned());
+                }
+            }
+        }
+        if options.user.is_some() && options.password.is_none() {
+            return Err("--user needs a password, given with -a".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Returns the address of the server
+    pub fn addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    fn value<'a>(
+        args: &mut impl Iterator<Item = &'a String>,
+        option: &str,
+    ) -> Result<&'a String, String> {
+        args.next()
+            .ok_or(format!("Missing value for option {}", option))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Options, String> {
+        let args: Vec<String> = args.split_whitespace().map(|x| x.to_string()).collect();
+        Options::parse(&args)
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let options = parse("").unwrap();
+
+        assert_eq!(options.addr(), "127.0.0.1:6379");
+        assert!(options.command.is_empty());
+        assert!(!options.pipe);
+    }
+
+    #[test]
+    fn test_parse_one_shot_command() {
+        let options = parse("-h localhost -p 7000 -a secret GET -p").unwrap();
+
+        assert_eq!(options.addr(), "localhost:7000");
+        assert_eq!(options.password, Some("secret".to_string()));
+        assert_eq!(options.command, vec!["GET", "-p"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("-p").unwrap_err(), "Missing value for option -p");
+        assert_eq!(parse("-p abc").unwrap_err(), "Invalid port: abc");
+        assert_eq!(parse("-x").unwrap_err(), "Unrecognized option: -x");
+        assert!(parse("--user admin").is_err());
+    }
+}
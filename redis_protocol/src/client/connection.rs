@@ -0,0 +1,311 @@
+use crate::client::pipeline::Pipeline;
+use crate::client::pubsub::PubSub;
+use crate::client::ClientError;
+use crate::parser::decoder::Decoder;
+use crate::request::split_arguments;
+use crate::types::ProtocolType;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const READ_BUFFER_SIZE: usize = 16 * 1024;
+
+/// Commands that change the state of the connection on the server,
+/// or that are answered with more than one reply
+const STATEFUL_COMMANDS: [&str; 24] = [
+    "auth",
+    "hello",
+    "client",
+    "select",
+    "reset",
+    "quit",
+    "subscribe",
+    "psubscribe",
+    "ssubscribe",
+    "unsubscribe",
+    "punsubscribe",
+    "sunsubscribe",
+    "monitor",
+    "multi",
+    "exec",
+    "discard",
+    "watch",
+    "unwatch",
+    "readonly",
+    "readwrite",
+    "asking",
+    "replconf",
+    "psync",
+    "sync",
+];
+
+///
+/// A synchronous connection to a redis server.
+/// Commands are sent as arrays of bulk strings and the replies are decoded
+/// incrementally, so replies of any size and RESP3 replies are supported.
+///
+pub struct Connection {
+    stream: TcpStream,
+    decoder: Decoder,
+    broken: bool,
+    stateful: bool,
+    unanswered: usize,
+}
+
+impl Connection {
+    /// Connects to the server at the given address, like `127.0.0.1:6379`
+    pub fn connect(addr: &str) -> Result<Self, ClientError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(Connection {
+            stream,
+            decoder: Decoder::new(),
+            broken: false,
+            stateful: false,
+            unanswered: 0,
+        })
+    }
+
+    /// Sets the time to wait for a reply. None waits forever.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), ClientError> {
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.set_write_timeout(timeout)?;
+        Ok(())
+    }
+
+    /// Authenticates the connection with the password of the default user
+    pub fn auth(&mut self, password: &str) -> Result<(), ClientError> {
+        self.query(&["AUTH", password]).map(|_| ())
+    }
+
+    /// Authenticates the connection as the given user
+    pub fn auth_user(&mut self, user: &str, password: &str) -> Result<(), ClientError> {
+        self.query(&["AUTH", user, password]).map(|_| ())
+    }
+
+    ///
+    /// Sends a command and returns its reply.
+    /// Error replies are returned as `ClientError::Reply`.
+    ///
+    pub fn query<S: AsRef<str>>(&mut self, arguments: &[S]) -> Result<ProtocolType, ClientError> {
+        self.send(arguments)?;
+        match self.receive()? {
+            ProtocolType::Error(error) => Err(ClientError::Reply(error)),
+            reply => Ok(reply),
+        }
+    }
+
+    ///
+    /// Splits a line into arguments, the same way the server splits inline commands,
+    /// and sends it as a command. Returns its reply.
+    ///
+    pub fn query_line(&mut self, line: &str) -> Result<ProtocolType, ClientError> {
+        let arguments = split_arguments(line).map_err(ClientError::Protocol)?;
+        if arguments.is_empty() {
+            return Err(ClientError::Protocol("empty command".to_string()));
+        }
+        self.query(&arguments)
+    }
+
+    /// Sends a command without waiting for its reply
+    pub fn send<S: AsRef<str>>(&mut self, arguments: &[S]) -> Result<(), ClientError> {
+        self.track(arguments);
+        self.write(encode(arguments).as_bytes())
+    }
+
+    /// Sends all the commands of the pipeline in a single write
+    pub(crate) fn send_all<S: AsRef<str>>(
+        &mut self,
+        commands: &[Vec<S>],
+    ) -> Result<(), ClientError> {
+        commands.iter().for_each(|x| self.track(x));
+        let bytes: String = commands.iter().map(|x| encode(x)).collect();
+        self.write(bytes.as_bytes())
+    }
+
+    ///
+    /// Reads the next reply, waiting for it if needed.
+    /// Error replies are returned as `ProtocolType::Error` and attributes are skipped.
+    ///
+    pub fn receive(&mut self) -> Result<ProtocolType, ClientError> {
+        loop {
+            match self.decoder.next_frame() {
+                Ok(Some(ProtocolType::Attribute(_))) => continue,
+                Ok(Some(frame)) => {
+                    self.unanswered = self.unanswered.saturating_sub(1);
+                    return Ok(frame);
+                }
+                Ok(None) => self.fill()?,
+                Err(error) => {
+                    self.broken = true;
+                    return Err(ClientError::Protocol(error));
+                }
+            }
+        }
+    }
+
+    /// Creates an empty pipeline for this connection
+    pub fn pipeline(&mut self) -> Pipeline<'_> {
+        Pipeline::new(self)
+    }
+
+    /// Turns the connection into a pub/sub connection
+    pub fn into_pubsub(self) -> PubSub {
+        PubSub::new(self)
+    }
+
+    ///
+    /// Returns true if the connection can not be used anymore,
+    /// because it was closed or it received an invalid reply.
+    ///
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    ///
+    /// Returns true if the connection can be used by another caller: it is not broken,
+    /// every reply was read and no command changed its state, like `SELECT` or `SUBSCRIBE`.
+    ///
+    pub fn is_reusable(&self) -> bool {
+        !self.broken && !self.stateful && self.unanswered == 0 && self.decoder.pending().is_empty()
+    }
+
+    /// Forgets the commands that changed the state of the connection,
+    /// used once the pool has authenticated a new connection
+    pub(crate) fn mark_reusable(&mut self) {
+        self.stateful = false;
+    }
+
+    fn track<S: AsRef<str>>(&mut self, arguments: &[S]) {
+        self.unanswered += 1;
+        if let Some(name) = arguments.first() {
+            let name = name.as_ref().to_ascii_lowercase();
+            self.stateful |= STATEFUL_COMMANDS.contains(&name.as_str());
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), ClientError> {
+        self.stream.write_all(bytes).map_err(|error| {
+            self.broken = true;
+            ClientError::from(error)
+        })
+    }
+
+    fn fill(&mut self) -> Result<(), ClientError> {
+        let mut buffer = [0; READ_BUFFER_SIZE];
+        match self.stream.read(&mut buffer) {
+            Ok(0) => {
+                self.broken = true;
+                Err(ClientError::Io(
+                    "connection closed by the server".to_string(),
+                ))
+            }
+            Ok(read) => {
+                self.decoder.feed(&buffer[..read]);
+                Ok(())
+            }
+            Err(error) => {
+                self.broken = true;
+                Err(ClientError::from(error))
+            }
+        }
+    }
+}
+
+/// Encodes a command as an array of bulk strings
+fn encode<S: AsRef<str>>(arguments: &[S]) -> String {
+    ProtocolType::Array(
+        arguments
+            .iter()
+            .map(|x| ProtocolType::String(x.as_ref().to_string()))
+            .collect(),
+    )
+    .serialize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Starts a server that reads once and answers with the given bytes
+    fn mock_server(reply: &'static [u8]) -> (String, thread::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            let read = stream.read(&mut buffer).unwrap();
+            for chunk in reply.chunks(3) {
+                stream.write_all(chunk).unwrap();
+            }
+            buffer[..read].to_vec()
+        });
+        (addr, handle)
+    }
+
+    #[test]
+    fn test_query_encodes_arguments_as_bulk_strings() {
+        let (addr, server) = mock_server(b"+OK\r\n");
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        let reply = connection.query(&["SET", "key", "a value"]).unwrap();
+
+        assert_eq!(reply.to_string(), "OK");
+        assert_eq!(
+            server.join().unwrap(),
+            b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$7\r\na value\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_query_line_splits_quoted_arguments() {
+        let (addr, server) = mock_server(b"$5\r\nhello\r\n");
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        let reply = connection.query_line("ECHO \"hello\"").unwrap();
+
+        assert_eq!(reply.to_string(), "hello");
+        assert_eq!(
+            server.join().unwrap(),
+            b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_query_returns_error_replies_as_errors() {
+        let (addr, _server) = mock_server(b"-ERR unknown command\r\n");
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        let reply = connection.query(&["FOO"]);
+
+        assert_eq!(
+            reply.err(),
+            Some(ClientError::Reply("ERR unknown command".to_string()))
+        );
+        assert!(!connection.is_broken());
+    }
+
+    #[test]
+    fn test_receive_decodes_replies_split_across_reads() {
+        let (addr, _server) = mock_server(b"*2\r\n$5\r\nfirst\r\n%1\r\n+a\r\n:1\r\n");
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        let reply = connection.query(&["GET"]).unwrap();
+
+        assert_eq!(reply.to_string(), "[first, {a: 1}]");
+    }
+
+    #[test]
+    fn test_closed_connection_is_broken() {
+        let (addr, _server) = mock_server(b"");
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        assert!(matches!(
+            connection.query(&["PING"]),
+            Err(ClientError::Io(_))
+        ));
+        assert!(connection.is_broken());
+    }
+}
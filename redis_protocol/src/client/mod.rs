@@ -0,0 +1,40 @@
+pub mod connection;
+pub mod pipeline;
+pub mod pool;
+pub mod pubsub;
+
+pub use connection::Connection;
+pub use pipeline::Pipeline;
+pub use pool::{Pool, PooledConnection};
+pub use pubsub::{Message, PubSub};
+
+use std::fmt;
+
+///
+/// Errors returned by the RESP client.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientError {
+    /// The connection failed, timed out or was closed by the server
+    Io(String),
+    /// The server sent bytes that are not a valid RESP reply
+    Protocol(String),
+    /// The server replied with an error, like `ERR unknown command`
+    Reply(String),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::Io(error) => write!(f, "IO error: {}", error),
+            ClientError::Protocol(error) => write!(f, "{}", error),
+            ClientError::Reply(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(error: std::io::Error) -> Self {
+        ClientError::Io(error.to_string())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::client::connection::Connection;
+use crate::client::ClientError;
+use crate::types::ProtocolType;
+
+///
+/// A batch of commands sent in a single write, whose replies are read afterwards.
+/// Saves a round trip per command.
+///
+pub struct Pipeline<'a> {
+    connection: &'a mut Connection,
+    commands: Vec<Vec<String>>,
+}
+
+impl<'a> Pipeline<'a> {
+    pub(crate) fn new(connection: &'a mut Connection) -> Self {
+        Pipeline {
+            connection,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Adds a command to the pipeline
+    pub fn command<S: AsRef<str>>(&mut self, arguments: &[S]) -> &mut Self {
+        self.commands
+            .push(arguments.iter().map(|x| x.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Returns the number of commands in the pipeline
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    ///
+    /// Sends every command and returns their replies, in order.
+    /// Error replies are kept as `ProtocolType::Error`, so a failed command
+    /// does not hide the replies of the others.
+    ///
+    pub fn execute(&mut self) -> Result<Vec<ProtocolType>, ClientError> {
+        let commands = std::mem::take(&mut self.commands);
+        if commands.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.connection.send_all(&commands)?;
+        commands.iter().map(|_| self.connection.receive()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_pipeline_reads_one_reply_per_command() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let expected = b"*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n*2\r\n$4\r\nINCR\r\n$1\r\na\r\n*1\r\n$3\r\nFOO\r\n";
+            let mut received = vec![0; expected.len()];
+            stream.read_exact(&mut received).unwrap();
+            assert_eq!(received, expected.to_vec());
+            stream
+                .write_all(b"+OK\r\n:2\r\n-ERR unknown command\r\n")
+                .unwrap();
+        });
+        let mut connection = Connection::connect(&addr).unwrap();
+
+        let mut pipeline = connection.pipeline();
+        pipeline
+            .command(&["SET", "a", "1"])
+            .command(&["INCR", "a"])
+            .command(&["FOO"]);
+        let replies = pipeline.execute().unwrap();
+
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0].to_string(), "OK");
+        assert_eq!(replies[1].integer().unwrap(), 2);
+        assert_eq!(
+            replies[2].clone().error().unwrap(),
+            "ERR unknown command".to_string()
+        );
+        assert!(pipeline.is_empty());
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,196 @@
+use crate::client::connection::Connection;
+use crate::client::ClientError;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::time::Duration;
+
+///
+/// A pool of connections to the same server, shareable between threads.
+/// Connections are opened on demand and returned to the pool when dropped,
+/// keeping at most `max_idle` of them open.
+///
+pub struct Pool {
+    addr: String,
+    password: Option<String>,
+    timeout: Option<Duration>,
+    max_idle: usize,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl Pool {
+    /// Creates an empty pool of connections to the given address
+    pub fn new(addr: &str, max_idle: usize) -> Self {
+        Pool {
+            addr: addr.to_string(),
+            password: None,
+            timeout: None,
+            max_idle,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Authenticates every new connection with the given password
+    pub fn with_password(mut self, password: Option<&str>) -> Self {
+        self.password = password.map(|x| x.to_string());
+        self
+    }
+
+    /// Sets the time every new connection waits for a reply
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Takes an idle connection from the pool, or opens a new one if there is none
+    pub fn get(&self) -> Result<PooledConnection<'_>, ClientError> {
+        let idle = self.idle.lock().ok().and_then(|mut x| x.pop());
+        let connection = match idle {
+            Some(connection) => connection,
+            None => self.open()?,
+        };
+        Ok(PooledConnection {
+            pool: self,
+            connection: Some(connection),
+        })
+    }
+
+    /// Returns the number of idle connections
+    pub fn idle(&self) -> usize {
+        self.idle.lock().map(|x| x.len()).unwrap_or(0)
+    }
+
+    fn open(&self) -> Result<Connection, ClientError> {
+        let mut connection = Connection::connect(&self.addr)?;
+        connection.set_timeout(self.timeout)?;
+        if let Some(password) = &self.password {
+            connection.auth(password)?;
+            connection.mark_reusable();
+        }
+        Ok(connection)
+    }
+
+    fn release(&self, connection: Connection) {
+        if !connection.is_reusable() {
+            return;
+        }
+        if let Ok(mut idle) = self.idle.lock() {
+            if idle.len() < self.max_idle {
+                idle.push(connection);
+            }
+        }
+    }
+}
+
+///
+/// A connection taken from a pool, returned to it when dropped.
+/// Connections that are broken, or left in another state by the commands
+/// they ran, are closed instead.
+///
+pub struct PooledConnection<'a> {
+    pool: &'a Pool,
+    connection: Option<Connection>,
+}
+
+impl Deref for PooledConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.connection.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        self.connection.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConnection<'_> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.release(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_pool_reuses_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            for _ in 0..2 {
+                assert!(stream.read(&mut buffer).unwrap() > 0);
+                stream.write_all(b"+PONG\r\n").unwrap();
+            }
+        });
+        let pool = Pool::new(&addr, 1);
+
+        for _ in 0..2 {
+            let mut connection = pool.get().unwrap();
+            assert_eq!(connection.query(&["PING"]).unwrap().to_string(), "PONG");
+        }
+
+        assert_eq!(pool.idle(), 1);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_pool_drops_broken_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            drop(stream);
+        });
+        let pool = Pool::new(&addr, 1);
+
+        let mut connection = pool.get().unwrap();
+        server.join().unwrap();
+        assert!(connection.query(&["PING"]).is_err());
+        drop(connection);
+
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn test_pool_drops_connections_left_in_another_state() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            for reply in [
+                &b"+OK\r\n"[..],
+                b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n",
+            ] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 1024];
+                assert!(stream.read(&mut buffer).unwrap() > 0);
+                stream.write_all(reply).unwrap();
+            }
+        });
+        let pool = Pool::new(&addr, 2);
+
+        let mut connection = pool.get().unwrap();
+        assert_eq!(
+            connection.query(&["select", "1"]).unwrap().to_string(),
+            "OK"
+        );
+        drop(connection);
+        assert_eq!(pool.idle(), 0);
+
+        let mut connection = pool.get().unwrap();
+        connection.query(&["Subscribe", "a", "b"]).unwrap();
+        assert!(!connection.is_reusable());
+        drop(connection);
+        assert_eq!(pool.idle(), 0);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use crate::client::connection::Connection;
+use crate::client::ClientError;
+use crate::types::ProtocolType;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+///
+/// A message published to one of the subscribed channels.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub channel: String,
+    pub payload: String,
+}
+
+///
+/// A connection in subscription mode. It only receives the messages of its
+/// channels, and the confirmations of its subscribe and unsubscribe commands.
+///
+pub struct PubSub {
+    connection: Connection,
+    messages: VecDeque<Message>,
+    subscriptions: i64,
+}
+
+impl PubSub {
+    pub(crate) fn new(connection: Connection) -> Self {
+        PubSub {
+            connection,
+            messages: VecDeque::new(),
+            subscriptions: 0,
+        }
+    }
+
+    /// Subscribes to the given channels, waiting for the server to confirm each one
+    pub fn subscribe<S: AsRef<str>>(&mut self, channels: &[S]) -> Result<(), ClientError> {
+        if channels.is_empty() {
+            return Ok(());
+        }
+        let mut command = vec!["SUBSCRIBE"];
+        command.extend(channels.iter().map(|x| x.as_ref()));
+        self.connection.send(&command)?;
+        for _ in channels {
+            self.confirmation("subscribe")?;
+        }
+        Ok(())
+    }
+
+    ///
+    /// Unsubscribes from the given channels, or from every channel if none is given.
+    /// Messages received while waiting for the confirmations are kept for `next_message`.
+    ///
+    pub fn unsubscribe<S: AsRef<str>>(&mut self, channels: &[S]) -> Result<(), ClientError> {
+        let mut command = vec!["UNSUBSCRIBE"];
+        command.extend(channels.iter().map(|x| x.as_ref()));
+        self.connection.send(&command)?;
+        if channels.is_empty() {
+            while self.confirmation("unsubscribe")? > 0 {}
+        } else {
+            for _ in channels {
+                self.confirmation("unsubscribe")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of channels the connection is subscribed to
+    pub fn subscriptions(&self) -> i64 {
+        self.subscriptions
+    }
+
+    /// Sets the time `next_message` waits for a message. None waits forever.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) -> Result<(), ClientError> {
+        self.connection.set_timeout(timeout)
+    }
+
+    /// Waits for the next message published to one of the subscribed channels
+    pub fn next_message(&mut self) -> Result<Message, ClientError> {
+        if let Some(message) = self.messages.pop_front() {
+            return Ok(message);
+        }
+        loop {
+            let reply = self.connection.receive()?;
+            if let Some(message) = parse_message(&reply) {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Leaves subscription mode, returning the connection
+    pub fn into_connection(mut self) -> Result<Connection, ClientError> {
+        if self.subscriptions > 0 {
+            self.unsubscribe::<&str>(&[])?;
+        }
+        Ok(self.connection)
+    }
+
+    /// Reads replies until the confirmation of the given kind, and returns the subscriptions left
+    fn confirmation(&mut self, kind: &str) -> Result<i64, ClientError> {
+        loop {
+            let reply = self.connection.receive()?;
+            if let ProtocolType::Error(error) = reply {
+                return Err(ClientError::Reply(error));
+            }
+            if let Some(message) = parse_message(&reply) {
+                self.messages.push_back(message);
+                continue;
+            }
+            let items = match reply {
+                ProtocolType::Array(items) | ProtocolType::Push(items) => items,
+                _ => continue,
+            };
+            if items.len() == 3 && items[0].to_string() == kind {
+                self.subscriptions = items[2].integer().unwrap_or(0);
+                return Ok(self.subscriptions);
+            }
+        }
+    }
+}
+
+/// Returns the message of a `message` push, or None for any other reply
+fn parse_message(reply: &ProtocolType) -> Option<Message> {
+    match reply {
+        ProtocolType::Array(items) | ProtocolType::Push(items)
+            if items.len() == 3 && items[0].to_string() == "message" =>
+        {
+            Some(Message {
+                channel: items[1].to_string(),
+                payload: items[2].to_string(),
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_subscribe_and_receive_messages() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buffer = [0; 1024];
+            assert!(stream.read(&mut buffer).unwrap() > 0);
+            stream
+                .write_all(b"*3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n")
+                .unwrap();
+            stream
+                .write_all(b"*3\r\n$9\r\nsubscribe\r\n$1\r\nb\r\n:2\r\n")
+                .unwrap();
+            stream
+                .write_all(b"*3\r\n$7\r\nmessage\r\n$1\r\nb\r\n$2\r\nhi\r\n")
+                .unwrap();
+            assert!(stream.read(&mut buffer).unwrap() > 0);
+            stream
+                .write_all(b">3\r\n$7\r\nmessage\r\n$1\r\na\r\n$3\r\nbye\r\n")
+                .unwrap();
+            stream
+                .write_all(b">3\r\n$11\r\nunsubscribe\r\n$1\r\na\r\n:1\r\n")
+                .unwrap();
+            stream
+                .write_all(b">3\r\n$11\r\nunsubscribe\r\n$1\r\nb\r\n:0\r\n")
+                .unwrap();
+        });
+        let mut pubsub = Connection::connect(&addr).unwrap().into_pubsub();
+
+        pubsub.subscribe(&["a", "b"]).unwrap();
+        assert_eq!(pubsub.subscriptions(), 2);
+        let message = pubsub.next_message().unwrap();
+        assert_eq!(
+            message,
+            Message {
+                channel: "b".to_string(),
+                payload: "hi".to_string()
+            }
+        );
+
+        pubsub.unsubscribe::<&str>(&[]).unwrap();
+        assert_eq!(pubsub.subscriptions(), 0);
+        assert_eq!(pubsub.next_message().unwrap().payload, "bye");
+        server.join().unwrap();
+    }
+}
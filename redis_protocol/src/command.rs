@@ -14,6 +14,25 @@ impl Command {
         Command { symbol, arguments }
     }
 
+    /// Create a command from a decoded RESP frame, which must be a non empty array
+    /// of strings whose first element is the name of the command.
+    pub fn from_frame(frame: ProtocolType) -> Result<Self, String> {
+        let mut arguments = frame
+            .array()
+            .map_err(|_| "Protocol error: expected an array of bulk strings".to_string())?;
+        if arguments.is_empty() {
+            return Err("Protocol error: empty command".to_string());
+        }
+        if arguments
+            .iter()
+            .any(|x| !matches!(x, ProtocolType::String(_)))
+        {
+            return Err("Protocol error: expected an array of bulk strings".to_string());
+        }
+        let symbol = arguments.remove(0).to_string();
+        Ok(Command::new(symbol, arguments))
+    }
+
     /// Returns the name of the command
     pub fn name(&self) -> String {
         self.symbol.clone()
@@ -1,3 +1,4 @@
+pub mod client;
 pub mod command;
 pub mod parser;
 pub mod request;
@@ -6,7 +6,7 @@ use crate::types::ProtocolType;
 /// Parses a serialized RESP array into a ProtocolType::Array
 ///
 pub struct ArrayParser {
-    count: u32,
+    count: i64,
     parsed_header: bool,
     last_parser_completed: bool,
     parsers: Vec<Box<dyn ProtocolParser>>,
@@ -36,12 +36,12 @@ impl ProtocolParser for ArrayParser {
                 let len = line.len();
                 let slice = line[1..len - 2].to_string();
                 return match slice.parse() {
-                    Ok(val) => {
+                    Ok(val) if val >= -1 => {
                         self.count = val;
                         self.parsed_header = true;
-                        Ok(self.count == 0)
+                        Ok(self.count <= 0)
                     }
-                    Err(_) => Err(format!("Invalid array length '{}' received.", slice)),
+                    _ => Err(format!("Invalid array length '{}' received.", slice)),
                 };
             } else if self.last_parser_completed {
                 let parser = ParserFactory::create(symbol);
@@ -59,7 +59,10 @@ impl ProtocolParser for ArrayParser {
     }
 
     fn build(&self) -> ProtocolType {
-        assert_eq!(self.count, self.parsers.len() as u32);
+        if self.count == -1 {
+            return ProtocolType::NilArray();
+        }
+        assert_eq!(self.count, self.parsers.len() as i64);
 
         let mut data = Vec::new();
         for parser in &self.parsers {
@@ -76,7 +79,7 @@ mod tests {
     fn parse_array(lines: Vec<&str>) -> Vec<ProtocolType> {
         let mut parser = ArrayParser::new();
         for line in lines {
-            parser.feed(&line.to_string()).unwrap();
+            parser.feed(line).unwrap();
         }
         parser.build().array().unwrap()
     }
@@ -117,6 +120,18 @@ mod tests {
         assert_eq!(nested_arr[0].integer().unwrap(), 4);
     }
 
+    #[test]
+    fn parse_null_arrays() {
+        let mut parser = ArrayParser::new();
+        assert!(parser.feed("*-1\r\n").unwrap());
+        assert!(matches!(parser.build(), ProtocolType::NilArray()));
+
+        let result = parse_array(vec!["*3\r\n", "*-1\r\n", "$-1\r\n", ":1\r\n"]);
+        assert!(matches!(result[0], ProtocolType::NilArray()));
+        assert!(matches!(result[1], ProtocolType::Nil()));
+        assert!(ArrayParser::new().feed("*-2\r\n").is_err());
+    }
+
     #[test]
     fn parse_empty_array() {
         let lines = vec!["*0\r\n"];
@@ -0,0 +1,347 @@
+use crate::types::ProtocolType;
+
+/// Longest line accepted while waiting for its CRLF terminator
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+/// Longest bulk string accepted, the same limit used by redis
+const MAX_BULK_LENGTH: i64 = 512 * 1024 * 1024;
+/// Maximum number of elements of an array
+const MAX_ARRAY_LENGTH: i64 = 1024 * 1024;
+/// Maximum nesting of arrays
+const MAX_DEPTH: usize = 128;
+
+///
+/// Decodes the RESP2 or RESP3 frame at the start of the given bytes.
+/// An attribute is returned as a frame of its own, before the reply it describes.
+/// Returns the frame and the number of bytes it used, or None if the bytes
+/// do not contain a complete frame yet. Malformed frames return an Err.
+///
+pub fn decode(bytes: &[u8]) -> Result<Option<(ProtocolType, usize)>, String> {
+    decode_frame(bytes, 0)
+}
+
+fn decode_frame(bytes: &[u8], depth: usize) -> Result<Option<(ProtocolType, usize)>, String> {
+    let (line, mut consumed) = match read_line(bytes)? {
+        Some(line) => line,
+        None => return Ok(None),
+    };
+    let (prefix, rest) = match line.split_first() {
+        Some(x) => x,
+        None => return Err("Protocol error: empty line".to_string()),
+    };
+
+    let frame = match prefix {
+        b'+' => ProtocolType::SimpleString(String::from_utf8_lossy(rest).to_string()),
+        b'-' => ProtocolType::Error(String::from_utf8_lossy(rest).to_string()),
+        b':' => ProtocolType::Integer(parse_integer(rest, "invalid integer")?),
+        b'_' if rest.is_empty() => ProtocolType::Null(),
+        b'#' => match rest {
+            b"t" => ProtocolType::Boolean(true),
+            b"f" => ProtocolType::Boolean(false),
+            _ => return Err("Protocol error: invalid boolean".to_string()),
+        },
+        b',' => ProtocolType::Double(
+            std::str::from_utf8(rest)
+                .ok()
+                .and_then(|x| x.parse().ok())
+                .ok_or_else(|| "Protocol error: invalid double".to_string())?,
+        ),
+        b'(' => {
+            let digits = rest.strip_prefix(b"-").unwrap_or(rest);
+            if digits.is_empty() || !digits.iter().all(|x| x.is_ascii_digit()) {
+                return Err("Protocol error: invalid big number".to_string());
+            }
+            ProtocolType::BigNumber(String::from_utf8_lossy(rest).to_string())
+        }
+        b'$' | b'=' => {
+            let length = parse_integer(rest, "invalid bulk length")?;
+            if length == -1 && *prefix == b'$' {
+                return Ok(Some((ProtocolType::Nil(), consumed)));
+            }
+            if !(0..=MAX_BULK_LENGTH).contains(&length) {
+                return Err("Protocol error: invalid bulk length".to_string());
+            }
+            let end = consumed + length as usize;
+            if bytes.len() < end + 2 {
+                return Ok(None);
+            }
+            if &bytes[end..end + 2] != b"\r\n" {
+                return Err("Protocol error: bulk string not terminated by CRLF".to_string());
+            }
+            // Values are kept as a String, so a payload which is not UTF-8 is rejected
+            // instead of replacing its bytes
+            let data = String::from_utf8(bytes[consumed..end].to_vec())
+                .map_err(|_| "Protocol error: invalid UTF-8 in bulk string".to_string())?;
+            consumed = end + 2;
+            if *prefix == b'$' {
+                ProtocolType::String(data)
+            } else if data.len() >= 4 && data.as_bytes()[3] == b':' {
+                ProtocolType::Verbatim(data[..3].to_string(), data[4..].to_string())
+            } else {
+                return Err("Protocol error: invalid verbatim string".to_string());
+            }
+        }
+        b'*' | b'~' | b'>' | b'%' | b'|' => {
+            let length = parse_integer(rest, "invalid multibulk length")?;
+            if length == -1 && *prefix == b'*' {
+                return Ok(Some((ProtocolType::NilArray(), consumed)));
+            }
+            if !(0..=MAX_ARRAY_LENGTH).contains(&length) {
+                return Err("Protocol error: invalid multibulk length".to_string());
+            }
+            if depth >= MAX_DEPTH {
+                return Err("Protocol error: too many nested arrays".to_string());
+            }
+            let pairs = matches!(prefix, b'%' | b'|');
+            let count = if pairs { length * 2 } else { length };
+            let mut items = Vec::new();
+            for _ in 0..count {
+                match decode_frame(&bytes[consumed..], depth + 1)? {
+                    Some((item, used)) => {
+                        items.push(item);
+                        consumed += used;
+                    }
+                    None => return Ok(None),
+                }
+            }
+            match prefix {
+                b'*' => ProtocolType::Array(items),
+                b'~' => ProtocolType::Set(items),
+                b'>' => ProtocolType::Push(items),
+                b'%' => ProtocolType::Map(into_pairs(items)),
+                _ => ProtocolType::Attribute(into_pairs(items)),
+            }
+        }
+        other => {
+            return Err(format!(
+                "Protocol error: unknown type prefix '{}'",
+                *other as char
+            ))
+        }
+    };
+    Ok(Some((frame, consumed)))
+}
+
+fn into_pairs(items: Vec<ProtocolType>) -> Vec<(ProtocolType, ProtocolType)> {
+    let mut pairs = Vec::new();
+    let mut items = items.into_iter();
+    while let (Some(key), Some(value)) = (items.next(), items.next()) {
+        pairs.push((key, value));
+    }
+    pairs
+}
+
+/// Returns the bytes before the first CRLF and the number of bytes up to and including it
+fn read_line(bytes: &[u8]) -> Result<Option<(&[u8], usize)>, String> {
+    match bytes.windows(2).position(|x| x == b"\r\n") {
+        Some(end) => Ok(Some((&bytes[..end], end + 2))),
+        None if bytes.len() > MAX_LINE_LENGTH => Err("Protocol error: too big line".to_string()),
+        None => Ok(None),
+    }
+}
+
+fn parse_integer(bytes: &[u8], error: &str) -> Result<i64, String> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| format!("Protocol error: {}", error))
+}
+
+///
+/// Incremental RESP decoder. Buffers the chunks read from a connection
+/// and returns the frames as soon as they are complete.
+///
+pub struct Decoder {
+    buffer: Vec<u8>,
+    position: usize,
+}
+
+impl Decoder {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Decoder {
+            buffer: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// Adds a chunk of bytes, of any size, to the buffer
+    pub fn feed(&mut self, bytes: &[u8]) {
+        if self.position > 0 {
+            self.buffer.drain(..self.position);
+            self.position = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete frame of the buffer, or None if more bytes are needed.
+    pub fn next_frame(&mut self) -> Result<Option<ProtocolType>, String> {
+        match decode(self.pending())? {
+            Some((frame, used)) => {
+                self.consume(used);
+                Ok(Some(frame))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the next line of the buffer without its `\n` or `\r\n` terminator,
+    /// or None if the line is not complete yet.
+    pub fn next_line(&mut self) -> Result<Option<String>, String> {
+        let end = match self.pending().iter().position(|x| *x == b'\n') {
+            Some(end) => end,
+            None if self.pending().len() > MAX_LINE_LENGTH => {
+                return Err("Protocol error: too big inline request".to_string())
+            }
+            None => return Ok(None),
+        };
+        let line = self.pending()[..end]
+            .strip_suffix(b"\r")
+            .unwrap_or(&self.pending()[..end]);
+        let line = String::from_utf8(line.to_vec())
+            .map_err(|_| "Protocol error: invalid UTF-8 in inline request".to_string())?;
+        self.consume(end + 1);
+        Ok(Some(line))
+    }
+
+    fn consume(&mut self, used: usize) {
+        self.position += used;
+        if self.position == self.buffer.len() {
+            self.buffer.clear();
+            self.position = 0;
+        }
+    }
+
+    /// Returns the bytes of the buffer, which do not form a complete frame yet
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer[self.position..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_returns_consumed_bytes() {
+        let bytes = b"*2\r\n$3\r\nGET\r\n$3\r\nkey\r\n:1\r\n";
+        let (frame, used) = decode(bytes).unwrap().unwrap();
+
+        assert_eq!(used, 22);
+        let items = frame.array().unwrap();
+        assert_eq!(items[0].clone().string().unwrap(), "GET");
+        assert_eq!(items[1].clone().string().unwrap(), "key");
+        let (frame, used) = decode(&bytes[22..]).unwrap().unwrap();
+        assert_eq!((frame.integer().unwrap(), used), (1, 4));
+    }
+
+    #[test]
+    fn test_decode_bulk_string_with_crlf() {
+        let (frame, _) = decode(b"$7\r\nfoo\r\nba\r\n").unwrap().unwrap();
+        assert_eq!(frame.string().unwrap(), "foo\r\nba");
+    }
+
+    #[test]
+    fn test_decode_incomplete_frames() {
+        let bytes = b"*2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n";
+        for end in 0..bytes.len() {
+            assert!(decode(&bytes[..end]).unwrap().is_none());
+        }
+        assert!(decode(bytes).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_decode_null_and_simple_types() {
+        assert!(matches!(
+            decode(b"$-1\r\n").unwrap(),
+            Some((ProtocolType::Nil(), 5))
+        ));
+        assert!(matches!(
+            decode(b"*-1\r\n").unwrap(),
+            Some((ProtocolType::NilArray(), 5))
+        ));
+        let (frame, _) = decode(b"-ERR failed\r\n").unwrap().unwrap();
+        assert_eq!(frame.error().unwrap(), "ERR failed");
+        let (frame, _) = decode(b"+OK\r\n").unwrap().unwrap();
+        assert_eq!(frame.string().unwrap(), "OK");
+    }
+
+    #[test]
+    fn test_decode_resp3_types() {
+        let bytes = b"%2\r\n+proto\r\n:3\r\n$4\r\nflag\r\n#t\r\n";
+        let (frame, used) = decode(bytes).unwrap().unwrap();
+        assert_eq!(used, bytes.len());
+        assert_eq!(frame.to_string(), "{proto: 3, flag: true}");
+
+        let values = [
+            ProtocolType::Set(vec![ProtocolType::Double(1.5), ProtocolType::Null()]),
+            ProtocolType::Push(vec![ProtocolType::BigNumber(
+                "-12345678901234567890".to_string(),
+            )]),
+            ProtocolType::Verbatim("txt".to_string(), "a\r\nb".to_string()),
+            ProtocolType::Attribute(vec![(
+                ProtocolType::Integer(1),
+                ProtocolType::Double(f64::INFINITY),
+            )]),
+        ];
+        for value in values.iter() {
+            let serialized = value.serialize_resp3();
+            let (frame, _) = decode(serialized.as_bytes()).unwrap().unwrap();
+            assert_eq!(frame.serialize_resp3(), serialized);
+        }
+        assert!(decode(b"#x\r\n").is_err());
+        assert!(decode(b",abc\r\n").is_err());
+        assert!(decode(b"(12a\r\n").is_err());
+        assert!(decode(b"=3\r\ntxt\r\n").is_err());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(b"$abc\r\n").is_err());
+        assert!(decode(b"$-2\r\n").is_err());
+        assert!(decode(b"*1\r\n$3\r\nfoobar\r\n").is_err());
+        assert!(decode(b"?\r\n").is_err());
+        assert!(decode(b"\r\n").is_err());
+        assert!(decode(&[b'*'; MAX_LINE_LENGTH + 1]).is_err());
+        assert!(decode("*1\r\n".repeat(MAX_DEPTH + 1).as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        assert!(decode(b"$2\r\n\xff\xfe\r\n").is_err());
+        assert!(decode(b"=6\r\ntxt:\xc3\x28\r\n").is_err());
+        let (frame, _) = decode("$2\r\n\u{e9}\r\n".as_bytes()).unwrap().unwrap();
+        assert_eq!(frame.string().unwrap(), "\u{e9}");
+
+        let mut decoder = Decoder::new();
+        decoder.feed(b"GET \xff\n");
+        assert!(decoder.next_line().is_err());
+    }
+
+    #[test]
+    fn test_decoder_byte_by_byte() {
+        let bytes = b"*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n";
+        let mut decoder = Decoder::new();
+        let mut frames = Vec::new();
+        for byte in bytes.iter() {
+            decoder.feed(&[*byte]);
+            while let Some(frame) = decoder.next_frame().unwrap() {
+                frames.push(frame);
+            }
+        }
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].to_string(), "[ECHO, hi]");
+        assert!(decoder.pending().is_empty());
+    }
+
+    #[test]
+    fn test_decoder_next_line() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"PING\r\nECHO hi\nSET");
+
+        assert_eq!(decoder.next_line().unwrap().unwrap(), "PING");
+        assert_eq!(decoder.next_line().unwrap().unwrap(), "ECHO hi");
+        assert!(decoder.next_line().unwrap().is_none());
+        assert_eq!(decoder.pending(), b"SET");
+    }
+}
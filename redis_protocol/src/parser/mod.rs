@@ -1,4 +1,5 @@
 pub mod array;
+pub mod decoder;
 pub mod error;
 pub mod integer;
 pub mod string;
@@ -10,9 +11,10 @@ use crate::parser::string::*;
 use crate::types::ProtocolType;
 
 ///
-/// Common functions between all RESP parsers
+/// Common functions between all RESP parsers.
+/// Parsers are Send so a partially parsed request can be resumed by another thread.
 ///
-pub trait ProtocolParser {
+pub trait ProtocolParser: Send {
     /// Return the prefix of this RESP parser
     fn get_prefix(&self) -> char;
     /// Process a new line. Returns true if it finished parsing.
@@ -36,12 +38,9 @@ impl ParserFactory {
             Box::new(BulkStringParser::new()),
             Box::new(ErrorParser::new()),
         ];
-        for option in options {
-            if option.get_prefix() == symbol {
-                return Some(option);
-            }
-        }
-        None
+        options
+            .into_iter()
+            .find(|option| option.get_prefix() == symbol)
     }
 
     /// Return a bool representing if the given symbol has a valid parser.
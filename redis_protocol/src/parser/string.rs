@@ -63,11 +63,11 @@ impl ProtocolParser for BulkStringParser {
         if symbol == self.get_prefix() {
             let slice_result = line[1..len - 2].to_string();
             match slice_result.parse::<i32>() {
-                Ok(val) => {
+                Ok(val) if val >= -1 => {
                     self.length = val;
                     Ok(matches!(val, -1))
                 }
-                Err(_) => Err(format!("Invalid '{}' length received.", slice_result)),
+                _ => Err(format!("Invalid '{}' length received.", slice_result)),
             }
         } else {
             self.data = line[0..self.length as usize].to_string();
@@ -103,12 +103,19 @@ mod tests {
     fn test_parse_bulk_string() {
         let mut parser = BulkStringParser::new();
 
-        assert!(!parser.feed(&"$22\r\n".to_string()).unwrap());
-        assert!(parser
-            .feed(&"Hi! I am a Bulk String\r\n".to_string())
-            .unwrap());
+        assert!(!parser.feed("$22\r\n").unwrap());
+        assert!(parser.feed("Hi! I am a Bulk String\r\n").unwrap());
 
         let result = parser.build().clone().string().unwrap();
         assert_eq!(result, "Hi! I am a Bulk String");
     }
+
+    #[test]
+    fn test_parse_null_bulk_string() {
+        let mut parser = BulkStringParser::new();
+
+        assert!(parser.feed("$-1\r\n").unwrap());
+        assert!(matches!(parser.build(), ProtocolType::Nil()));
+        assert!(BulkStringParser::new().feed("$-2\r\n").is_err());
+    }
 }
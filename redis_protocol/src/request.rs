@@ -1,10 +1,12 @@
 use crate::command::Command;
-use crate::parser::array::ArrayParser;
-use crate::parser::ProtocolParser;
+use crate::parser::decoder::Decoder;
+use crate::types::ProtocolType;
 
-/// Parses a RESP command request line by line.
+/// Parses RESP command requests from chunks of any size.
+/// Requests are arrays of bulk strings, or inline commands: lines of arguments
+/// separated by spaces, as typed by hand in a telnet session.
 pub struct Request {
-    parser: ArrayParser,
+    decoder: Decoder,
 }
 
 impl Request {
@@ -12,24 +14,121 @@ impl Request {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Request {
-            parser: ArrayParser::new(),
+            decoder: Decoder::new(),
         }
     }
 
-    /// Feed a line to the internal parser
-    pub fn feed(&mut self, line: &str) -> Result<bool, String> {
-        self.parser.feed(line)
+    /// Feed a chunk of bytes to the internal decoder
+    pub fn feed(&mut self, chunk: &[u8]) {
+        self.decoder.feed(chunk);
     }
 
-    /// Build a new command from the parsed request.
-    pub fn build(&self) -> Command {
-        let mut types = self.parser.build().array().unwrap();
-        let symbol = types[0].clone().string().unwrap();
-        types.remove(0);
-        Command::new(symbol, types)
+    /// Returns the next complete command, or None if more bytes are needed.
+    /// Empty inline commands are skipped.
+    pub fn next_command(&mut self) -> Result<Option<Command>, String> {
+        loop {
+            match self.decoder.pending().first() {
+                None => return Ok(None),
+                Some(b'*') => {
+                    return match self.decoder.next_frame()? {
+                        Some(frame) => Command::from_frame(frame).map(Some),
+                        None => Ok(None),
+                    }
+                }
+                Some(_) => {
+                    let line = match self.decoder.next_line()? {
+                        Some(line) => line,
+                        None => return Ok(None),
+                    };
+                    let mut arguments = split_arguments(&line)?;
+                    if arguments.is_empty() {
+                        continue;
+                    }
+                    let symbol = arguments.remove(0);
+                    let arguments = arguments.into_iter().map(ProtocolType::String).collect();
+                    return Ok(Some(Command::new(symbol, arguments)));
+                }
+            }
+        }
+    }
+}
+
+///
+/// Splits an inline command into its arguments, the same way redis does.
+/// Arguments are separated by spaces and may be quoted. Double quoted arguments
+/// accept the escapes `\n`, `\r`, `\t`, `\b`, `\a` and `\xHH`, single quoted ones only `\'`.
+///
+pub fn split_arguments(line: &str) -> Result<Vec<String>, String> {
+    const UNBALANCED: &str = "Protocol error: unbalanced quotes in request";
+    let bytes = line.as_bytes();
+    let mut arguments = Vec::new();
+    let mut i = 0;
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return Ok(arguments);
+        }
+
+        let mut current = Vec::new();
+        let mut quote = None;
+        loop {
+            let byte = match bytes.get(i) {
+                Some(byte) => *byte,
+                None if quote.is_some() => return Err(UNBALANCED.to_string()),
+                None => break,
+            };
+            match quote {
+                Some(b'"') if byte == b'\\' && i + 3 < bytes.len() && bytes[i + 1] == b'x' => {
+                    // The digits are decoded from the bytes, as a multi-byte char may follow
+                    match (hex_digit(bytes[i + 2]), hex_digit(bytes[i + 3])) {
+                        (Some(high), Some(low)) => {
+                            current.push(high * 16 + low);
+                            i += 3;
+                        }
+                        _ => current.push(byte),
+                    }
+                }
+                Some(b'"') if byte == b'\\' && i + 1 < bytes.len() => {
+                    i += 1;
+                    current.push(match bytes[i] {
+                        b'n' => b'\n',
+                        b'r' => b'\r',
+                        b't' => b'\t',
+                        b'b' => 8,
+                        b'a' => 7,
+                        other => other,
+                    });
+                }
+                Some(b'\'') if byte == b'\\' && bytes.get(i + 1) == Some(&b'\'') => {
+                    i += 1;
+                    current.push(b'\'');
+                }
+                Some(closing) if byte == closing => {
+                    // The closing quote must be followed by a space or the end of the line
+                    if bytes.get(i + 1).is_some_and(|x| !x.is_ascii_whitespace()) {
+                        return Err(UNBALANCED.to_string());
+                    }
+                    i += 1;
+                    break;
+                }
+                Some(_) => current.push(byte),
+                None if byte.is_ascii_whitespace() => break,
+                None if byte == b'"' || byte == b'\'' => quote = Some(byte),
+                None => current.push(byte),
+            }
+            i += 1;
+        }
+        arguments.push(String::from_utf8_lossy(&current).to_string());
     }
 }
 
+/// Returns the value of an ASCII hexadecimal digit
+fn hex_digit(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|x| x as u8)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,13 +139,76 @@ mod tests {
         let mut request = Request::new();
 
         for line in sample_request {
-            request.feed(&line.to_string()).unwrap();
+            request.feed(line.as_bytes());
         }
 
-        let command = request.build();
+        let command = request.next_command().unwrap().unwrap();
         assert_eq!(command.name(), "LLEN".to_string());
         let args = command.arguments();
         assert_eq!(args.len(), 1);
         assert_eq!(args[0].clone().string().unwrap(), "mylist");
     }
+
+    #[test]
+    fn test_parse_request_in_chunks() {
+        let mut request = Request::new();
+
+        request.feed(b"*2\r\n$3\r\nGE");
+        assert!(request.next_command().unwrap().is_none());
+        request.feed(b"T\r\n$4\r\na\r\nb\r\n");
+
+        let command = request.next_command().unwrap().unwrap();
+        assert_eq!(command.name(), "GET");
+        assert_eq!(command.arguments()[0].to_string(), "a\r\nb");
+    }
+
+    #[test]
+    fn test_build_invalid_request() {
+        let mut request = Request::new();
+        request.feed(b"*1\r\n:1\r\n");
+        assert!(request.next_command().is_err());
+        let mut request = Request::new();
+        request.feed(b"*1\r\n$x\r\n");
+        assert!(request.next_command().is_err());
+    }
+
+    #[test]
+    fn test_parse_inline_requests() {
+        let mut request = Request::new();
+        request.feed(b"PING\r\n\r\nSET foo \"hello world\"\n*1\r\n$4\r\nPING\r\n");
+
+        assert_eq!(request.next_command().unwrap().unwrap().name(), "PING");
+        let command = request.next_command().unwrap().unwrap();
+        assert_eq!(command.name(), "SET");
+        assert_eq!(command.arguments()[1].to_string(), "hello world");
+        assert_eq!(request.next_command().unwrap().unwrap().name(), "PING");
+        assert!(request.next_command().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_split_arguments() {
+        assert_eq!(
+            split_arguments("  set  key 'it\\'s' \"a\\tb\\x41\" ").unwrap(),
+            vec!["set", "key", "it's", "a\tbA"]
+        );
+        assert_eq!(split_arguments("\"\" ''").unwrap(), vec!["", ""]);
+        assert!(split_arguments("").unwrap().is_empty());
+        assert!(split_arguments("set \"key").is_err());
+        assert!(split_arguments("set \"key\"value").is_err());
+    }
+
+    #[test]
+    fn test_split_arguments_invalid_hex_escapes() {
+        assert_eq!(
+            split_arguments("set k \"\\x1é\"").unwrap(),
+            vec!["set", "k", "\\x1é"]
+        );
+        assert_eq!(
+            split_arguments("set k \"\\xé1\"").unwrap(),
+            vec!["set", "k", "\\xé1"]
+        );
+        assert_eq!(split_arguments("\"\\x4\"").unwrap(), vec!["\\x4"]);
+        assert!(split_arguments("\"\\x").is_err());
+        assert!(split_arguments("\"\\x4").is_err());
+    }
 }
@@ -31,6 +31,15 @@ impl ResponseBuilder {
             .join("")
     }
 
+    /// Serializes the objects into a RESP3 compatible format.
+    pub fn serialize_resp3(&self) -> String {
+        self.results
+            .iter()
+            .map(|x| x.serialize_resp3())
+            .collect::<Vec<String>>()
+            .join("")
+    }
+
     pub fn is_empty(&self) -> bool {
         self.results.is_empty()
     }
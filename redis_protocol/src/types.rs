@@ -1,5 +1,9 @@
+use std::fmt;
+
 ///
 /// Represents all the possible RESP types.
+/// The RESP3 types are downgraded to their RESP2 equivalent when serialized with `serialize`,
+/// and kept by `serialize_resp3`.
 ///
 #[derive(Clone)]
 pub enum ProtocolType {
@@ -8,7 +12,28 @@ pub enum ProtocolType {
     Integer(i64),
     Array(Vec<ProtocolType>),
     Error(String),
+    /// Null bulk string, the reply for a missing value.
     Nil(),
+    /// Null array, the reply for a missing list of values.
+    NilArray(),
+    /// RESP3 map of key value pairs. A flat array in RESP2.
+    Map(Vec<(ProtocolType, ProtocolType)>),
+    /// RESP3 unordered collection. An array in RESP2.
+    Set(Vec<ProtocolType>),
+    /// RESP3 floating point number. A bulk string in RESP2.
+    Double(f64),
+    /// RESP3 boolean. The integer 1 or 0 in RESP2.
+    Boolean(bool),
+    /// RESP3 null. A null bulk string in RESP2.
+    Null(),
+    /// RESP3 integer of any size, as its decimal digits. A bulk string in RESP2.
+    BigNumber(String),
+    /// RESP3 string with a three letters format, like `txt`. A bulk string in RESP2.
+    Verbatim(String, String),
+    /// RESP3 out of band data, like pub/sub messages. An array in RESP2.
+    Push(Vec<ProtocolType>),
+    /// RESP3 auxiliary information about the reply that follows it. Omitted in RESP2.
+    Attribute(Vec<(ProtocolType, ProtocolType)>),
 }
 
 #[allow(dead_code)]
@@ -18,7 +43,7 @@ impl ProtocolType {
     ///
     pub fn array(self) -> Result<Vec<ProtocolType>, &'static str> {
         match self {
-            ProtocolType::Array(vec) => Ok(vec),
+            ProtocolType::Array(vec) | ProtocolType::Set(vec) | ProtocolType::Push(vec) => Ok(vec),
             _ => Err("Type is not array"),
         }
     }
@@ -64,34 +89,121 @@ impl ProtocolType {
     }
 
     ///
-    /// Serializes the ProtocolType into RESP format.
+    /// Serializes the ProtocolType into RESP2 format.
     ///
     pub fn serialize(&self) -> String {
         match self {
-            ProtocolType::Array(vec) => format!(
-                "*{}\r\n{}",
-                vec.len(),
-                vec.iter()
-                    .map(|x| x.serialize())
-                    .collect::<Vec<_>>()
-                    .join("")
-            ),
+            ProtocolType::Array(vec) | ProtocolType::Set(vec) | ProtocolType::Push(vec) => {
+                serialize_resp2_array(vec.iter())
+            }
+            ProtocolType::Map(pairs) => {
+                serialize_resp2_array(pairs.iter().flat_map(|(k, v)| vec![k, v]))
+            }
             ProtocolType::SimpleString(err) => format!("+{}\r\n", err),
             ProtocolType::String(str) => format!("${}\r\n{}\r\n", str.len(), str),
-            ProtocolType::Integer(int) => format!(":{}\r\n", int.to_string()),
+            ProtocolType::Integer(int) => format!(":{}\r\n", int),
             ProtocolType::Error(err) => format!("-{}\r\n", err),
-            ProtocolType::Nil() => "$-1\r\n".to_string(),
+            ProtocolType::Nil() | ProtocolType::Null() => "$-1\r\n".to_string(),
+            ProtocolType::NilArray() => "*-1\r\n".to_string(),
+            ProtocolType::Double(_) | ProtocolType::BigNumber(_) | ProtocolType::Verbatim(_, _) => {
+                ProtocolType::String(self.to_string()).serialize()
+            }
+            ProtocolType::Boolean(value) => format!(":{}\r\n", *value as i64),
+            ProtocolType::Attribute(_) => String::new(),
         }
     }
-}
 
-impl ToString for ProtocolType {
     ///
-    /// Returns a string with a displayable representation of the ProtocolType.
+    /// Serializes the ProtocolType into RESP3 format.
     ///
-    fn to_string(&self) -> String {
+    pub fn serialize_resp3(&self) -> String {
         match self {
-            ProtocolType::Array(vec) => format!(
+            ProtocolType::Array(vec) => {
+                format!("*{}\r\n{}", vec.len(), serialize_all(vec.iter(), true))
+            }
+            ProtocolType::Set(vec) => {
+                format!("~{}\r\n{}", vec.len(), serialize_all(vec.iter(), true))
+            }
+            ProtocolType::Push(vec) => {
+                format!(">{}\r\n{}", vec.len(), serialize_all(vec.iter(), true))
+            }
+            ProtocolType::Map(pairs) => format!("%{}\r\n{}", pairs.len(), serialize_pairs(pairs)),
+            ProtocolType::Attribute(pairs) => {
+                format!("|{}\r\n{}", pairs.len(), serialize_pairs(pairs))
+            }
+            ProtocolType::Nil() | ProtocolType::NilArray() | ProtocolType::Null() => {
+                "_\r\n".to_string()
+            }
+            ProtocolType::Double(_) => format!(",{}\r\n", self),
+            ProtocolType::Boolean(value) => format!("#{}\r\n", if *value { 't' } else { 'f' }),
+            ProtocolType::BigNumber(number) => format!("({}\r\n", number),
+            ProtocolType::Verbatim(format, text) => {
+                format!("={}\r\n{}:{}\r\n", text.len() + 4, format, text)
+            }
+            _ => self.serialize(),
+        }
+    }
+}
+
+/// Serializes the values as a RESP2 array. Attributes have no RESP2 equivalent,
+/// so they are dropped and not counted in the length of the array.
+fn serialize_resp2_array<'a, I: Iterator<Item = &'a ProtocolType>>(values: I) -> String {
+    let values: Vec<&ProtocolType> = values
+        .filter(|x| !matches!(x, ProtocolType::Attribute(_)))
+        .collect();
+    format!(
+        "*{}\r\n{}",
+        values.len(),
+        serialize_all(values.into_iter(), false)
+    )
+}
+
+fn serialize_all<'a, I: Iterator<Item = &'a ProtocolType>>(values: I, resp3: bool) -> String {
+    values
+        .map(|x| {
+            if resp3 {
+                x.serialize_resp3()
+            } else {
+                x.serialize()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+fn serialize_pairs(pairs: &[(ProtocolType, ProtocolType)]) -> String {
+    serialize_all(pairs.iter().flat_map(|(k, v)| vec![k, v]), true)
+}
+
+/// Formats a double the way RESP3 expects it, with `inf`, `-inf` and `nan` for the special values
+fn format_double(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn format_pairs(pairs: &[(ProtocolType, ProtocolType)]) -> String {
+    format!(
+        "{{{}}}",
+        pairs
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+impl fmt::Display for ProtocolType {
+    ///
+    /// Writes a displayable representation of the ProtocolType.
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let repr = match self {
+            ProtocolType::Array(vec) | ProtocolType::Set(vec) | ProtocolType::Push(vec) => format!(
                 "[{}]",
                 vec.iter()
                     .map(|x| x.to_string())
@@ -102,8 +214,16 @@ impl ToString for ProtocolType {
             ProtocolType::SimpleString(str) => str.clone(),
             ProtocolType::Integer(int) => int.to_string(),
             ProtocolType::Error(err) => err.clone(),
-            ProtocolType::Nil() => "nil".to_string(),
-        }
+            ProtocolType::Nil() | ProtocolType::NilArray() | ProtocolType::Null() => {
+                "nil".to_string()
+            }
+            ProtocolType::Map(pairs) | ProtocolType::Attribute(pairs) => format_pairs(pairs),
+            ProtocolType::Double(value) => format_double(*value),
+            ProtocolType::Boolean(value) => value.to_string(),
+            ProtocolType::BigNumber(number) => number.clone(),
+            ProtocolType::Verbatim(_, text) => text.clone(),
+        };
+        write!(f, "{}", repr)
     }
 }
 
@@ -201,4 +321,73 @@ mod tests {
         ]);
         assert_eq!(val.serialize(), "*3\r\n:10\r\n*2\r\n:4\r\n:3\r\n:1\r\n");
     }
+
+    #[test]
+    fn test_serialize_nil() {
+        assert_eq!(ProtocolType::Nil().serialize(), "$-1\r\n");
+        assert_eq!(ProtocolType::NilArray().serialize(), "*-1\r\n");
+        assert_eq!(ProtocolType::NilArray().serialize_resp3(), "_\r\n");
+        assert!(ProtocolType::NilArray().array().is_err());
+    }
+
+    #[test]
+    fn test_serialize_resp3_types() {
+        let map = ProtocolType::Map(vec![(
+            ProtocolType::String("proto".to_string()),
+            ProtocolType::Integer(3),
+        )]);
+        assert_eq!(map.serialize_resp3(), "%1\r\n$5\r\nproto\r\n:3\r\n");
+        assert_eq!(map.serialize(), "*2\r\n$5\r\nproto\r\n:3\r\n");
+
+        let set = ProtocolType::Set(vec![ProtocolType::Nil()]);
+        assert_eq!(set.serialize_resp3(), "~1\r\n_\r\n");
+        assert_eq!(set.serialize(), "*1\r\n$-1\r\n");
+
+        let push = ProtocolType::Push(vec![ProtocolType::Boolean(true)]);
+        assert_eq!(push.serialize_resp3(), ">1\r\n#t\r\n");
+        assert_eq!(push.serialize(), "*1\r\n:1\r\n");
+    }
+
+    #[test]
+    fn test_serialize_resp3_scalars() {
+        assert_eq!(ProtocolType::Double(1.5).serialize_resp3(), ",1.5\r\n");
+        assert_eq!(
+            ProtocolType::Double(f64::NEG_INFINITY).serialize_resp3(),
+            ",-inf\r\n"
+        );
+        assert_eq!(ProtocolType::Double(1.5).serialize(), "$3\r\n1.5\r\n");
+        assert_eq!(ProtocolType::Null().serialize_resp3(), "_\r\n");
+        assert_eq!(
+            ProtocolType::BigNumber("12345678901234567890".to_string()).serialize_resp3(),
+            "(12345678901234567890\r\n"
+        );
+        let verbatim = ProtocolType::Verbatim("txt".to_string(), "Some string".to_string());
+        assert_eq!(verbatim.serialize_resp3(), "=15\r\ntxt:Some string\r\n");
+        assert_eq!(verbatim.serialize(), "$11\r\nSome string\r\n");
+        assert_eq!(ProtocolType::Attribute(vec![]).serialize(), "");
+    }
+
+    #[test]
+    fn test_serialize_attributes_in_resp2_arrays() {
+        let attribute = ProtocolType::Attribute(vec![(
+            ProtocolType::SimpleString("ttl".to_string()),
+            ProtocolType::Integer(3600),
+        )]);
+        let array = ProtocolType::Array(vec![
+            attribute.clone(),
+            ProtocolType::Integer(1),
+            ProtocolType::Integer(2),
+        ]);
+        assert_eq!(array.serialize(), "*2\r\n:1\r\n:2\r\n");
+        assert_eq!(
+            array.serialize_resp3(),
+            "*3\r\n|1\r\n+ttl\r\n:3600\r\n:1\r\n:2\r\n"
+        );
+
+        let map = ProtocolType::Map(vec![(
+            ProtocolType::String("key".to_string()),
+            ProtocolType::Array(vec![attribute]),
+        )]);
+        assert_eq!(map.serialize(), "*2\r\n$3\r\nkey\r\n*0\r\n");
+    }
 }
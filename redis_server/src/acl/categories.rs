@@ -0,0 +1,162 @@
+use crate::command_table::{self, COMMANDS};
+
+/// Categories a command can belong to, used by the `+@category` and `-@category` ACL rules.
+pub const CATEGORIES: [&str; 7] = [
+    "keyspace",
+    "read",
+    "write",
+    "admin",
+    "dangerous",
+    "pubsub",
+    "connection",
+];
+
+/// Returns the name of every known command.
+pub fn all_commands() -> impl Iterator<Item = &'static str> {
+    COMMANDS.iter().map(|x| x.name)
+}
+
+/// Returns if the command is known by the server.
+pub fn is_command(name: &str) -> bool {
+    command_table::lookup(name).is_some()
+}
+
+/// Returns the commands that belong to the category, or None if the category does not exist.
+pub fn commands_in(category: &str) -> Option<Vec<&'static str>> {
+    if category == "all" {
+        return Some(all_commands().collect());
+    }
+    if !CATEGORIES.contains(&category) {
+        return None;
+    }
+    Some(
+        COMMANDS
+            .iter()
+            .filter(|x| x.categories.contains(&category))
+            .map(|x| x.name)
+            .collect(),
+    )
+}
+
+/// Returns the keys accessed by a command given its arguments.
+pub fn keys(name: &str, arguments: &[String]) -> Vec<String> {
+    let positions: Vec<usize> = match name {
+        "memory"
+            if arguments
+                .first()
+                .is_some_and(|x| x.eq_ignore_ascii_case("usage")) =>
+        {
+            vec![1]
+        }
+        "sort" => {
+            let mut positions = vec![0];
+            positions.extend(
+                arguments
+                    .iter()
+                    .position(|x| x.eq_ignore_ascii_case("store"))
+                    .map(|x| x + 1),
+            );
+            positions
+        }
+        _ => command_table::lookup(name)
+            .map(|x| x.key_positions(arguments.len()))
+            .unwrap_or_default(),
+    };
+    positions
+        .into_iter()
+        .filter_map(|x| arguments.get(x).cloned())
+        .collect()
+}
+
+/// Returns the BY and GET patterns of a SORT command that read other keys: every GET pattern
+/// but `#`, and the BY pattern if it has a `*`, as without one it only skips sorting.
+pub fn sort_patterns(arguments: &[String]) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut i = 1;
+    while i < arguments.len() {
+        let value = arguments.get(i + 1);
+        match &arguments[i].to_ascii_lowercase()[..] {
+            "by" => {
+                patterns.extend(value.filter(|x| x.contains('*')).cloned());
+                i += 2;
+            }
+            "get" => {
+                patterns.extend(value.filter(|x| *x != "#").cloned());
+                i += 2;
+            }
+            "store" => i += 2,
+            "limit" => i += 3,
+            _ => i += 1,
+        }
+    }
+    patterns
+}
+
+/// Returns the channels accessed by a command given its arguments.
+pub fn channels(name: &str, arguments: &[String]) -> Vec<String> {
+    match name {
+        "publish" => arguments.iter().take(1).cloned().collect(),
+        "subscribe" => arguments.to_vec(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arguments(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_categories() {
+        assert!(commands_in("read").unwrap().contains(&"get"));
+        assert!(!commands_in("write").unwrap().contains(&"get"));
+        assert!(commands_in("pubsub").unwrap().contains(&"publish"));
+        assert!(commands_in("unknown").is_none());
+        assert_eq!(commands_in("all").unwrap().len(), all_commands().count());
+    }
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(keys("get", &arguments(&["key"])), vec!["key"]);
+        assert_eq!(
+            keys("mset", &arguments(&["a", "1", "b", "2"])),
+            vec!["a", "b"]
+        );
+        assert_eq!(keys("rename", &arguments(&["a", "b"])), vec!["a", "b"]);
+        assert_eq!(
+            keys("sort", &arguments(&["list", "ALPHA", "STORE", "dst"])),
+            vec!["list", "dst"]
+        );
+        assert!(keys("ping", &arguments(&["hola"])).is_empty());
+        assert!(keys("keys", &arguments(&["*"])).is_empty());
+    }
+
+    #[test]
+    fn test_sort_patterns() {
+        assert_eq!(
+            sort_patterns(&arguments(&[
+                "list", "BY", "nosort", "LIMIT", "0", "10", "GET", "#", "GET", "user:*"
+            ])),
+            vec!["user:*"]
+        );
+        assert_eq!(
+            sort_patterns(&arguments(&["list", "by", "weight_*", "STORE", "get"])),
+            vec!["weight_*"]
+        );
+    }
+
+    #[test]
+    fn test_channels() {
+        assert_eq!(
+            channels("publish", &arguments(&["news", "hola"])),
+            vec!["news"]
+        );
+        assert_eq!(
+            channels("subscribe", &arguments(&["a", "b"])),
+            vec!["a", "b"]
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod categories;
+pub mod user;
+pub mod users;
@@ -0,0 +1,380 @@
+use crate::acl::categories;
+use crate::error::RedisError;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+/// Struct User. Holds the credentials and the permissions of an ACL user.
+///
+/// * `enabled` - If the user can authenticate (`on` / `off`).
+/// * `nopass` - If any password is accepted for the user.
+/// * `passwords` - SHA256 of the passwords accepted, in hexadecimal.
+/// * `commands` - Commands the user is allowed to run.
+/// * `command_rules` - Rules that built `commands`, kept to describe the user.
+/// * `keys` - Patterns of the keys the user can access.
+/// * `channels` - Patterns of the pub/sub channels the user can access.
+#[derive(Clone)]
+pub struct User {
+    name: String,
+    enabled: bool,
+    nopass: bool,
+    passwords: BTreeSet<String>,
+    commands: BTreeSet<String>,
+    command_rules: Vec<String>,
+    keys: Vec<String>,
+    channels: Vec<String>,
+}
+
+impl User {
+    /// Creates a disabled user without passwords nor permissions.
+    pub fn new(name: &str) -> Self {
+        User {
+            name: name.to_string(),
+            enabled: false,
+            nopass: false,
+            passwords: BTreeSet::new(),
+            commands: BTreeSet::new(),
+            command_rules: vec!["-@all".to_string()],
+            keys: vec![],
+            channels: vec![],
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn nopass(&self) -> bool {
+        self.nopass
+    }
+
+    /// Applies an ACL rule (`on`, `>password`, `~pattern`, `+@category`, ...) to the user.
+    pub fn apply(&mut self, rule: &str) -> Result<(), RedisError> {
+        match &rule.to_ascii_lowercase()[..] {
+            "on" => self.enabled = true,
+            "off" => self.enabled = false,
+            "nopass" => {
+                self.nopass = true;
+                self.passwords.clear();
+            }
+            "resetpass" => {
+                self.nopass = false;
+                self.passwords.clear();
+            }
+            "allkeys" => self.keys = vec!["*".to_string()],
+            "resetkeys" => self.keys.clear(),
+            "allchannels" => self.channels = vec!["*".to_string()],
+            "resetchannels" => self.channels.clear(),
+            "allcommands" => self.apply("+@all")?,
+            "nocommands" => self.apply("-@all")?,
+            "reset" => {
+                for rule in ["resetpass", "resetkeys", "resetchannels", "off", "-@all"].iter() {
+                    self.apply(rule)?;
+                }
+            }
+            _ => self.apply_with_argument(rule)?,
+        }
+        Ok(())
+    }
+
+    fn apply_with_argument(&mut self, rule: &str) -> Result<(), RedisError> {
+        let mut chars = rule.chars();
+        let prefix = chars.next().ok_or(RedisError::Syntax)?;
+        let argument = chars.as_str();
+        match prefix {
+            '>' => {
+                self.passwords.insert(hash(argument));
+                self.nopass = false;
+            }
+            '<' => {
+                self.passwords.remove(&hash(argument));
+            }
+            '#' if is_hash(argument) => {
+                self.passwords.insert(argument.to_ascii_lowercase());
+                self.nopass = false;
+            }
+            '!' if is_hash(argument) => {
+                self.passwords.remove(&argument.to_ascii_lowercase());
+            }
+            '#' | '!' => return Err(RedisError::Generic("The password hash must be exactly 64 characters and contain only lowercase hexadecimal characters")),
+            '~' => self.keys.push(argument.to_string()),
+            '&' => self.channels.push(argument.to_string()),
+            '+' | '-' => self.apply_command_rule(prefix == '+', &argument.to_ascii_lowercase())?,
+            _ => return Err(RedisError::Syntax),
+        }
+        Ok(())
+    }
+
+    fn apply_command_rule(&mut self, allow: bool, argument: &str) -> Result<(), RedisError> {
+        let commands: Vec<String> = match argument.strip_prefix('@') {
+            Some(category) => categories::commands_in(category)
+                .ok_or(RedisError::Generic("Unknown category"))?
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+            None if categories::is_command(argument) => vec![argument.to_string()],
+            None => return Err(RedisError::Generic("Unknown command")),
+        };
+
+        for command in commands {
+            if allow {
+                self.commands.insert(command);
+            } else {
+                self.commands.remove(&command);
+            }
+        }
+
+        let rule = format!("{}{}", if allow { '+' } else { '-' }, argument);
+        if argument == "@all" {
+            self.command_rules = vec![rule];
+        } else {
+            self.command_rules.push(rule);
+        }
+        Ok(())
+    }
+
+    /// Returns if the password is accepted for the user.
+    pub fn check_password(&self, password: &str) -> bool {
+        self.enabled && (self.nopass || self.passwords.contains(&hash(password)))
+    }
+
+    /// Returns if the user can run the command.
+    pub fn can_run(&self, command: &str) -> bool {
+        self.commands.contains(command)
+    }
+
+    /// Returns if the user can access the key.
+    pub fn can_access_key(&self, key: &str) -> bool {
+        self.keys.iter().any(|pattern| glob_match(pattern, key))
+    }
+
+    /// Returns if the user can access every key, like with the `allkeys` rule.
+    pub fn can_access_all_keys(&self) -> bool {
+        self.keys.iter().any(|pattern| pattern == "*")
+    }
+
+    /// Returns if the user can access the pub/sub channel.
+    pub fn can_access_channel(&self, channel: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|pattern| glob_match(pattern, channel))
+    }
+
+    /// Returns the flags of the user, as shown by ACL GETUSER.
+    pub fn flags(&self) -> Vec<String> {
+        let mut flags = vec![if self.enabled { "on" } else { "off" }.to_string()];
+        if self.nopass {
+            flags.push("nopass".to_string());
+        }
+        flags
+    }
+
+    /// Returns the SHA256 of the passwords of the user.
+    pub fn passwords(&self) -> Vec<String> {
+        self.passwords.iter().cloned().collect()
+    }
+
+    /// Returns the command rules of the user, like `+@all -flushdb`.
+    pub fn command_rules(&self) -> String {
+        self.command_rules.join(" ")
+    }
+
+    /// Returns the key patterns of the user, like `~cache:* ~session:*`.
+    pub fn key_rules(&self) -> String {
+        self.keys
+            .iter()
+            .map(|x| format!("~{}", x))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the channel patterns of the user, like `&news.*`.
+    pub fn channel_rules(&self) -> String {
+        self.channels
+            .iter()
+            .map(|x| format!("&{}", x))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Describes the user with the rules needed to create it again,
+    /// in the format used by ACL LIST and the ACL file.
+    pub fn describe(&self) -> String {
+        let mut rules = vec![format!("user {}", self.name)];
+        rules.extend(self.flags());
+        rules.extend(self.passwords.iter().map(|x| format!("#{}", x)));
+        rules.push(if self.keys.is_empty() {
+            "resetkeys".to_string()
+        } else {
+            self.key_rules()
+        });
+        rules.push(if self.channels.is_empty() {
+            "resetchannels".to_string()
+        } else {
+            self.channel_rules()
+        });
+        rules.push(self.command_rules());
+        rules.join(" ")
+    }
+}
+
+/// SHA256 of the password in hexadecimal, the way redis stores ACL passwords.
+fn hash(password: &str) -> String {
+    Sha256::digest(password.as_bytes())
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect()
+}
+
+fn is_hash(argument: &str) -> bool {
+    argument.len() == 64 && argument.chars().all(|x| x.is_ascii_hexdigit())
+}
+
+/// Matches a glob style pattern against the value, like redis does:
+/// `*` matches any characters, `?` a single one, `[abc]`, `[^abc]` and `[a-z]`
+/// match a single character of a class and `\` escapes the next character.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    let (mut p, mut v) = (0, 0);
+    // Position of the pattern after the last `*` and of the value it was tried at,
+    // to retry with one more character consumed by the `*` when a match fails
+    let mut star = None;
+    while v < value.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, v));
+            continue;
+        }
+        if let Some(next) = match_char(&pattern, p, value[v]) {
+            p = next;
+            v += 1;
+            continue;
+        }
+        match star {
+            Some((star_p, star_v)) => {
+                p = star_p;
+                v = star_v + 1;
+                star = Some((star_p, v));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|x| *x == '*')
+}
+
+/// Matches the character against the element of the pattern at `p`, other than `*`.
+/// Returns the position of the next element of the pattern if it matches.
+fn match_char(pattern: &[char], p: usize, value: char) -> Option<usize> {
+    match pattern.get(p)? {
+        '?' => Some(p + 1),
+        '\\' if p + 1 < pattern.len() => (pattern[p + 1] == value).then(|| p + 2),
+        '[' => {
+            let mut i = p + 1;
+            let negate = pattern.get(i) == Some(&'^');
+            if negate {
+                i += 1;
+            }
+            let mut matched = false;
+            // A class without its closing `]` ends with the pattern
+            while i < pattern.len() && pattern[i] != ']' {
+                if pattern[i] == '\\' && i + 1 < pattern.len() {
+                    i += 1;
+                    matched |= pattern[i] == value;
+                } else if i + 2 < pattern.len() && pattern[i + 1] == '-' && pattern[i + 2] != ']' {
+                    let (start, end) = if pattern[i] <= pattern[i + 2] {
+                        (pattern[i], pattern[i + 2])
+                    } else {
+                        (pattern[i + 2], pattern[i])
+                    };
+                    matched |= start <= value && value <= end;
+                    i += 2;
+                } else {
+                    matched |= pattern[i] == value;
+                }
+                i += 1;
+            }
+            (matched != negate).then(|| (i + 1).min(pattern.len()))
+        }
+        x => (*x == value).then(|| p + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(rules: &[&str]) -> User {
+        let mut user = User::new("alice");
+        for rule in rules {
+            user.apply(rule).unwrap();
+        }
+        user
+    }
+
+    #[test]
+    fn test_password() {
+        let user = user(&["on", ">secreto"]);
+        assert!(user.check_password("secreto"));
+        assert!(!user.check_password("otro"));
+        assert_eq!(user.passwords()[0].len(), 64);
+
+        let disabled = self::user(&[">secreto"]);
+        assert!(!disabled.check_password("secreto"));
+    }
+
+    #[test]
+    fn test_command_rules() {
+        let user = user(&["+@read", "-keys", "+set"]);
+        assert!(user.can_run("get"));
+        assert!(user.can_run("set"));
+        assert!(!user.can_run("keys"));
+        assert!(!user.can_run("del"));
+        assert_eq!(user.command_rules(), "-@all +@read -keys +set");
+
+        let mut unknown = User::new("bob");
+        assert!(unknown.apply("+foo").is_err());
+        assert!(unknown.apply("+@foo").is_err());
+    }
+
+    #[test]
+    fn test_key_and_channel_patterns() {
+        let user = user(&["~cache:*", "&news.?"]);
+        assert!(user.can_access_key("cache:1"));
+        assert!(!user.can_access_key("session:1"));
+        assert!(user.can_access_channel("news.a"));
+        assert!(!user.can_access_channel("news.ab"));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("cache:*", "cache:"));
+        assert!(glob_match("*:*:end", "a:b:c:end"));
+        assert!(!glob_match("*:end", "a:endx"));
+        assert!(glob_match("h?llo", "hello"));
+        assert!(!glob_match("h?llo", "hllo"));
+        assert!(glob_match("h[ae]llo", "hallo"));
+        assert!(!glob_match("h[ae]llo", "hillo"));
+        assert!(glob_match("h[^e]llo", "hallo"));
+        assert!(!glob_match("h[^e]llo", "hello"));
+        assert!(glob_match("key[0-9]", "key5"));
+        assert!(glob_match("key[9-0]", "key5"));
+        assert!(!glob_match("key[0-9]", "keya"));
+        assert!(glob_match("key[a-]", "key-"));
+        assert!(glob_match("a\\*b", "a*b"));
+        assert!(!glob_match("a\\*b", "axb"));
+        assert!(glob_match("[\\]]", "]"));
+        assert!(glob_match("key[ab", "keyb"));
+        assert!(glob_match("ñ?", "ñu"));
+    }
+
+    #[test]
+    fn test_describe() {
+        let user = user(&["on", "nopass", "allkeys", "allchannels", "allcommands"]);
+        assert_eq!(user.describe(), "user alice on nopass ~* &* +@all");
+    }
+}
@@ -0,0 +1,295 @@
+use crate::acl::categories;
+use crate::acl::user::User;
+use crate::error::RedisError;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::RwLock;
+
+pub const DEFAULT_USER: &str = "default";
+
+/// Struct Acl. Holds every ACL user, indexed by its name.
+pub struct Acl {
+    users: RwLock<HashMap<String, User>>,
+}
+
+/// Implementation of the Acl structure.
+#[allow(clippy::new_without_default)]
+impl Acl {
+    /// Create the Acl structure with only the default user, which can run every command
+    /// without a password.
+    pub fn new() -> Self {
+        let mut users = HashMap::new();
+        users.insert(DEFAULT_USER.to_string(), default_user());
+        Acl {
+            users: RwLock::new(users),
+        }
+    }
+
+    /// Sets the password of the default user. With None the default user needs no password.
+    pub fn set_requirepass(&self, requirepass: Option<&String>) -> Result<(), RedisError> {
+        let rules = match requirepass {
+            Some(password) => vec!["resetpass".to_string(), format!(">{}", password)],
+            None => vec!["nopass".to_string()],
+        };
+        self.set_user(DEFAULT_USER, &rules)
+    }
+
+    /// Creates the user if it does not exist and applies the rules to it.
+    /// If any rule is invalid the user is left untouched.
+    pub fn set_user(&self, name: &str, rules: &[String]) -> Result<(), RedisError> {
+        let mut lock = self
+            .users
+            .write()
+            .ok()
+            .ok_or(RedisError::Generic("Failed to lock users"))?;
+        let mut user = lock.get(name).cloned().unwrap_or_else(|| User::new(name));
+        for rule in rules {
+            user.apply(rule)?;
+        }
+        lock.insert(name.to_string(), user);
+        Ok(())
+    }
+
+    /// Returns the user with the given name.
+    pub fn get_user(&self, name: &str) -> Result<Option<User>, RedisError> {
+        let lock = self
+            .users
+            .read()
+            .ok()
+            .ok_or(RedisError::Generic("Failed to lock users"))?;
+        Ok(lock.get(name).cloned())
+    }
+
+    /// Deletes the users and returns how many of them existed.
+    pub fn delete_users(&self, names: &[String]) -> Result<usize, RedisError> {
+        if names.iter().any(|x| x == DEFAULT_USER) {
+            return Err(RedisError::Generic("The 'default' user cannot be removed"));
+        }
+        let mut lock = self
+            .users
+            .write()
+            .ok()
+            .ok_or(RedisError::Generic("Failed to lock users"))?;
+        Ok(names.iter().filter(|x| lock.remove(*x).is_some()).count())
+    }
+
+    /// Returns every user, sorted by name.
+    pub fn users(&self) -> Result<Vec<User>, RedisError> {
+        let lock = self
+            .users
+            .read()
+            .ok()
+            .ok_or(RedisError::Generic("Failed to lock users"))?;
+        let mut users: Vec<User> = lock.values().cloned().collect();
+        users.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(users)
+    }
+
+    /// Returns if the user exists and accepts the password.
+    pub fn authenticate(&self, name: &str, password: &str) -> bool {
+        match self.get_user(name) {
+            Ok(Some(user)) => user.check_password(password),
+            _ => false,
+        }
+    }
+
+    /// Returns if new connections are authenticated as the default user without calling AUTH.
+    pub fn default_user_nopass(&self) -> bool {
+        match self.get_user(DEFAULT_USER) {
+            Ok(Some(user)) => user.enabled() && user.nopass(),
+            _ => false,
+        }
+    }
+
+    /// Checks that the user can run the command and access every key and channel in its arguments.
+    pub fn check(&self, name: &str, command: &str, arguments: &[String]) -> Result<(), RedisError> {
+        let user = self
+            .get_user(name)?
+            .ok_or(RedisError::NoPerm("this user has been deleted"))?;
+        if !user.can_run(command) {
+            return Err(RedisError::NoPerm(
+                "this user has no permissions to run this command",
+            ));
+        }
+        if !categories::keys(command, arguments)
+            .iter()
+            .all(|key| user.can_access_key(key))
+        {
+            return Err(RedisError::NoPerm(
+                "this user has no permissions to access one of the keys used as arguments",
+            ));
+        }
+        // Like redis, the patterns of SORT could read any key, so they need access to all of them
+        if command == "sort"
+            && !user.can_access_all_keys()
+            && !categories::sort_patterns(arguments).is_empty()
+        {
+            return Err(RedisError::NoPerm(
+                "this user has no permissions to use BY or GET patterns of SORT",
+            ));
+        }
+        if !categories::channels(command, arguments)
+            .iter()
+            .all(|channel| user.can_access_channel(channel))
+        {
+            return Err(RedisError::NoPerm(
+                "this user has no permissions to access one of the channels used as arguments",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replaces the users with the ones described in the ACL file.
+    /// Each line of the file has the format `user <name> <rule> <rule> ...`.
+    /// If the file is invalid the current users are kept, and if it does not describe
+    /// the default user the current one is kept, along with its password.
+    pub fn load(&self, path: &str) -> Result<(), RedisError> {
+        let file = fs::read_to_string(path)
+            .ok()
+            .ok_or(RedisError::Generic("Error loading the ACL file"))?;
+
+        let mut users = HashMap::new();
+        for line in file.lines().filter(|x| !x.trim().is_empty()) {
+            let mut words = line.split_whitespace();
+            if words.next() != Some("user") {
+                return Err(RedisError::Generic("Error loading the ACL file"));
+            }
+            let name = words
+                .next()
+                .ok_or(RedisError::Generic("Error loading the ACL file"))?;
+            let mut user = User::new(name);
+            for rule in words {
+                user.apply(rule)?;
+            }
+            users.insert(name.to_string(), user);
+        }
+        let mut lock = self
+            .users
+            .write()
+            .ok()
+            .ok_or(RedisError::Generic("Failed to lock users"))?;
+        if !users.contains_key(DEFAULT_USER) {
+            let default = lock.get(DEFAULT_USER).cloned().unwrap_or_else(default_user);
+            users.insert(DEFAULT_USER.to_string(), default);
+        }
+        *lock = users;
+        Ok(())
+    }
+
+    /// Writes every user into the ACL file, in the format read by load.
+    pub fn save(&self, path: &str) -> Result<(), RedisError> {
+        let contents: String = self
+            .users()?
+            .iter()
+            .map(|x| format!("{}\n", x.describe()))
+            .collect();
+        fs::write(path, contents)
+            .ok()
+            .ok_or(RedisError::Generic("Error saving the ACL file"))
+    }
+}
+
+fn default_user() -> User {
+    let mut user = User::new(DEFAULT_USER);
+    for rule in ["on", "nopass", "allkeys", "allchannels", "allcommands"].iter() {
+        user.apply(rule).unwrap();
+    }
+    user
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_user() {
+        let acl = Acl::new();
+        assert!(acl.default_user_nopass());
+        assert!(acl.check(DEFAULT_USER, "flushdb", &[]).is_ok());
+
+        acl.set_requirepass(Some(&"secreto".to_string())).unwrap();
+        assert!(!acl.default_user_nopass());
+        assert!(acl.authenticate(DEFAULT_USER, "secreto"));
+        assert!(acl.delete_users(&rules(&[DEFAULT_USER])).is_err());
+    }
+
+    #[test]
+    fn test_check_permissions() {
+        let acl = Acl::new();
+        acl.set_user(
+            "alice",
+            &rules(&["on", ">pass", "~cache:*", "&news", "+@read", "+publish"]),
+        )
+        .unwrap();
+
+        assert!(acl.check("alice", "get", &rules(&["cache:1"])).is_ok());
+        assert!(acl.check("alice", "get", &rules(&["other"])).is_err());
+        assert!(acl
+            .check("alice", "set", &rules(&["cache:1", "v"]))
+            .is_err());
+        assert!(acl
+            .check("alice", "publish", &rules(&["news", "m"]))
+            .is_ok());
+        assert!(acl
+            .check("alice", "publish", &rules(&["sports", "m"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_sort_patterns_need_all_keys() {
+        let acl = Acl::new();
+        acl.set_user("alice", &rules(&["on", ">pass", "~public:*", "+sort"]))
+            .unwrap();
+        acl.set_user("bob", &rules(&["on", ">pass", "allkeys", "+sort"]))
+            .unwrap();
+
+        let sort = |user: &str, values: &[&str]| acl.check(user, "sort", &rules(values));
+        assert!(sort("alice", &["public:list", "BY", "nosort", "GET", "#"]).is_ok());
+        assert!(sort("alice", &["public:list", "BY", "nosort", "GET", "secret:*"]).is_err());
+        assert!(sort("alice", &["public:list", "BY", "public:*"]).is_err());
+        assert!(sort("bob", &["list", "BY", "weight_*", "GET", "secret:*"]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_rule_keeps_user() {
+        let acl = Acl::new();
+        acl.set_user("alice", &rules(&["on", "+get"])).unwrap();
+        assert!(acl.set_user("alice", &rules(&["+set", "+foo"])).is_err());
+        assert!(!acl.get_user("alice").unwrap().unwrap().can_run("set"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = "test_files/acl_save_and_load.acl";
+        let acl = Acl::new();
+        acl.set_user("alice", &rules(&["on", ">pass", "~*", "+get"]))
+            .unwrap();
+        acl.save(path).unwrap();
+
+        let loaded = Acl::new();
+        loaded.load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(loaded.authenticate("alice", "pass"));
+        assert!(loaded.check("alice", "get", &rules(&["key"])).is_ok());
+        assert!(loaded.check("alice", "set", &rules(&["key"])).is_err());
+        assert!(loaded.default_user_nopass());
+    }
+
+    #[test]
+    fn test_load_keeps_requirepass_of_default_user() {
+        let path = "test_files/acl_load_keeps_requirepass.acl";
+        std::fs::write(path, "user alice on >pass ~* +get\n").unwrap();
+        let acl = Acl::new();
+        acl.set_requirepass(Some(&"secreto".to_string())).unwrap();
+
+        acl.load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(!acl.default_user_nopass());
+        assert!(acl.authenticate(DEFAULT_USER, "secreto"));
+        assert!(acl.authenticate("alice", "pass"));
+    }
+}
@@ -1,33 +1,232 @@
+use mio::unix::SourceFd;
+use mio::{Events, Interest, Poll, Token};
 use redis_protocol::command::Command;
 use redis_protocol::request::Request;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
 use std::hash::{Hash, Hasher};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{ErrorKind, Read, Write};
 use std::net::{Shutdown, TcpStream};
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 use std::sync::Mutex;
 
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 static CLIENT_ID: AtomicU64 = AtomicU64::new(0);
 
-/// A represents Redis client. Wraps a TCP socket and some state.
+/// Size of the chunks read from the socket
+const READ_CHUNK: usize = 4096;
+/// Time to wait for a slow client to accept more output before giving up
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A represents Redis client. Wraps a non blocking TCP socket and some state.
 pub struct Client {
     write_socket: Mutex<TcpStream>,
     read_socket: Mutex<TcpStream>,
+    input: Mutex<Request>,
+    busy: AtomicBool,
     in_pubsub: AtomicBool,
+    in_monitor: AtomicBool,
+    in_replica: AtomicBool,
+    asking: AtomicBool,
+    protocol: AtomicU8,
+    user: Mutex<Option<String>>,
     closed: AtomicBool,
     id: u64,
+    addr: String,
+    laddr: String,
+    name: Mutex<Option<String>>,
+    created: SystemTime,
+    last_interaction: Mutex<Instant>,
+    last_command: Mutex<String>,
+    postponed: Mutex<(Vec<Command>, Option<String>)>,
 }
 
 impl Client {
-    /// Returns a new client from a socket
+    /// Returns a new client from a socket. The socket is set in non blocking mode.
     pub fn new(socket: TcpStream) -> Self {
+        let now = SystemTime::now();
+        if socket.set_nonblocking(true).is_err() {
+            println!("Failed to set the client socket as non blocking");
+        }
         Client {
+            addr: socket
+                .peer_addr()
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
+            laddr: socket
+                .local_addr()
+                .map(|x| x.to_string())
+                .unwrap_or_default(),
             read_socket: Mutex::new(socket.try_clone().unwrap()),
             write_socket: Mutex::new(socket),
+            input: Mutex::new(Request::new()),
+            busy: AtomicBool::new(false),
             in_pubsub: AtomicBool::new(false),
+            in_monitor: AtomicBool::new(false),
+            in_replica: AtomicBool::new(false),
+            asking: AtomicBool::new(false),
+            protocol: AtomicU8::new(2),
+            user: Mutex::new(None),
             closed: AtomicBool::new(false),
             id: CLIENT_ID.fetch_add(1, Ordering::SeqCst),
+            name: Mutex::new(None),
+            created: now,
+            last_interaction: Mutex::new(Instant::now()),
+            last_command: Mutex::new("NULL".to_string()),
+            postponed: Mutex::new((Vec::new(), None)),
+        }
+    }
+
+    /// Returns the unique id of the connection
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the file descriptor of the socket, used to register it in the event loop
+    pub fn raw_fd(&self) -> RawFd {
+        self.write_socket.lock().unwrap().as_raw_fd()
+    }
+
+    /// Marks the client as being served by a worker.
+    /// Returns false if another worker is already serving it.
+    pub fn try_start_serving(&self) -> bool {
+        self.busy
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    /// Marks the client as no longer being served by a worker
+    pub fn finish_serving(&self) {
+        self.busy.store(false, Ordering::SeqCst);
+    }
+
+    /// Returns the time since the client sent its last command
+    pub fn idle(&self) -> Duration {
+        self.last_interaction.lock().unwrap().elapsed()
+    }
+
+    /// Returns the address of the remote end of the connection
+    pub fn addr(&self) -> &str {
+        &self.addr
+    }
+
+    /// Returns the name set with CLIENT SETNAME, if any
+    pub fn name(&self) -> Option<String> {
+        self.name.lock().unwrap().clone()
+    }
+
+    /// Sets the name of the connection. An empty name removes it.
+    pub fn set_name(&self, name: &str) {
+        let mut lock = self.name.lock().unwrap();
+        *lock = if name.is_empty() {
+            None
+        } else {
+            Some(name.to_string())
+        };
+    }
+
+    /// Registers the command the client is about to execute
+    pub fn register_command(&self, command: &str) {
+        *self.last_command.lock().unwrap() = command.to_ascii_lowercase();
+        *self.last_interaction.lock().unwrap() = Instant::now();
+    }
+
+    /// Gets if its in monitor mode
+    pub fn in_monitor_mode(&self) -> bool {
+        self.in_monitor.load(Ordering::SeqCst)
+    }
+
+    /// Sets the client in monitor mode
+    pub fn set_monitor_mode(&self, new: bool) {
+        self.in_monitor.store(new, Ordering::SeqCst);
+    }
+
+    /// Gets if the connection is a replica receiving the replication stream
+    pub fn in_replica_mode(&self) -> bool {
+        self.in_replica.load(Ordering::SeqCst)
+    }
+
+    /// Sets the connection as a replica
+    pub fn set_replica_mode(&self, new: bool) {
+        self.in_replica.store(new, Ordering::SeqCst);
+    }
+
+    /// Lets the next command of the client use a slot this cluster node is importing
+    pub fn set_asking(&self) {
+        self.asking.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns if the client sent ASKING before this command, clearing the flag
+    pub fn take_asking(&self) -> bool {
+        self.asking.swap(false, Ordering::SeqCst)
+    }
+
+    /// Returns the ACL user the client authenticated as with AUTH, if any
+    pub fn user(&self) -> Option<String> {
+        self.user.lock().unwrap().clone()
+    }
+
+    /// Sets the ACL user of the client
+    pub fn set_user(&self, name: &str) {
+        *self.user.lock().unwrap() = Some(name.to_string());
+    }
+
+    /// Returns the RESP version spoken by the client, 2 unless it was changed with HELLO
+    pub fn protocol(&self) -> u8 {
+        self.protocol.load(Ordering::SeqCst)
+    }
+
+    /// Sets the RESP version spoken by the client
+    pub fn set_protocol(&self, protocol: u8) {
+        self.protocol.store(protocol, Ordering::SeqCst);
+    }
+
+    /// Returns a line describing the connection, in the format used by CLIENT LIST and CLIENT INFO
+    pub fn info(&self) -> String {
+        let flags = if self.in_monitor_mode() {
+            "O"
+        } else if self.in_replica_mode() {
+            "S"
+        } else if self.in_pubsub_mode() {
+            "P"
+        } else {
+            "N"
+        };
+        format!(
+            "id={} addr={} laddr={} name={} age={} idle={} flags={} db=0 cmd={} user={}",
+            self.id,
+            self.addr,
+            self.laddr,
+            self.name().unwrap_or_default(),
+            elapsed_secs(self.created),
+            self.idle().as_secs(),
+            flags,
+            self.last_command.lock().unwrap(),
+            self.user().unwrap_or_else(|| "default".to_string())
+        )
+    }
+
+    /// Closes the connection. Responses still pending for the client are sent
+    /// but no more commands are read from it.
+    pub fn kill(&self) -> Result<(), &'static str> {
+        self.closed.store(true, Ordering::SeqCst);
+        let lock = self
+            .write_socket
+            .lock()
+            .ok()
+            .ok_or("Failed to lock socket")?;
+        lock.shutdown(Shutdown::Read)
+            .ok()
+            .ok_or("Error while closing the connection")
+    }
+
+    /// Closes both directions of the connection once the client is disconnected
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        if let Ok(lock) = self.write_socket.lock() {
+            let _ = lock.shutdown(Shutdown::Both);
         }
     }
 
@@ -46,80 +245,117 @@ impl Client {
         self.closed.load(Ordering::SeqCst)
     }
 
-    /// Send a string message to this client
+    /// Send a string message to this client.
+    /// If the socket buffer is full it waits until the client reads enough of it.
     pub fn send(&self, msg: &str) -> Result<(), &'static str> {
         let mut lock = self
             .write_socket
             .lock()
             .ok()
             .ok_or("Failed to lock socket")?;
-        lock.write_all(msg.as_bytes())
-            .ok()
-            .ok_or("Error while writing to client")?;
+        let mut pending = msg.as_bytes();
+        while !pending.is_empty() {
+            match lock.write(pending) {
+                Ok(0) => return Err("Error while writing to client"),
+                Ok(written) => pending = &pending[written..],
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == ErrorKind::WouldBlock => wait_writable(lock.as_raw_fd())?,
+                Err(_) => return Err("Error while writing to client"),
+            }
+        }
         Ok(())
     }
 
-    /// Parses a command from a socket connection
-    pub fn parse_commands(&self, timeout: u64) -> Result<Vec<Command>, String> {
-        let locked_socket = self
-            .read_socket
-            .lock()
-            .ok()
-            .ok_or_else(|| "Failed to lock socket".to_string())?;
-        let mut request = Request::new();
-        let mut commands = Vec::new();
-        let copy = locked_socket.try_clone().unwrap();
-        copy.set_read_timeout(Some(Duration::from_millis(10)))
-            .unwrap();
-        let mut reader = BufReader::new(copy);
-        let mut line = String::new();
-        let mut offset = 0;
-        let now = SystemTime::now();
+    /// Sends a response, serialized with the RESP version of the client
+    pub fn send_response(&self, response: &ResponseBuilder) -> Result<(), &'static str> {
+        if self.protocol() == 3 {
+            self.send(&response.serialize_resp3())
+        } else {
+            self.send(&response.serialize())
+        }
+    }
+
+    /// Sends a single value, serialized with the RESP version of the client
+    pub fn send_value(&self, value: &ProtocolType) -> Result<(), &'static str> {
+        if self.protocol() == 3 {
+            self.send(&value.serialize_resp3())
+        } else {
+            self.send(&value.serialize())
+        }
+    }
+
+    /// Keeps commands that can not run yet, because of CLIENT PAUSE, and the protocol
+    /// error found after them. They are returned first by the next read_commands.
+    pub fn postpone(&self, commands: Vec<Command>, error: Option<String>) {
+        *self.postponed.lock().unwrap() = (commands, error);
+    }
+
+    /// Reads everything available in the socket without blocking and returns the
+    /// commands completed by it, after the postponed ones. Incomplete commands are kept
+    /// until more data arrives. If the client closed the connection it is marked as closed.
+    /// A malformed request stops the decoding: the commands before it are returned
+    /// together with the protocol error. A killed client returns no commands, since its
+    /// socket may still receive data after being shut down.
+    pub fn read_commands(&self) -> (Vec<Command>, Option<String>) {
+        if self.is_closed() {
+            return (Vec::new(), None);
+        }
+        let (mut commands, error) = std::mem::take(&mut *self.postponed.lock().unwrap());
+        if error.is_some() {
+            return (commands, error);
+        }
+        let (mut socket, mut input) = match (self.read_socket.lock(), self.input.lock()) {
+            (Ok(socket), Ok(input)) => (socket, input),
+            _ => return (commands, Some("Failed to lock socket".to_string())),
+        };
+        let mut chunk = [0; READ_CHUNK];
         loop {
-            let read_result = reader.read_line(&mut line);
-            match read_result {
-                Ok(s) => {
-                    if s == 0 {
-                        self.closed.store(true, Ordering::SeqCst);
-                        return Err("Client closed the connection".to_string());
-                    } else {
-                        let l = &line[offset..offset + s];
-                        offset += s;
-                        let result = request.feed(l);
-                        if let Ok(val) = result {
-                            if val {
-                                commands.push(request.build());
-                                request = Request::new();
-                            } else {
-                            }
-                        } else if let Err(e) = result {
-                            return Err(e);
-                        }
-                    }
+            match socket.read(&mut chunk) {
+                Ok(0) => {
+                    self.closed.store(true, Ordering::SeqCst);
+                    break;
                 }
+                Ok(read) => input.feed(&chunk[..read]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
                 Err(_) => {
-                    if !commands.is_empty() {
-                        break;
-                    }
+                    self.closed.store(true, Ordering::SeqCst);
+                    break;
                 }
             }
-            let new_now = SystemTime::now();
-            let difference = new_now.duration_since(now);
-            let result = difference.unwrap();
-            if timeout != 0
-                && result.as_secs() > timeout
-                && commands.is_empty()
-                && !self.in_pubsub_mode()
-            {
-                self.closed.store(true, Ordering::SeqCst);
-                locked_socket.shutdown(Shutdown::Both).unwrap();
-                break;
+        }
+
+        loop {
+            match input.next_command() {
+                Ok(Some(command)) => commands.push(command),
+                Ok(None) => return (commands, None),
+                Err(e) => return (commands, Some(e)),
             }
         }
-        Ok(commands)
     }
 }
 
+/// Blocks until the socket accepts more data or the write timeout expires
+fn wait_writable(fd: RawFd) -> Result<(), &'static str> {
+    let mut poll = Poll::new().ok().ok_or("Failed to wait for the client")?;
+    poll.registry()
+        .register(&mut SourceFd(&fd), Token(0), Interest::WRITABLE)
+        .ok()
+        .ok_or("Failed to wait for the client")?;
+    let mut events = Events::with_capacity(1);
+    poll.poll(&mut events, Some(WRITE_TIMEOUT))
+        .ok()
+        .ok_or("Failed to wait for the client")?;
+    if events.is_empty() {
+        return Err("Timeout while writing to client");
+    }
+    Ok(())
+}
+
+fn elapsed_secs(time: SystemTime) -> u64 {
+    time.elapsed().map(|x| x.as_secs()).unwrap_or(0)
+}
+
 impl PartialEq for Client {
     fn eq(&self, other: &Self) -> bool {
         self.id.eq(&other.id)
@@ -0,0 +1,178 @@
+use crate::client::Client;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+/// Struct ClientRegistry. Holds every client connected to the server,
+/// indexed by its id, and the state of CLIENT PAUSE.
+pub struct ClientRegistry {
+    clients: RwLock<HashMap<u64, Arc<Client>>>,
+    paused: Mutex<Option<Pause>>,
+}
+
+/// A pause started with CLIENT PAUSE. Only write commands are paused unless `all` is set.
+struct Pause {
+    until: SystemTime,
+    all: bool,
+}
+
+/// Implementation of the ClientRegistry structure.
+#[allow(clippy::new_without_default)]
+impl ClientRegistry {
+    /// Create the ClientRegistry structure. Initially with no clients.
+    pub fn new() -> Self {
+        ClientRegistry {
+            clients: RwLock::new(HashMap::new()),
+            paused: Mutex::new(None),
+        }
+    }
+
+    /// Registers a new connected client
+    pub fn add(&self, client: Arc<Client>) -> Result<(), &'static str> {
+        let mut lock = self.clients.write().ok().ok_or("Failed to lock clients")?;
+        lock.insert(client.id(), client);
+        Ok(())
+    }
+
+    /// Removes a client once its connection is closed
+    pub fn remove(&self, id: u64) -> Result<(), &'static str> {
+        let mut lock = self.clients.write().ok().ok_or("Failed to lock clients")?;
+        lock.remove(&id);
+        Ok(())
+    }
+
+    /// Returns the client with the given id, if it is connected
+    pub fn get(&self, id: u64) -> Option<Arc<Client>> {
+        let lock = self.clients.read().ok()?;
+        lock.get(&id).cloned()
+    }
+
+    /// Returns every connected client, sorted by id
+    pub fn list(&self) -> Result<Vec<Arc<Client>>, &'static str> {
+        let lock = self.clients.read().ok().ok_or("Failed to lock clients")?;
+        let mut clients: Vec<Arc<Client>> = lock.values().cloned().collect();
+        clients.sort_by_key(|x| x.id());
+        Ok(clients)
+    }
+
+    /// Closes the connection of every client matching the filter.
+    /// Returns the number of clients killed.
+    pub fn kill<F>(&self, filter: F) -> Result<usize, &'static str>
+    where
+        F: Fn(&Client) -> bool,
+    {
+        let mut killed = 0;
+        for client in self.list()? {
+            if !client.is_closed() && filter(&client) {
+                client.kill()?;
+                killed += 1;
+            }
+        }
+        Ok(killed)
+    }
+
+    /// Pauses the commands of every client for the given duration.
+    /// If `all` is false only write commands are paused.
+    pub fn pause(&self, duration: Duration, all: bool) -> Result<(), &'static str> {
+        let mut lock = self.paused.lock().ok().ok_or("Failed to lock clients")?;
+        *lock = Some(Pause {
+            until: SystemTime::now() + duration,
+            all,
+        });
+        Ok(())
+    }
+
+    /// Stops a pause started with pause
+    pub fn unpause(&self) -> Result<(), &'static str> {
+        let mut lock = self.paused.lock().ok().ok_or("Failed to lock clients")?;
+        *lock = None;
+        Ok(())
+    }
+
+    /// Returns the end of the pause if commands of the given kind are paused.
+    /// Write commands are paused by any pause.
+    pub fn paused_until(&self, write: bool) -> Option<SystemTime> {
+        let lock = self.paused.lock().unwrap();
+        match &*lock {
+            Some(pause) if (pause.all || write) && SystemTime::now() < pause.until => {
+                Some(pause.until)
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(test)]
+    fn is_paused(&self, write: bool) -> bool {
+        self.paused_until(write).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_client;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_add_and_remove() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let registry = ClientRegistry::new();
+        let (_first_stream, first) = new_client(&listener);
+        let (_second_stream, second) = new_client(&listener);
+        registry.add(second.clone()).unwrap();
+        registry.add(first.clone()).unwrap();
+
+        let ids: Vec<u64> = registry.list().unwrap().iter().map(|x| x.id()).collect();
+        assert_eq!(ids, vec![first.id(), second.id()]);
+
+        assert!(registry.get(first.id()).is_some());
+        registry.remove(first.id()).unwrap();
+        assert_eq!(registry.list().unwrap().len(), 1);
+        assert!(registry.get(first.id()).is_none());
+    }
+
+    #[test]
+    fn test_kill_by_filter() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let registry = ClientRegistry::new();
+        let (_first_stream, first) = new_client(&listener);
+        let (_second_stream, second) = new_client(&listener);
+        registry.add(first.clone()).unwrap();
+        registry.add(second.clone()).unwrap();
+
+        let id = first.id();
+        assert_eq!(registry.kill(|x| x.id() == id).unwrap(), 1);
+        assert!(first.is_closed());
+        assert!(!second.is_closed());
+        assert_eq!(registry.kill(|x| x.id() == id).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_killed_client_runs_no_commands() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (mut stream, client) = new_client(&listener);
+        let registry = ClientRegistry::new();
+        registry.add(client.clone()).unwrap();
+
+        let id = client.id();
+        assert_eq!(registry.kill(|x| x.id() == id).unwrap(), 1);
+        stream.write_all(b"PING\r\n").unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+
+        let (commands, error) = client.read_commands();
+        assert!(commands.is_empty());
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn test_pause_write_only() {
+        let registry = ClientRegistry::new();
+        registry.pause(Duration::from_secs(10), false).unwrap();
+        assert!(registry.is_paused(true));
+        assert!(!registry.is_paused(false));
+
+        registry.unpause().unwrap();
+        assert!(!registry.is_paused(true));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::cluster::Cluster;
+use crate::config::configuration::Configuration;
+use crate::logging::logger::Logger;
+use redis_protocol::client::{ClientError, Connection};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Time between the gossip messages sent to every node
+const GOSSIP_PERIOD: Duration = Duration::from_millis(100);
+/// Longest wait for the answer of a node
+const NODE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends the gossip of this node to the other nodes of the cluster, until the server shuts down.
+/// The gossip is a CLUSTER GOSSIP command sent through the port of the clients of each node,
+/// on a connection kept open while the node answers.
+pub fn run(cluster: Arc<Cluster>, config: Arc<Mutex<Configuration>>, logger: Arc<Logger>) {
+    let mut links: HashMap<String, Connection> = HashMap::new();
+    while !cluster.is_stopped() {
+        let mut command = vec!["CLUSTER".to_string(), "GOSSIP".to_string()];
+        command.extend(cluster.gossip_message());
+        let peers = cluster.peers();
+        links.retain(|id, _| peers.iter().any(|(x, _)| x == id));
+
+        for (id, addr) in peers {
+            let result = match links.remove(&id) {
+                Some(link) => Ok(link),
+                None => connect(&addr, &config),
+            }
+            .and_then(|mut link| {
+                let answer = link.query(&command)?;
+                Ok((link, answer.to_string()))
+            });
+            match result {
+                Ok((link, answered_id)) => {
+                    cluster.pong(&id, &answered_id);
+                    links.insert(answered_id, link);
+                }
+                Err(e) => {
+                    if cluster.disconnected(&id) {
+                        let msg = format!("Connection with node {} lost: {}", id, e);
+                        println!("{}", msg);
+                        let _ = logger.log(&msg);
+                    }
+                }
+            }
+        }
+        thread::sleep(GOSSIP_PERIOD);
+    }
+}
+
+/// Opens a connection with a node, authenticated with `masterauth` if it is set
+fn connect(addr: &str, config: &Mutex<Configuration>) -> Result<Connection, ClientError> {
+    let mut link = Connection::connect(addr)?;
+    link.set_timeout(Some(NODE_TIMEOUT))?;
+    let password = config.lock().unwrap().get_masterauth().cloned();
+    if let Some(password) = password {
+        link.auth(&password)?;
+    }
+    Ok(link)
+}
@@ -0,0 +1,658 @@
+use crate::cluster::slots::{format_range, parse_ranges, ranges, SLOTS};
+use crate::error::RedisError;
+use crate::stats::random_run_id;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::types::ProtocolType;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub mod bus;
+pub mod slots;
+
+/// Time after which a node met with CLUSTER MEET that never answered is forgotten
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub const CLUSTER_DISABLED: RedisError =
+    RedisError::Generic("This instance has cluster support disabled");
+const UNKNOWN_NODE: RedisError = RedisError::Generic("Unknown node");
+const INVALID_GOSSIP: RedisError = RedisError::Generic("Invalid cluster gossip message");
+
+/// A node of the cluster, as known by this server.
+struct Node {
+    ip: String,
+    port: u16,
+    epoch: u64,
+    handshake: bool,
+    connected: bool,
+    met: Instant,
+    ping_sent: u64,
+    pong_received: u64,
+}
+
+impl Node {
+    fn new(ip: &str, port: u16) -> Self {
+        Node {
+            ip: ip.to_string(),
+            port,
+            epoch: 0,
+            handshake: false,
+            connected: false,
+            met: Instant::now(),
+            ping_sent: 0,
+            pong_received: 0,
+        }
+    }
+
+    fn addr(&self) -> String {
+        format!("{}:{}", self.ip, self.port)
+    }
+}
+
+/// The view of the cluster of this server: the known nodes, the owner of each slot
+/// and the slots being migrated from or to this node.
+struct State {
+    myself: String,
+    current_epoch: u64,
+    nodes: HashMap<String, Node>,
+    owners: Vec<Option<String>>,
+    migrating: HashMap<u16, String>,
+    importing: HashMap<u16, String>,
+}
+
+impl State {
+    fn node(&self, id: &str) -> Result<&Node, RedisError> {
+        self.nodes.get(id).ok_or(UNKNOWN_NODE)
+    }
+
+    fn owner(&self, slot: u16) -> Option<&String> {
+        self.owners[slot as usize].as_ref()
+    }
+
+    fn is_mine(&self, slot: u16) -> bool {
+        self.owner(slot) == Some(&self.myself)
+    }
+
+    fn slots_of(&self, id: &str) -> impl Iterator<Item = u16> + '_ {
+        let id = id.to_string();
+        (0..SLOTS).filter(move |x| self.owner(*x) == Some(&id))
+    }
+
+    /// Forgets the nodes still in handshake at the address, which is now known by its id
+    fn forget_handshake(&mut self, ip: &str, port: u16) {
+        self.nodes
+            .retain(|_, x| !(x.handshake && x.ip == ip && x.port == port));
+    }
+
+    /// Updates the owners of the slots with the ones a node claims. A node takes the
+    /// free slots and the slots of nodes with a lower epoch, and releases the slots it no
+    /// longer claims.
+    fn claim(&mut self, id: &str, epoch: u64, claimed: &HashSet<u16>) {
+        for slot in 0..SLOTS {
+            let owner = self.owner(slot).cloned();
+            let takes = claimed.contains(&slot)
+                && match &owner {
+                    None => true,
+                    Some(owner) if owner == id => false,
+                    Some(owner) => self.nodes.get(owner).is_none_or(|x| epoch > x.epoch),
+                };
+            if takes {
+                if owner.as_ref() == Some(&self.myself) {
+                    self.migrating.remove(&slot);
+                }
+                self.owners[slot as usize] = Some(id.to_string());
+            } else if owner.as_deref() == Some(id) && !claimed.contains(&slot) {
+                self.owners[slot as usize] = None;
+            }
+        }
+    }
+}
+
+/// Struct Cluster. Holds the cluster state of a server started with `cluster-enabled`.
+/// The keys are divided in 16384 slots and every node serves the keys of the slots it owns,
+/// redirecting the clients to the owner of the other ones. The nodes share their slots and
+/// the nodes they know through the gossip sent by the `bus` module.
+pub struct Cluster {
+    enabled: bool,
+    stopped: AtomicBool,
+    state: Mutex<State>,
+}
+
+impl Cluster {
+    /// Returns the state of a new node, which owns no slots and only knows itself
+    pub fn new(enabled: bool, ip: &str, port: u16) -> Self {
+        let myself = random_run_id();
+        let mut nodes = HashMap::new();
+        nodes.insert(myself.clone(), Node::new(ip, port));
+        Cluster {
+            enabled,
+            stopped: AtomicBool::new(false),
+            state: Mutex::new(State {
+                myself,
+                current_epoch: 0,
+                nodes,
+                owners: vec![None; SLOTS as usize],
+                migrating: HashMap::new(),
+                importing: HashMap::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns if the server was started in cluster mode
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Fails if the server was not started in cluster mode
+    pub fn check_enabled(&self) -> Result<(), RedisError> {
+        if self.enabled {
+            Ok(())
+        } else {
+            Err(CLUSTER_DISABLED)
+        }
+    }
+
+    /// Stops the gossip with the other nodes
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Returns the id of this node
+    pub fn myself(&self) -> String {
+        self.lock().myself.clone()
+    }
+
+    /// Checks that this node can run a command on the keys, which must belong to the same slot.
+    /// Keys of slots owned by other nodes are redirected with MOVED. While a slot is migrated,
+    /// keys missing from it are redirected with ASK to the node importing it, which accepts
+    /// them from the clients that sent ASKING before the command.
+    pub fn route(
+        &self,
+        keys: &[String],
+        asking: bool,
+        data: &DataStorage,
+    ) -> Result<(), RedisError> {
+        if !self.enabled || keys.is_empty() {
+            return Ok(());
+        }
+        let slot = slots::key_slot(&keys[0]);
+        if keys[1..].iter().any(|x| slots::key_slot(x) != slot) {
+            return Err(RedisError::CrossSlot);
+        }
+
+        let state = self.lock();
+        if asking && state.importing.contains_key(&slot) {
+            return Ok(());
+        }
+        match state.owner(slot) {
+            None => Err(RedisError::ClusterDown),
+            Some(owner) if *owner == state.myself => match state.migrating.get(&slot) {
+                Some(target) => {
+                    let missing = keys.iter().filter(|x| data.peek_entry(x).is_none()).count();
+                    if missing == 0 {
+                        Ok(())
+                    } else if missing == keys.len() {
+                        Err(RedisError::Ask {
+                            slot,
+                            addr: state.node(target)?.addr(),
+                        })
+                    } else {
+                        Err(RedisError::TryAgain)
+                    }
+                }
+                None => Ok(()),
+            },
+            Some(owner) => Err(RedisError::Moved {
+                slot,
+                addr: state.node(owner)?.addr(),
+            }),
+        }
+    }
+
+    /// Assigns free slots to this node
+    pub fn add_slots(&self, slots: &[u16]) -> Result<(), RedisError> {
+        let mut state = self.lock();
+        if slots.iter().any(|x| state.owner(*x).is_some()) {
+            return Err(RedisError::Generic("Slot is already busy"));
+        }
+        for slot in slots {
+            state.owners[*slot as usize] = Some(state.myself.clone());
+            state.importing.remove(slot);
+        }
+        Ok(())
+    }
+
+    /// Releases slots, which are no longer served by any node
+    pub fn del_slots(&self, slots: &[u16]) -> Result<(), RedisError> {
+        let mut state = self.lock();
+        if slots.iter().any(|x| state.owner(*x).is_none()) {
+            return Err(RedisError::Generic("Slot is already unassigned"));
+        }
+        for slot in slots {
+            state.owners[*slot as usize] = None;
+            state.migrating.remove(slot);
+        }
+        Ok(())
+    }
+
+    /// Starts a handshake with the node at the address, unless it is already known.
+    /// Once it answers, each node learns the other one and the nodes it knows.
+    pub fn meet(&self, ip: &str, port: u16) {
+        let mut state = self.lock();
+        if state.nodes.values().any(|x| x.ip == ip && x.port == port) {
+            return;
+        }
+        let mut node = Node::new(ip, port);
+        node.handshake = true;
+        state.nodes.insert(random_run_id(), node);
+    }
+
+    /// Marks a slot as being moved from this node to another one
+    pub fn set_migrating(&self, slot: u16, id: &str) -> Result<(), RedisError> {
+        let mut state = self.lock();
+        if !state.is_mine(slot) {
+            return Err(RedisError::Generic("I'm not the owner of hash slot"));
+        }
+        state.node(id)?;
+        state.migrating.insert(slot, id.to_string());
+        Ok(())
+    }
+
+    /// Marks a slot as being moved from another node to this one
+    pub fn set_importing(&self, slot: u16, id: &str) -> Result<(), RedisError> {
+        let mut state = self.lock();
+        if state.is_mine(slot) {
+            return Err(RedisError::Generic("I'm already the owner of hash slot"));
+        }
+        state.node(id)?;
+        state.importing.insert(slot, id.to_string());
+        Ok(())
+    }
+
+    /// Cancels the migration of a slot
+    pub fn set_stable(&self, slot: u16) {
+        let mut state = self.lock();
+        state.migrating.remove(&slot);
+        state.importing.remove(&slot);
+    }
+
+    /// Assigns a slot to a node, ending its migration. A node taking a slot it was
+    /// importing gets a new epoch, so the other nodes prefer its claim to the one of the
+    /// previous owner.
+    pub fn set_node(&self, slot: u16, id: &str) -> Result<(), RedisError> {
+        let mut state = self.lock();
+        state.node(id)?;
+        if id == state.myself {
+            if state.importing.remove(&slot).is_some() {
+                state.current_epoch += 1;
+                let epoch = state.current_epoch;
+                let myself = state.myself.clone();
+                if let Some(node) = state.nodes.get_mut(&myself) {
+                    node.epoch = epoch;
+                }
+            }
+        } else {
+            state.migrating.remove(&slot);
+        }
+        state.owners[slot as usize] = Some(id.to_string());
+        Ok(())
+    }
+
+    /// Returns the id and address of the other nodes, the ones the gossip is sent to.
+    /// Nodes still in handshake for too long are forgotten.
+    pub fn peers(&self) -> Vec<(String, String)> {
+        let mut state = self.lock();
+        state
+            .nodes
+            .retain(|_, x| !x.handshake || x.met.elapsed() < HANDSHAKE_TIMEOUT);
+        let myself = state.myself.clone();
+        state
+            .nodes
+            .iter_mut()
+            .filter(|(id, _)| **id != myself)
+            .map(|(id, node)| {
+                node.ping_sent = now_millis();
+                (id.clone(), node.addr())
+            })
+            .collect()
+    }
+
+    /// Returns the arguments of the CLUSTER GOSSIP sent to the other nodes: the id, port,
+    /// epoch and slots of this node, the current epoch and the id and address of every
+    /// other node it knows.
+    pub fn gossip_message(&self) -> Vec<String> {
+        let state = self.lock();
+        let myself = state.node(&state.myself).expect("the node knows itself");
+        let mut message = vec![
+            state.myself.clone(),
+            myself.port.to_string(),
+            myself.epoch.to_string(),
+            state.current_epoch.to_string(),
+            slots::format_ranges(&ranges(state.slots_of(&state.myself))),
+        ];
+        for (id, node) in state.nodes.iter() {
+            if *id != state.myself && !node.handshake {
+                message.extend(vec![id.clone(), node.ip.clone(), node.port.to_string()]);
+            }
+        }
+        message
+    }
+
+    /// Applies the gossip received from the node at the ip, returning the id of this node.
+    pub fn receive_gossip(&self, ip: &str, message: &[String]) -> Result<String, RedisError> {
+        if message.len() < 5 || !(message.len() - 5).is_multiple_of(3) {
+            return Err(INVALID_GOSSIP);
+        }
+        let id = &message[0];
+        let port: u16 = message[1].parse().map_err(|_| INVALID_GOSSIP)?;
+        let epoch: u64 = message[2].parse().map_err(|_| INVALID_GOSSIP)?;
+        let current_epoch: u64 = message[3].parse().map_err(|_| INVALID_GOSSIP)?;
+        let claimed: HashSet<u16> = parse_ranges(&message[4])
+            .ok_or(INVALID_GOSSIP)?
+            .into_iter()
+            .collect();
+
+        let mut state = self.lock();
+        if *id == state.myself {
+            return Ok(state.myself.clone());
+        }
+        state.current_epoch = state.current_epoch.max(current_epoch).max(epoch);
+        state.forget_handshake(ip, port);
+        let node = state
+            .nodes
+            .entry(id.clone())
+            .or_insert_with(|| Node::new(ip, port));
+        node.ip = ip.to_string();
+        node.port = port;
+        node.epoch = epoch;
+        node.pong_received = now_millis();
+        state.claim(id, epoch, &claimed);
+
+        for known in message[5..].chunks(3) {
+            let port: u16 = known[2].parse().map_err(|_| INVALID_GOSSIP)?;
+            if known[0] != state.myself && !state.nodes.contains_key(&known[0]) {
+                state.forget_handshake(&known[1], port);
+                state
+                    .nodes
+                    .insert(known[0].clone(), Node::new(&known[1], port));
+            }
+        }
+        Ok(state.myself.clone())
+    }
+
+    /// Registers the answer of a node to the gossip. A node in handshake is
+    /// renamed with the id it answered.
+    pub fn pong(&self, id: &str, answered_id: &str) {
+        let mut state = self.lock();
+        if id != answered_id {
+            let node = match state.nodes.remove(id) {
+                Some(node) => node,
+                None => return,
+            };
+            if answered_id == state.myself || state.nodes.contains_key(answered_id) {
+                return;
+            }
+            state
+                .nodes
+                .insert(answered_id.to_string(), Node::new(&node.ip, node.port));
+        }
+        if let Some(node) = state.nodes.get_mut(answered_id) {
+            node.connected = true;
+            node.pong_received = now_millis();
+        }
+    }
+
+    /// Marks the link with a node as broken, returning if it was connected
+    pub fn disconnected(&self, id: &str) -> bool {
+        let mut state = self.lock();
+        match state.nodes.get_mut(id) {
+            Some(node) => std::mem::replace(&mut node.connected, false),
+            None => false,
+        }
+    }
+
+    /// Returns the keys of the database that belong to the slot
+    pub fn keys_in_slot(data: &DataStorage, slot: u16) -> Vec<String> {
+        let mut keys: Vec<String> = data
+            .get_keys()
+            .into_iter()
+            .filter(|x| slots::key_slot(x) == slot)
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Returns the description of every node in the format of CLUSTER NODES
+    pub fn nodes(&self) -> String {
+        let state = self.lock();
+        let mut lines = Vec::new();
+        for (id, node) in state.nodes.iter() {
+            let myself = *id == state.myself;
+            let flags = if myself {
+                "myself,master"
+            } else if node.handshake {
+                "handshake"
+            } else {
+                "master"
+            };
+            let link = if myself || node.connected {
+                "connected"
+            } else {
+                "disconnected"
+            };
+            let mut line = format!(
+                "{} {}@{} {} - {} {} {} {}",
+                id,
+                node.addr(),
+                node.port,
+                flags,
+                node.ping_sent,
+                node.pong_received,
+                node.epoch,
+                link
+            );
+            for (start, end) in ranges(state.slots_of(id)) {
+                line.push(' ');
+                line.push_str(&format_range(start, end));
+            }
+            if myself {
+                let mut migrations: Vec<String> = state
+                    .migrating
+                    .iter()
+                    .map(|(slot, target)| format!("[{}->-{}]", slot, target))
+                    .chain(
+                        state
+                            .importing
+                            .iter()
+                            .map(|(slot, source)| format!("[{}-<-{}]", slot, source)),
+                    )
+                    .collect();
+                migrations.sort();
+                for migration in migrations {
+                    line.push(' ');
+                    line.push_str(&migration);
+                }
+            }
+            lines.push(line);
+        }
+        lines.sort();
+        lines.iter().map(|x| format!("{}\n", x)).collect()
+    }
+
+    /// Returns the ranges of slots and the node serving them, in the format of CLUSTER SLOTS
+    pub fn slots(&self) -> ProtocolType {
+        let state = self.lock();
+        let mut reply = Vec::new();
+        let mut slot = 0;
+        while slot < SLOTS {
+            let owner = match state.owner(slot) {
+                Some(owner) => owner,
+                None => {
+                    slot += 1;
+                    continue;
+                }
+            };
+            let start = slot;
+            while slot + 1 < SLOTS && state.owner(slot + 1) == Some(owner) {
+                slot += 1;
+            }
+            if let Some(node) = state.nodes.get(owner) {
+                reply.push(ProtocolType::Array(vec![
+                    ProtocolType::Integer(start as i64),
+                    ProtocolType::Integer(slot as i64),
+                    ProtocolType::Array(vec![
+                        ProtocolType::String(node.ip.clone()),
+                        ProtocolType::Integer(node.port as i64),
+                        ProtocolType::String(owner.clone()),
+                    ]),
+                ]));
+            }
+            slot += 1;
+        }
+        ProtocolType::Array(reply)
+    }
+
+    /// Returns the state of the cluster in the format of CLUSTER INFO
+    pub fn info(&self) -> Vec<String> {
+        let state = self.lock();
+        let assigned = state.owners.iter().filter(|x| x.is_some()).count();
+        let size = state
+            .owners
+            .iter()
+            .flatten()
+            .collect::<HashSet<&String>>()
+            .len();
+        let my_epoch = state.node(&state.myself).map(|x| x.epoch).unwrap_or(0);
+        vec![
+            format!(
+                "cluster_state:{}",
+                if assigned == SLOTS as usize {
+                    "ok"
+                } else {
+                    "fail"
+                }
+            ),
+            format!("cluster_slots_assigned:{}", assigned),
+            format!("cluster_slots_ok:{}", assigned),
+            "cluster_slots_pfail:0".to_string(),
+            "cluster_slots_fail:0".to_string(),
+            format!("cluster_known_nodes:{}", state.nodes.len()),
+            format!("cluster_size:{}", size),
+            format!("cluster_current_epoch:{}", state.current_epoch),
+            format!("cluster_my_epoch:{}", my_epoch),
+        ]
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn keys(values: &[&str]) -> Vec<String> {
+        values.iter().map(|x| x.to_string()).collect()
+    }
+
+    /// Delivers the gossip of a node to another one, and its answer back
+    fn exchange(from: &Cluster, to: &Cluster, id_at_from: &str) {
+        let answer = to
+            .receive_gossip("127.0.0.1", &from.gossip_message())
+            .unwrap();
+        from.pong(id_at_from, &answer);
+    }
+
+    #[test]
+    fn test_route() {
+        let cluster = Cluster::new(true, "127.0.0.1", 7000);
+        let data = DataStorage::new();
+        let foo = slots::key_slot("foo");
+        cluster.add_slots(&[foo]).unwrap();
+
+        assert!(cluster.route(&keys(&["foo"]), false, &data).is_ok());
+        assert!(cluster
+            .route(&keys(&["{foo}a", "{foo}b"]), false, &data)
+            .is_ok());
+        assert_eq!(
+            cluster.route(&keys(&["foo", "bar"]), false, &data),
+            Err(RedisError::CrossSlot)
+        );
+        assert_eq!(
+            cluster.route(&keys(&["bar"]), false, &data),
+            Err(RedisError::ClusterDown)
+        );
+        assert!(cluster.add_slots(&[foo]).is_err());
+
+        let disabled = Cluster::new(false, "127.0.0.1", 7001);
+        assert!(disabled.route(&keys(&["foo", "bar"]), false, &data).is_ok());
+    }
+
+    #[test]
+    fn test_gossip_and_redirections() {
+        let first = Cluster::new(true, "127.0.0.1", 7000);
+        let second = Cluster::new(true, "127.0.0.1", 7001);
+        let data = DataStorage::new();
+        let foo = slots::key_slot("foo");
+        first.add_slots(&[0, 1, 2]).unwrap();
+        second.add_slots(&[foo]).unwrap();
+
+        first.meet("127.0.0.1", 7001);
+        let (handshake, _) = first.peers().pop().unwrap();
+        exchange(&first, &second, &handshake);
+        exchange(&second, &first, &first.myself());
+
+        let moved = RedisError::Moved {
+            slot: foo,
+            addr: "127.0.0.1:7001".to_string(),
+        };
+        assert_eq!(first.route(&keys(&["foo"]), false, &data), Err(moved));
+        assert_eq!(
+            first.peers(),
+            vec![(second.myself(), "127.0.0.1:7001".to_string())]
+        );
+        assert!(second
+            .nodes()
+            .contains(&format!("{} 127.0.0.1:7000@7000 master", first.myself())));
+        assert!(second.nodes().contains(" connected 0-2\n"));
+
+        second.set_migrating(foo, &first.myself()).unwrap();
+        first.set_importing(foo, &second.myself()).unwrap();
+        data.set("{foo}here", Value::String("x".to_string()))
+            .unwrap();
+        let ask = RedisError::Ask {
+            slot: foo,
+            addr: "127.0.0.1:7000".to_string(),
+        };
+        assert!(second.route(&keys(&["{foo}here"]), false, &data).is_ok());
+        assert_eq!(second.route(&keys(&["foo"]), false, &data), Err(ask));
+        assert_eq!(
+            second.route(&keys(&["foo", "{foo}here"]), false, &data),
+            Err(RedisError::TryAgain)
+        );
+        assert!(first.route(&keys(&["foo"]), true, &data).is_ok());
+
+        first.set_node(foo, &first.myself()).unwrap();
+        exchange(&first, &second, &second.myself());
+        assert!(first.route(&keys(&["foo"]), false, &data).is_ok());
+        assert!(matches!(
+            second.route(&keys(&["foo"]), false, &data),
+            Err(RedisError::Moved { .. })
+        ));
+        assert!(first.info().contains(&"cluster_my_epoch:1".to_string()));
+    }
+}
@@ -0,0 +1,115 @@
+/// Number of hash slots the keys of a cluster are divided in
+pub const SLOTS: u16 = 16384;
+
+/// Returns the CRC16 of the bytes, with the XMODEM polynomial used by redis.
+pub fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in bytes {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Returns the hash slot of a key. If the key has a non empty hash tag, the part between
+/// its first `{` and the following `}`, only the tag is hashed, so related keys like
+/// `{user1}.name` and `{user1}.age` are kept in the same slot.
+pub fn key_slot(key: &str) -> u16 {
+    let bytes = key.as_bytes();
+    let tag = bytes.iter().position(|x| *x == b'{').and_then(|start| {
+        bytes[start + 1..]
+            .iter()
+            .position(|x| *x == b'}')
+            .filter(|length| *length > 0)
+            .map(|length| &bytes[start + 1..start + 1 + length])
+    });
+    crc16(tag.unwrap_or(bytes)) % SLOTS
+}
+
+/// Parses a slot number, failing if it is out of range.
+pub fn parse_slot(slot: &str) -> Option<u16> {
+    slot.parse().ok().filter(|x| *x < SLOTS)
+}
+
+/// Groups sorted slots in ranges of consecutive slots.
+pub fn ranges(slots: impl Iterator<Item = u16>) -> Vec<(u16, u16)> {
+    let mut ranges: Vec<(u16, u16)> = Vec::new();
+    for slot in slots {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == slot => *end = slot,
+            _ => ranges.push((slot, slot)),
+        }
+    }
+    ranges
+}
+
+/// Formats slots like `0-5460,5462`, or `-` if there are none, as sent between nodes.
+pub fn format_ranges(ranges: &[(u16, u16)]) -> String {
+    if ranges.is_empty() {
+        return "-".to_string();
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| format_range(*start, *end))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Formats a range like CLUSTER NODES does: `start-end`, or the slot alone.
+pub fn format_range(start: u16, end: u16) -> String {
+    if start == end {
+        start.to_string()
+    } else {
+        format!("{}-{}", start, end)
+    }
+}
+
+/// Parses the slots formatted by `format_ranges`.
+pub fn parse_ranges(ranges: &str) -> Option<Vec<u16>> {
+    if ranges == "-" {
+        return Some(vec![]);
+    }
+    let mut slots = Vec::new();
+    for range in ranges.split(',') {
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (parse_slot(start)?, parse_slot(end)?),
+            None => (parse_slot(range)?, parse_slot(range)?),
+        };
+        slots.extend(start..=end);
+    }
+    Some(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_slot() {
+        assert_eq!(crc16(b"123456789"), 0x31c3);
+        assert_eq!(key_slot("foo"), 12182);
+        assert_eq!(key_slot("somekey"), 11058);
+        assert_eq!(key_slot("{user1000}.following"), key_slot("user1000"));
+        assert_eq!(key_slot("{user1000}.followers"), key_slot("user1000"));
+        assert_eq!(key_slot("foo{}{bar}"), crc16(b"foo{}{bar}") % SLOTS);
+        assert_eq!(key_slot("foo{{bar}}zap"), key_slot("{bar"));
+    }
+
+    #[test]
+    fn test_ranges() {
+        let slots = vec![0, 1, 2, 5, 7, 8];
+        let grouped = ranges(slots.iter().cloned());
+
+        assert_eq!(grouped, vec![(0, 2), (5, 5), (7, 8)]);
+        assert_eq!(format_ranges(&grouped), "0-2,5,7-8");
+        assert_eq!(parse_ranges("0-2,5,7-8"), Some(slots));
+        assert_eq!(parse_ranges("-"), Some(vec![]));
+        assert_eq!(parse_ranges("0-16384"), None);
+    }
+}
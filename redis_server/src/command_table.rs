@@ -0,0 +1,274 @@
+use crate::error::RedisError;
+
+/// Struct CommandSpec. Describes a command known by the server.
+///
+/// * `arity` - Number of words of the command, including its name. A negative arity `-N`
+///   means that the command takes at least `N` words.
+/// * `flags` - Flags reported by COMMAND INFO, like `write`, `readonly` or `admin`.
+/// * `first_key`, `last_key`, `step` - Positions of the keys in the command, starting from
+///   the name at position 0. A negative `last_key` counts from the end of the command, and a
+///   `first_key` of 0 means that the command takes no keys.
+/// * `categories` - ACL categories the command belongs to.
+/// * `group` and `summary` - Documentation returned by COMMAND DOCS.
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub arity: i64,
+    pub flags: &'static [&'static str],
+    pub first_key: i64,
+    pub last_key: i64,
+    pub step: i64,
+    pub categories: &'static [&'static str],
+    pub group: &'static str,
+    pub summary: &'static str,
+}
+
+macro_rules! command {
+    ($name:literal, $arity:expr, [$($flag:literal),*], ($first:expr, $last:expr, $step:expr),
+     [$($category:literal),*], $group:literal, $summary:literal) => {
+        CommandSpec {
+            name: $name,
+            arity: $arity,
+            flags: &[$($flag),*],
+            first_key: $first,
+            last_key: $last,
+            step: $step,
+            categories: &[$($category),*],
+            group: $group,
+            summary: $summary,
+        }
+    };
+}
+
+/// Every command known by the server, sorted by name.
+pub const COMMANDS: &[CommandSpec] = &[
+    command!("acl", -2, ["admin", "noscript", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "A container for Access List Control commands."),
+    command!("append", 3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "string", "Appends a string to the value of a key. Creates the key if it doesn't exist."),
+    command!("asking", 1, ["fast"], (0, 0, 0),
+        ["connection"], "cluster", "Signals that a cluster client is following an -ASK redirect."),
+    command!("auth", -2, ["noscript", "loading", "stale", "fast", "no-auth"], (0, 0, 0),
+        ["connection"], "connection", "Authenticates the connection."),
+    command!("client", -2, ["admin", "noscript", "random", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous", "connection"], "connection", "A container for client connection commands."),
+    command!("cluster", -2, ["random", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "cluster", "A container for Redis Cluster commands."),
+    command!("command", -1, ["random", "loading", "stale"], (0, 0, 0),
+        ["connection"], "server", "Returns detailed information about all commands."),
+    command!("config", -2, ["admin", "noscript", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "A container for server configuration commands."),
+    command!("copy", 3, ["write", "denyoom"], (1, 2, 1),
+        ["keyspace", "write"], "generic", "Copies the value of a key to a new key."),
+    command!("dbsize", 1, ["readonly", "fast"], (0, 0, 0),
+        ["keyspace", "read"], "server", "Returns the number of keys in the database."),
+    command!("decrby", 3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "string", "Decrements a number from the integer value of a key. Uses 0 as initial value if the key doesn't exist."),
+    command!("del", -2, ["write"], (1, -1, 1),
+        ["keyspace", "write"], "generic", "Deletes one or more keys."),
+    command!("dump", 2, ["readonly", "random"], (1, 1, 1),
+        ["keyspace", "read"], "generic", "Returns a serialized representation of the value stored at a key."),
+    command!("exists", -2, ["readonly", "fast"], (1, -1, 1),
+        ["keyspace", "read"], "generic", "Determines whether one or more keys exist."),
+    command!("expire", 3, ["write", "fast"], (1, 1, 1),
+        ["keyspace", "write"], "generic", "Sets the expiration time of a key in seconds."),
+    command!("expireat", 3, ["write", "fast"], (1, 1, 1),
+        ["keyspace", "write"], "generic", "Sets the expiration time of a key to a Unix timestamp."),
+    command!("flushdb", -1, ["write"], (0, 0, 0),
+        ["keyspace", "write", "dangerous"], "server", "Removes all keys from the current database."),
+    command!("geoadd", -5, ["write", "denyoom"], (1, 1, 1),
+        ["write"], "geo", "Adds one or more members to a geospatial index. The key is created if it doesn't exist."),
+    command!("geodist", -4, ["readonly"], (1, 1, 1),
+        ["read"], "geo", "Returns the distance between two members of a geospatial index."),
+    command!("geohash", -2, ["readonly"], (1, 1, 1),
+        ["read"], "geo", "Returns members from a geospatial index as geohash strings."),
+    command!("geopos", -2, ["readonly"], (1, 1, 1),
+        ["read"], "geo", "Returns the longitude and latitude of members from a geospatial index."),
+    command!("geosearch", -7, ["readonly"], (1, 1, 1),
+        ["read"], "geo", "Queries a geospatial index for members inside an area of a box or a circle."),
+    command!("get", 2, ["readonly", "fast"], (1, 1, 1),
+        ["read"], "string", "Returns the string value of a key."),
+    command!("getdel", 2, ["write", "fast"], (1, 1, 1),
+        ["write"], "string", "Returns the string value of a key after deleting the key."),
+    command!("getset", 3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "string", "Returns the previous string value of a key after setting it to a new value."),
+    command!("hello", -1, ["noscript", "loading", "stale", "fast", "no-auth"], (0, 0, 0),
+        ["fast", "connection"], "connection", "Handshakes with the Redis server."),
+    command!("incrby", 3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "string", "Increments the integer value of a key by a number. Uses 0 as initial value if the key doesn't exist."),
+    command!("info", -1, ["random", "loading", "stale"], (0, 0, 0),
+        ["dangerous"], "server", "Returns information and statistics about the server."),
+    command!("keys", 2, ["readonly"], (0, 0, 0),
+        ["keyspace", "read", "dangerous"], "generic", "Returns all key names that match a pattern."),
+    command!("latency", -2, ["admin", "noscript", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "A container for latency diagnostics commands."),
+    command!("lindex", 3, ["readonly"], (1, 1, 1),
+        ["read"], "list", "Returns an element from a list by its index."),
+    command!("llen", 2, ["readonly", "fast"], (1, 1, 1),
+        ["read"], "list", "Returns the length of a list."),
+    command!("lpop", -2, ["write", "fast"], (1, 1, 1),
+        ["write"], "list", "Returns the first elements in a list after removing it. Deletes the list if the last element was popped."),
+    command!("lpush", -3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "list", "Prepends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!("lpushx", -3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "list", "Prepends one or more elements to a list only when the list exists."),
+    command!("lrange", 4, ["readonly"], (1, 1, 1),
+        ["read"], "list", "Returns a range of elements from a list."),
+    command!("lrem", 4, ["write"], (1, 1, 1),
+        ["write"], "list", "Removes elements from a list. Deletes the list if the last element was removed."),
+    command!("lset", 4, ["write", "denyoom"], (1, 1, 1),
+        ["write"], "list", "Sets the value of an element in a list by its index."),
+    command!("memory", -2, ["readonly", "random"], (0, 0, 0),
+        ["read"], "server", "A container for memory diagnostics commands."),
+    command!("mget", -2, ["readonly", "fast"], (1, -1, 1),
+        ["read"], "string", "Atomically returns the string values of one or more keys."),
+    command!("monitor", 1, ["admin", "noscript", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "Listens for all requests received by the server in real-time."),
+    command!("mset", -3, ["write", "denyoom"], (1, -1, 2),
+        ["write"], "string", "Atomically creates or modifies the string values of one or more keys."),
+    command!("object", -2, ["readonly", "random"], (2, 2, 1),
+        ["keyspace", "read"], "generic", "A container for object introspection commands."),
+    command!("persist", 2, ["write", "fast"], (1, 1, 1),
+        ["keyspace", "write"], "generic", "Removes the expiration time of a key."),
+    command!("ping", -1, ["stale", "fast"], (0, 0, 0),
+        ["connection"], "connection", "Returns the server's liveliness response."),
+    command!("psync", -3, ["admin", "noscript"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "An internal command used in replication."),
+    command!("publish", 3, ["pubsub", "loading", "stale", "fast"], (0, 0, 0),
+        ["pubsub"], "pubsub", "Posts a message to a channel."),
+    command!("pubsub", -2, ["pubsub", "random", "loading", "stale"], (0, 0, 0),
+        ["pubsub"], "pubsub", "A container for Pub/Sub commands."),
+    command!("punsubscribe", -1, ["pubsub", "noscript", "loading", "stale"], (0, 0, 0),
+        ["pubsub"], "pubsub", "Stops listening to messages published to channels that match one or more patterns."),
+    command!("quit", -1, ["loading", "stale", "fast", "no-auth"], (0, 0, 0),
+        ["connection"], "connection", "Closes the connection."),
+    command!("randomkey", 1, ["readonly", "random"], (0, 0, 0),
+        ["keyspace", "read"], "generic", "Returns a random key name from the database."),
+    command!("rename", 3, ["write"], (1, 2, 1),
+        ["keyspace", "write"], "generic", "Renames a key and overwrites the destination."),
+    command!("renamenx", 3, ["write", "fast"], (1, 2, 1),
+        ["keyspace", "write"], "generic", "Renames a key only when the target key name doesn't exist."),
+    command!("replconf", -1, ["admin", "noscript", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "An internal command for configuring the replication stream."),
+    command!("replicaof", 3, ["admin", "noscript", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "Configures a server as replica of another, or promotes it to a master."),
+    command!("restore", -4, ["write", "denyoom"], (1, 1, 1),
+        ["keyspace", "write", "dangerous"], "generic", "Creates a key from the serialized representation of a value."),
+    command!("role", 1, ["noscript", "loading", "stale", "fast"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "Returns the replication role."),
+    command!("rpop", -2, ["write", "fast"], (1, 1, 1),
+        ["write"], "list", "Returns and removes the last elements of a list. Deletes the list if the last element was popped."),
+    command!("rpush", -3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "list", "Appends one or more elements to a list. Creates the key if it doesn't exist."),
+    command!("rpushx", -3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "list", "Appends an element to a list only when the list exists."),
+    command!("sadd", -3, ["write", "denyoom", "fast"], (1, 1, 1),
+        ["write"], "set", "Adds one or more members to a set. Creates the key if it doesn't exist."),
+    command!("scard", 2, ["readonly", "fast"], (1, 1, 1),
+        ["read"], "set", "Returns the number of members in a set."),
+    command!("set", -3, ["write", "denyoom"], (1, 1, 1),
+        ["write"], "string", "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist."),
+    command!("sismember", 3, ["readonly", "fast"], (1, 1, 1),
+        ["read"], "set", "Determines whether a member belongs to a set."),
+    command!("slaveof", 3, ["admin", "noscript", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "Sets a server as a replica of another, or promotes it to being a master."),
+    command!("slowlog", -2, ["admin", "random", "loading", "stale"], (0, 0, 0),
+        ["admin", "dangerous"], "server", "A container for slow log commands."),
+    command!("smembers", 2, ["readonly"], (1, 1, 1),
+        ["read"], "set", "Returns all members of a set."),
+    command!("sort", -2, ["write", "denyoom"], (1, 1, 1),
+        ["write", "dangerous"], "generic", "Sorts the elements in a list, a set or a sorted set, optionally storing the result."),
+    command!("srem", -3, ["write", "fast"], (1, 1, 1),
+        ["write"], "set", "Removes one or more members from a set. Deletes the set if the last member was removed."),
+    command!("strlen", 2, ["readonly", "fast"], (1, 1, 1),
+        ["read"], "string", "Returns the length of a string value."),
+    command!("subscribe", -2, ["pubsub", "noscript", "loading", "stale"], (0, 0, 0),
+        ["pubsub"], "pubsub", "Listens for messages published to channels."),
+    command!("touch", -2, ["readonly", "fast"], (1, -1, 1),
+        ["keyspace", "read"], "generic", "Returns the number of existing keys out of those specified after updating the time they were last accessed."),
+    command!("ttl", 2, ["readonly", "random", "fast"], (1, 1, 1),
+        ["keyspace", "read"], "generic", "Returns the expiration time in seconds of a key."),
+    command!("type", 2, ["readonly", "fast"], (1, 1, 1),
+        ["keyspace", "read"], "generic", "Determines the type of value stored at a key."),
+    command!("unlink", -2, ["write", "fast"], (1, -1, 1),
+        ["keyspace", "write"], "generic", "Asynchronously deletes one or more keys."),
+    command!("unsubscribe", -1, ["pubsub", "noscript", "loading", "stale"], (0, 0, 0),
+        ["pubsub"], "pubsub", "Stops listening to messages posted to channels."),
+];
+
+/// Returns the command with the given name, which must be in lowercase.
+pub fn lookup(name: &str) -> Option<&'static CommandSpec> {
+    COMMANDS
+        .binary_search_by(|x| x.name.cmp(name))
+        .ok()
+        .map(|x| &COMMANDS[x])
+}
+
+impl CommandSpec {
+    /// Checks that the command is called with a valid number of arguments,
+    /// not counting the name of the command.
+    pub fn check_arity(&self, arguments: usize) -> Result<(), RedisError> {
+        let words = arguments as i64 + 1;
+        if (self.arity >= 0 && words != self.arity) || words < -self.arity {
+            return Err(RedisError::WrongArity(self.name));
+        }
+        Ok(())
+    }
+
+    /// Returns if the command has the flag.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /// Returns the positions of the keys among the arguments of the command,
+    /// not counting the name of the command.
+    pub fn key_positions(&self, arguments: usize) -> Vec<usize> {
+        if self.first_key <= 0 {
+            return vec![];
+        }
+        let last = if self.last_key < 0 {
+            arguments as i64 + self.last_key
+        } else {
+            self.last_key - 1
+        };
+        if last < 0 {
+            return vec![];
+        }
+        (self.first_key as usize - 1..=last as usize)
+            .step_by(self.step.max(1) as usize)
+            .filter(|x| *x < arguments)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_sorted() {
+        assert!(COMMANDS.windows(2).all(|x| x[0].name < x[1].name));
+        assert!(lookup("get").is_some());
+        assert!(lookup("foo").is_none());
+    }
+
+    #[test]
+    fn test_check_arity() {
+        let get = lookup("get").unwrap();
+        assert!(get.check_arity(1).is_ok());
+        assert_eq!(get.check_arity(0), Err(RedisError::WrongArity("get")));
+        assert!(get.check_arity(2).is_err());
+
+        let del = lookup("del").unwrap();
+        assert!(del.check_arity(0).is_err());
+        assert!(del.check_arity(3).is_ok());
+    }
+
+    #[test]
+    fn test_key_positions() {
+        assert_eq!(lookup("get").unwrap().key_positions(1), vec![0]);
+        assert_eq!(lookup("mset").unwrap().key_positions(4), vec![0, 2]);
+        assert_eq!(lookup("del").unwrap().key_positions(3), vec![0, 1, 2]);
+        assert_eq!(lookup("object").unwrap().key_positions(2), vec![1]);
+        assert!(lookup("ping").unwrap().key_positions(1).is_empty());
+    }
+}
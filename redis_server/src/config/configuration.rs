@@ -7,6 +7,10 @@ const DEFAULT_TIMEOUT: u32 = 0;
 const DEFAULT_DBFILENAME: &str = "dump.rdb";
 const DEFAULT_LOGFILE: &str = "logfile.txt";
 const DEFAULT_IP: &str = "127.0.0.1";
+const DEFAULT_MAXCLIENTS: u64 = 10000;
+const DEFAULT_SLOWLOG_LOG_SLOWER_THAN: i64 = 10000;
+const DEFAULT_SLOWLOG_MAX_LEN: u64 = 128;
+const DEFAULT_LATENCY_MONITOR_THRESHOLD: u64 = 0;
 
 //To add a new configuration attribute:
 //  1) Add de default value as a constant.
@@ -15,7 +19,7 @@ const DEFAULT_IP: &str = "127.0.0.1";
 //  4) Add the check and set to the set_all_params function.
 //  5) Add the get_/attribute/ function to return the value.
 
-///The basic configuration for de server. Includes [Verbose, Port, Timeout, DBFilename, LOGfile, IP]
+///The basic configuration for de server. Includes [Verbose, Port, Timeout, DBFilename, LOGfile, IP, Requirepass, ACLfile, Maxclients, Masterauth, Cluster-enabled, Slowlog-log-slower-than, Slowlog-max-len, Latency-monitor-threshold]
 #[allow(dead_code)]
 #[derive(Default)]
 pub struct Configuration {
@@ -25,6 +29,14 @@ pub struct Configuration {
     dbfilename: String,
     logfile: String,
     ip: String,
+    requirepass: Option<String>,
+    aclfile: Option<String>,
+    maxclients: u64,
+    masterauth: Option<String>,
+    cluster_enabled: bool,
+    slowlog_log_slower_than: i64,
+    slowlog_max_len: u64,
+    latency_monitor_threshold: u64,
     config_file: Option<String>,
 }
 
@@ -39,6 +51,14 @@ impl Configuration {
             dbfilename: DEFAULT_DBFILENAME.to_string(),
             logfile: DEFAULT_LOGFILE.to_string(),
             ip: DEFAULT_IP.to_string(),
+            requirepass: None,
+            aclfile: None,
+            maxclients: DEFAULT_MAXCLIENTS,
+            masterauth: None,
+            cluster_enabled: false,
+            slowlog_log_slower_than: DEFAULT_SLOWLOG_LOG_SLOWER_THAN,
+            slowlog_max_len: DEFAULT_SLOWLOG_MAX_LEN,
+            latency_monitor_threshold: DEFAULT_LATENCY_MONITOR_THRESHOLD,
             config_file: None,
         }
     }
@@ -46,11 +66,7 @@ impl Configuration {
     /// Re-sets the configuration based on a configuration file (.config).
     /// If any problem happens, it returns a string describing the problem.
     pub fn set_config(&mut self, file_path: &str) -> Result<bool, String> {
-        let map;
-        match self.parse(file_path) {
-            Ok(map_) => map = map_,
-            Err(err) => return Err(err),
-        }
+        let map = self.parse(file_path)?;
 
         if let Some(err) = self.set_all_params(map) {
             return Err(err);
@@ -145,15 +161,98 @@ impl Configuration {
                 println!("Configuración de la ip cargada : {}", self.ip);
             }
         }
+
+        if let Some(requirepass_) = map.get("requirepass") {
+            self.set_requirepass(requirepass_.to_string());
+            if self.verbose == 1 {
+                println!("Configuración de la contraseña cargada");
+            }
+        }
+
+        if let Some(aclfile_) = map.get("aclfile") {
+            self.aclfile = Some(aclfile_.to_string());
+            if self.verbose == 1 {
+                println!("Configuración del archivo de ACL cargada : {}", aclfile_);
+            }
+        }
+
+        if let Some(maxclients_) = map.get("maxclients") {
+            match maxclients_.parse::<u64>() {
+                Ok(number) if number > 0 => self.maxclients = number,
+                _ => return Some("Maxclients mal configurado.".to_string()),
+            }
+            if self.verbose == 1 {
+                println!("Configuración de maxclients cargada : {}", self.maxclients);
+            }
+        }
+
+        if let Some(masterauth_) = map.get("masterauth") {
+            self.set_masterauth(masterauth_.to_string());
+            if self.verbose == 1 {
+                println!("Configuración de la contraseña del master cargada");
+            }
+        }
+
+        if let Some(cluster_enabled_) = map.get("cluster-enabled") {
+            match &cluster_enabled_.to_ascii_lowercase()[..] {
+                "yes" => self.cluster_enabled = true,
+                "no" => self.cluster_enabled = false,
+                _ => return Some("Cluster-enabled mal configurado.".to_string()),
+            }
+            if self.verbose == 1 {
+                println!(
+                    "Configuración del modo cluster cargada : {}",
+                    cluster_enabled_
+                );
+            }
+        }
+
+        if let Some(slower_than_) = map.get("slowlog-log-slower-than") {
+            match slower_than_.parse::<i64>() {
+                Ok(number) => self.slowlog_log_slower_than = number,
+                Err(_) => return Some("Slowlog-log-slower-than mal configurado.".to_string()),
+            }
+            if self.verbose == 1 {
+                println!(
+                    "Configuración del umbral del slowlog cargada : {}",
+                    self.slowlog_log_slower_than
+                );
+            }
+        }
+
+        if let Some(max_len_) = map.get("slowlog-max-len") {
+            match max_len_.parse::<u64>() {
+                Ok(number) => self.slowlog_max_len = number,
+                Err(_) => return Some("Slowlog-max-len mal configurado.".to_string()),
+            }
+            if self.verbose == 1 {
+                println!(
+                    "Configuración del largo del slowlog cargada : {}",
+                    self.slowlog_max_len
+                );
+            }
+        }
+
+        if let Some(threshold_) = map.get("latency-monitor-threshold") {
+            match threshold_.parse::<u64>() {
+                Ok(number) => self.latency_monitor_threshold = number,
+                Err(_) => return Some("Latency-monitor-threshold mal configurado.".to_string()),
+            }
+            if self.verbose == 1 {
+                println!(
+                    "Configuración del umbral del monitor de latencia cargada : {}",
+                    self.latency_monitor_threshold
+                );
+            }
+        }
         None
     }
 
     fn check_number_between(&mut self, number: &str, bottom: u32, top: u32) -> bool {
-        let int_number: u32;
-        match number.parse::<u32>() {
-            Ok(x) => int_number = x,
+        let int_number: u32 = match number.parse::<u32>() {
+            Ok(x) => x,
             Err(_) => return false,
-        }
+        };
 
         if int_number <= top && int_number >= bottom {
             return true;
@@ -189,9 +288,41 @@ impl Configuration {
         &self.ip
     }
 
+    pub fn get_requirepass(&self) -> Option<&String> {
+        self.requirepass.as_ref()
+    }
+
+    pub fn get_aclfile(&self) -> Option<&String> {
+        self.aclfile.as_ref()
+    }
+
+    pub fn get_maxclients(&self) -> u64 {
+        self.maxclients
+    }
+
+    pub fn get_masterauth(&self) -> Option<&String> {
+        self.masterauth.as_ref()
+    }
+
+    pub fn get_cluster_enabled(&self) -> bool {
+        self.cluster_enabled
+    }
+
+    pub fn get_slowlog_log_slower_than(&self) -> i64 {
+        self.slowlog_log_slower_than
+    }
+
+    pub fn get_slowlog_max_len(&self) -> u64 {
+        self.slowlog_max_len
+    }
+
+    pub fn get_latency_monitor_threshold(&self) -> u64 {
+        self.latency_monitor_threshold
+    }
+
     pub fn set_verbose(&mut self, new_verb: u8) -> Result<&'static str, &'static str> {
         if new_verb != 0 && new_verb != 1 {
-            return Err("La verbosidad tiene que ser 0 o 1.");
+            return Err("Verbosity must be 0 or 1");
         }
         self.verbose = new_verb;
         Ok("Ok")
@@ -205,6 +336,36 @@ impl Configuration {
         self.timeout = new_timeout;
     }
 
+    /// Sets the time in microseconds over which commands are recorded in the slow log.
+    /// A negative time disables the slow log.
+    pub fn set_slowlog_log_slower_than(&mut self, new_slower_than: i64) {
+        self.slowlog_log_slower_than = new_slower_than;
+    }
+
+    pub fn set_slowlog_max_len(&mut self, new_max_len: u64) {
+        self.slowlog_max_len = new_max_len;
+    }
+
+    /// Sets the time in milliseconds over which events are recorded by the latency monitor.
+    /// A threshold of zero disables the latency monitor.
+    pub fn set_latency_monitor_threshold(&mut self, new_threshold: u64) {
+        self.latency_monitor_threshold = new_threshold;
+    }
+
+    /// Starts the server in cluster mode. It only has effect before the server runs.
+    pub fn set_cluster_enabled(&mut self, new_cluster_enabled: bool) {
+        self.cluster_enabled = new_cluster_enabled;
+    }
+
+    /// Sets the maximum number of connected clients. It must be at least one.
+    pub fn set_maxclients(&mut self, new_maxclients: u64) -> Result<&'static str, &'static str> {
+        if new_maxclients == 0 {
+            return Err("Maxclients must be greater than 0");
+        }
+        self.maxclients = new_maxclients;
+        Ok("Ok")
+    }
+
     pub fn set_dbfilename(&mut self, new_dbfilename: String) {
         self.dbfilename = new_dbfilename;
     }
@@ -212,6 +373,29 @@ impl Configuration {
     pub fn set_logfile(&mut self, new_logfile: String) {
         self.logfile = new_logfile;
     }
+
+    /// Sets the password clients must send with AUTH. An empty password disables authentication.
+    pub fn set_requirepass(&mut self, new_requirepass: String) {
+        self.requirepass = if new_requirepass.is_empty() {
+            None
+        } else {
+            Some(new_requirepass)
+        };
+    }
+
+    /// Sets the file the ACL users are loaded from. It only has effect before the server runs.
+    pub fn set_aclfile(&mut self, new_aclfile: String) {
+        self.aclfile = Some(new_aclfile);
+    }
+
+    /// Sets the password a replica sends to its master. An empty password removes it.
+    pub fn set_masterauth(&mut self, new_masterauth: String) {
+        self.masterauth = if new_masterauth.is_empty() {
+            None
+        } else {
+            Some(new_masterauth)
+        };
+    }
 }
 
 #[cfg(test)]
@@ -222,7 +406,7 @@ mod tests {
     fn test_1_general_configuration() {
         let mut configuration = Configuration::new();
 
-        match configuration.set_config(&"test_files/test_configuration_1.config".to_string()) {
+        match configuration.set_config("test_files/test_configuration_1.config") {
             Err(_) => {
                 assert_eq!(true, false)
             }
@@ -232,6 +416,8 @@ mod tests {
                 assert_eq!(configuration.get_timeout(), 100);
                 assert_eq!(configuration.get_dbfilename(), "andres.config");
                 assert_eq!(configuration.get_logfile(), "asda");
+                assert_eq!(configuration.get_requirepass(), None);
+                assert_eq!(configuration.get_maxclients(), 10000);
             }
         }
     }
@@ -240,7 +426,7 @@ mod tests {
     fn test_2_error_while_verbose_different_than_0_or_1() {
         let mut configuration = Configuration::new();
 
-        match configuration.set_config(&"test_files/test_configuration_2.config".to_string()) {
+        match configuration.set_config("test_files/test_configuration_2.config") {
             Err(x) => {
                 assert_eq!(x, "Verbosidad mal configurada.");
             }
@@ -254,7 +440,7 @@ mod tests {
     fn test_3_accepts_less_parameters_and_has_default() {
         let mut configuration = Configuration::new();
 
-        match configuration.set_config(&"test_files/test_configuration_3.config".to_string()) {
+        match configuration.set_config("test_files/test_configuration_3.config") {
             Err(_) => {
                 assert_eq!(true, false)
             }
@@ -264,6 +450,39 @@ mod tests {
                 assert_eq!(configuration.get_timeout(), 0);
                 assert_eq!(configuration.get_dbfilename(), "andres.config");
                 assert_eq!(configuration.get_logfile(), "asda");
+                assert!(!configuration.get_cluster_enabled());
+                assert_eq!(configuration.get_slowlog_log_slower_than(), 10000);
+                assert_eq!(configuration.get_slowlog_max_len(), 128);
+                assert_eq!(configuration.get_latency_monitor_threshold(), 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_4_requirepass() {
+        let mut configuration = Configuration::new();
+
+        match configuration.set_config("test_files/test_configuration_4.config") {
+            Err(_) => {
+                assert_eq!(true, false)
+            }
+            Ok(_) => {
+                assert_eq!(
+                    configuration.get_requirepass(),
+                    Some(&"secreto".to_string())
+                );
+                configuration.set_requirepass("".to_string());
+                assert_eq!(configuration.get_requirepass(), None);
+                assert_eq!(
+                    configuration.get_aclfile(),
+                    Some(&"test_files/users.acl".to_string())
+                );
+                assert_eq!(configuration.get_maxclients(), 100);
+                assert!(configuration.set_maxclients(0).is_err());
+                assert!(configuration.get_cluster_enabled());
+                assert_eq!(configuration.get_slowlog_log_slower_than(), 500);
+                assert_eq!(configuration.get_slowlog_max_len(), 64);
+                assert_eq!(configuration.get_latency_monitor_threshold(), 100);
             }
         }
     }
@@ -0,0 +1,184 @@
+use std::fmt;
+
+/// Enum RedisError. Errors returned by the commands, rendered with the prefix
+/// redis uses for each kind of error so client libraries can classify them.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedisError {
+    /// The key holds a value of a different type than the one expected by the command.
+    WrongType,
+    /// The arguments of the command are not valid.
+    Syntax,
+    /// An argument, or the value of a key, is not an integer.
+    NotInteger,
+    /// An argument, or the value of a key, is not a float.
+    NotFloat,
+    /// The key used by the command does not exist.
+    NoSuchKey,
+    /// The target key of the command already exists.
+    BusyKey,
+    /// The command was called with a wrong number of arguments.
+    WrongArity(&'static str),
+    /// The command is not known by the server.
+    UnknownCommand {
+        name: String,
+        arguments: Vec<String>,
+    },
+    /// The client must call AUTH before running the command.
+    NoAuth,
+    /// The password given to AUTH is not valid.
+    WrongPass,
+    /// The ACL user of the client is not allowed to run the command.
+    NoPerm(&'static str),
+    /// The client asked HELLO for a RESP version the server does not speak.
+    NoProto,
+    /// The client sent a write command to a read only replica.
+    ReadOnly,
+    /// The slot of the keys is served by another node of the cluster.
+    Moved { slot: u16, addr: String },
+    /// The slot of the keys is being migrated, and the missing keys must be asked to another node.
+    Ask { slot: u16, addr: String },
+    /// The keys of a command belong to different slots of the cluster.
+    CrossSlot,
+    /// Some keys of a command were already migrated to another node.
+    TryAgain,
+    /// The slot of the keys is not served by any node of the cluster.
+    ClusterDown,
+    /// Any other error, rendered with the generic `ERR` prefix.
+    Generic(&'static str),
+}
+
+impl RedisError {
+    /// Returns the prefix of the error, like `ERR` or `WRONGTYPE`.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            RedisError::WrongType => "WRONGTYPE",
+            RedisError::BusyKey => "BUSYKEY",
+            RedisError::NoAuth => "NOAUTH",
+            RedisError::WrongPass => "WRONGPASS",
+            RedisError::NoPerm(_) => "NOPERM",
+            RedisError::NoProto => "NOPROTO",
+            RedisError::ReadOnly => "READONLY",
+            RedisError::Moved { .. } => "MOVED",
+            RedisError::Ask { .. } => "ASK",
+            RedisError::CrossSlot => "CROSSSLOT",
+            RedisError::TryAgain => "TRYAGAIN",
+            RedisError::ClusterDown => "CLUSTERDOWN",
+            _ => "ERR",
+        }
+    }
+
+    /// Returns if the error refused the command before running it, which redis counts
+    /// as a rejected call instead of a failed one.
+    pub fn is_rejection(&self) -> bool {
+        matches!(
+            self,
+            RedisError::WrongArity(_)
+                | RedisError::UnknownCommand { .. }
+                | RedisError::NoAuth
+                | RedisError::NoPerm(_)
+                | RedisError::ReadOnly
+                | RedisError::Moved { .. }
+                | RedisError::Ask { .. }
+                | RedisError::CrossSlot
+                | RedisError::TryAgain
+                | RedisError::ClusterDown
+        )
+    }
+}
+
+impl fmt::Display for RedisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.prefix())?;
+        match self {
+            RedisError::WrongType => {
+                write!(f, "Operation against a key holding the wrong kind of value")
+            }
+            RedisError::Syntax => write!(f, "syntax error"),
+            RedisError::NotInteger => write!(f, "value is not an integer or out of range"),
+            RedisError::NotFloat => write!(f, "value is not a valid float"),
+            RedisError::NoSuchKey => write!(f, "no such key"),
+            RedisError::BusyKey => write!(f, "Target key name already exists."),
+            RedisError::WrongArity(name) => {
+                write!(f, "wrong number of arguments for '{}' command", name)
+            }
+            RedisError::UnknownCommand { name, arguments } => {
+                write!(f, "unknown command '{}', with args beginning with: ", name)?;
+                for argument in arguments {
+                    write!(f, "'{}' ", argument)?;
+                }
+                Ok(())
+            }
+            RedisError::NoAuth => write!(f, "Authentication required."),
+            RedisError::WrongPass => {
+                write!(f, "invalid username-password pair or user is disabled.")
+            }
+            RedisError::NoPerm(message) => write!(f, "{}", message),
+            RedisError::NoProto => write!(f, "unsupported protocol version"),
+            RedisError::ReadOnly => write!(f, "You can't write against a read only replica."),
+            RedisError::Moved { slot, addr } | RedisError::Ask { slot, addr } => {
+                write!(f, "{} {}", slot, addr)
+            }
+            RedisError::CrossSlot => write!(f, "Keys in request don't hash to the same slot"),
+            RedisError::TryAgain => write!(f, "Multiple keys request during rehashing of slot"),
+            RedisError::ClusterDown => write!(f, "Hash slot not served"),
+            RedisError::Generic(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Errors of the protocol types, the logger or the configuration become generic errors.
+/// Commands reading an integer argument map its error to `NotInteger` themselves.
+impl From<&'static str> for RedisError {
+    fn from(message: &'static str) -> Self {
+        RedisError::Generic(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            RedisError::WrongType.to_string(),
+            "WRONGTYPE Operation against a key holding the wrong kind of value"
+        );
+        assert_eq!(
+            RedisError::WrongArity("get").to_string(),
+            "ERR wrong number of arguments for 'get' command"
+        );
+        assert_eq!(
+            RedisError::NoPerm("this user has no permissions to run this command").to_string(),
+            "NOPERM this user has no permissions to run this command"
+        );
+        assert_eq!(
+            RedisError::Moved {
+                slot: 3999,
+                addr: "127.0.0.1:6381".to_string()
+            }
+            .to_string(),
+            "MOVED 3999 127.0.0.1:6381"
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let error = RedisError::UnknownCommand {
+            name: "foo".to_string(),
+            arguments: vec!["a".to_string(), "b".to_string()],
+        };
+        assert_eq!(
+            error.to_string(),
+            "ERR unknown command 'foo', with args beginning with: 'a' 'b' "
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            RedisError::from("Failed to lock database").to_string(),
+            "ERR Failed to lock database"
+        );
+    }
+}
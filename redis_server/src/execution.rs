@@ -1,8 +1,17 @@
+use crate::acl::categories;
+use crate::acl::users::{Acl, DEFAULT_USER};
 use crate::client::Client;
+use crate::client_registry::ClientRegistry;
+use crate::cluster::Cluster;
+use crate::command_table;
 use crate::config::configuration::Configuration;
+use crate::error::RedisError;
+use crate::geo_command::{geoadd, geodist, geohash, geopos, geosearch};
 use crate::key_command::{
-    copy, del, exists, expire, expireat, key_type, keys, persist, rename, sort, touch, ttl,
+    copy, del, dump, exists, expire, expireat, key_type, keys, object, persist, randomkey, rename,
+    renamenx, restore, sort, touch, ttl, unlink,
 };
+use crate::latency::{LatencyMonitor, COMMAND_EVENT};
 use crate::lists_command::{
     lindex, llen, lpop, lpush, lpushx, lrange, lrem, lset, rpop, rpush, rpushx,
 };
@@ -10,15 +19,21 @@ use crate::logging::logger::Logger;
 use crate::monitor::Monitor;
 use crate::pubsub::PublisherSubscriber;
 use crate::pubsub_command::{publish, pubsub, punsubscribe, subscribe, unsubscribe};
-use crate::server_command::{config, dbsize, flushdb, info, monitor, ping, quit};
+use crate::replication::{self, Replication};
+use crate::server_command::{
+    acl, asking, auth, client as client_command, cluster, command, config, dbsize, flushdb, hello,
+    info, latency, memory, monitor, ping, psync, quit, replconf, replicaof, role, slowlog,
+};
 use crate::set_command::{sadd, scard, sismember, smembers, srem};
+use crate::slowlog::SlowLog;
+use crate::stats::{CallOutcome, ServerStats};
 use crate::storage::data_storage::DataStorage;
 use crate::string_command::{append, decrby, get, getdel, getset, incrby, mget, mset, set, strlen};
 use redis_protocol::command::Command;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 #[allow(dead_code)]
 /// Struct which holds an execution context for the server
@@ -26,13 +41,20 @@ pub struct Execution {
     data: Arc<DataStorage>,
     config: Arc<Mutex<Configuration>>,
     sys_time: Arc<SystemTime>,
-    client_connected: u64,
     logger: Arc<Logger>,
     pubsub: Arc<PublisherSubscriber>,
     monitor: Monitor,
+    stats: Arc<ServerStats>,
+    clients: Arc<ClientRegistry>,
+    acl: Arc<Acl>,
+    replication: Arc<Replication>,
+    cluster: Arc<Cluster>,
+    slowlog: Arc<SlowLog>,
+    latency: Arc<LatencyMonitor>,
 }
 
 impl Execution {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         data: Arc<DataStorage>,
         config: Arc<Mutex<Configuration>>,
@@ -40,56 +62,192 @@ impl Execution {
         logger: Arc<Logger>,
         pubsub: Arc<PublisherSubscriber>,
         monitor: Monitor,
+        stats: Arc<ServerStats>,
+        clients: Arc<ClientRegistry>,
+        acl: Arc<Acl>,
+        replication: Arc<Replication>,
+        cluster: Arc<Cluster>,
+        slowlog: Arc<SlowLog>,
+        latency: Arc<LatencyMonitor>,
     ) -> Self {
         Execution {
             data,
             config,
             sys_time,
-            client_connected: 0,
             logger,
             pubsub,
             monitor,
+            stats,
+            clients,
+            acl,
+            replication,
+            cluster,
+            slowlog,
+            latency,
+        }
+    }
+
+    /// Returns the end of the CLIENT PAUSE that delays the command, if it is paused.
+    /// The CLIENT command is never paused, so a pause can always be stopped.
+    pub fn paused_until(&self, cmd: &Command) -> Option<SystemTime> {
+        let name = cmd.name().to_ascii_lowercase();
+        if name == "client" {
+            return None;
         }
+        self.clients.paused_until(is_write_command(&name))
     }
 
     /// Matches a command with it's executing function and runs it.
+    /// Commands missing from the command table, or called with a wrong number of arguments,
+    /// are rejected before running. Write commands are sent to the replicas once they succeed,
+    /// and rejected if this server is a replica. In cluster mode the commands on keys of slots
+    /// served by other nodes are redirected.
     pub fn run(
         &self,
         cmd: &Command,
         builder: &mut ResponseBuilder,
         client: Arc<Client>,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), RedisError> {
+        self.stats.command_processed();
+        client.register_command(&cmd.name());
+        let name = cmd.name().to_ascii_lowercase();
+        let spec = command_table::lookup(&name).ok_or_else(|| unknown_command(cmd))?;
+        spec.check_arity(cmd.arguments().len())?;
+        self.check_permissions(&name, cmd, &client)?;
+        if self.cluster.is_enabled() && name != "asking" {
+            let arguments: Vec<String> = cmd.arguments().iter().map(|x| x.to_string()).collect();
+            let keys = categories::keys(&name, &arguments);
+            self.cluster
+                .route(&keys, client.take_asking(), &self.data)?;
+        }
+
+        // With RESP3 the messages are pushes, so every command can be used in pub/sub mode
         if client.in_pubsub_mode()
+            && client.protocol() == 2
             && !matches!(
                 &cmd.name().to_uppercase()[..],
                 "SUBSCRIBE" | "PSUBSCRIBE" | "UNSUBSCRIBE" | "PUNSUBSCRIBE" | "PING" | "QUIT"
             )
         {
-            return Err("A client in pub/sub mode can only use SUBSCRIBE, PSUBSCRIBE, UNSUBSCRIBE, PUNSUBSCRIBE, PING and QUIT");
+            return Err(RedisError::Generic("A client in pub/sub mode can only use SUBSCRIBE, PSUBSCRIBE, UNSUBSCRIBE, PUNSUBSCRIBE, PING and QUIT"));
+        }
+
+        if spec.has_flag("write") && self.replication.is_replica() {
+            return Err(RedisError::ReadOnly);
         }
 
-        if self.monitor.is_active() {
-            let msg = get_message(cmd);
-            self.monitor.send(&msg.serialize())?;
+        if self.monitor.is_active() && !spec.has_flag("admin") {
+            self.monitor.feed(cmd, client.addr());
+        }
+
+        match &name[..] {
+            "auth" => auth::run(builder, cmd.arguments(), client, &self.acl),
+            "hello" => hello::run(
+                builder,
+                cmd.arguments(),
+                client,
+                &self.acl,
+                &self.replication,
+            ),
+            "acl" => acl::run(builder, cmd.arguments(), client, &self.acl, &self.config),
+            "config" => config::run(
+                cmd.arguments(),
+                builder,
+                self.config.clone(),
+                self.logger.clone(),
+                &self.acl,
+                client.protocol(),
+                &self.stats,
+                &self.data,
+            ),
+            "unsubscribe" => {
+                unsubscribe::run(self.pubsub.clone(), client, builder, cmd.arguments())
+            }
+            "subscribe" => subscribe::run(self.pubsub.clone(), client, builder, cmd.arguments()),
+            "punsubscribe" => punsubscribe::run(self.pubsub.clone(), client, builder),
+            "monitor" => monitor::run(&self.monitor, client, builder),
+            "quit" => quit::run(&self.monitor, client, builder),
+            "client" => client_command::run(builder, cmd.arguments(), client, &self.clients),
+            "replicaof" | "slaveof" => replicaof::run(builder, cmd.arguments(), &self.replication),
+            "replconf" => replconf::run(builder, cmd.arguments(), client, &self.replication),
+            "psync" => psync::run(cmd.arguments(), client, &self.replication, &self.data),
+            "role" => role::run(builder, &self.replication),
+            "cluster" => cluster::run(builder, cmd.arguments(), client, &self.cluster, &self.data),
+            "asking" => asking::run(builder, client, &self.cluster),
+            _ if is_write_command(&name) && !self.replication.is_replica() => {
+                let mut stream = self.replication.lock();
+                self.execute(&name, cmd, builder)?;
+                stream.feed(&replication::encode(cmd));
+                Ok(())
+            }
+            _ => self.execute(&name, cmd, builder),
+        }
+    }
+
+    /// Registers how long a command took in the command statistics, and in the latency monitor
+    /// if it was slower than `latency-monitor-threshold`. Unknown commands are not registered.
+    pub fn record_call(&self, cmd: &Command, duration: Duration, outcome: CallOutcome) {
+        let name = cmd.name().to_ascii_lowercase();
+        if command_table::lookup(&name).is_none() {
+            return;
+        }
+        self.stats.command_called(&name, duration, outcome);
+        if outcome != CallOutcome::Rejected {
+            let threshold = self.config.lock().unwrap().get_latency_monitor_threshold();
+            self.latency.record(COMMAND_EVENT, duration, threshold);
         }
+    }
+
+    /// Runs a command of the replication stream received from the master of this replica.
+    /// The commands run without a client and without a reply, and are passed on to the
+    /// replicas of this server.
+    pub fn replicate(&self, frame: ProtocolType, bytes: &str) -> Result<(), RedisError> {
+        let mut stream = self.replication.lock();
+        stream.feed(bytes);
+        let cmd = Command::from_frame(frame)
+            .map_err(|_| RedisError::Generic("invalid command in the replication stream"))?;
+        let name = cmd.name().to_ascii_lowercase();
+        command_table::lookup(&name)
+            .ok_or_else(|| unknown_command(&cmd))?
+            .check_arity(cmd.arguments().len())?;
+        self.execute(&name, &cmd, &mut ResponseBuilder::new())
+    }
 
-        match &cmd.name().to_ascii_lowercase()[..] {
+    /// Runs the commands that do not depend on the connection that sent them.
+    fn execute(
+        &self,
+        name: &str,
+        cmd: &Command,
+        builder: &mut ResponseBuilder,
+    ) -> Result<(), RedisError> {
+        match name {
             "ping" => ping::run(builder),
-            "info" => info::run(builder, &self.config, &self.sys_time),
+            "command" => command::run(builder, cmd.arguments()),
+            "info" => info::run(
+                builder,
+                cmd.arguments(),
+                &self.config,
+                &self.sys_time,
+                &self.data,
+                &self.stats,
+                &self.replication,
+            ),
+            "slowlog" => slowlog::run(builder, cmd.arguments(), &self.slowlog),
+            "latency" => latency::run(builder, cmd.arguments(), &self.latency),
             "expire" => expire::run(builder, cmd, &self.data),
             "expireat" => expireat::run(builder, cmd.arguments(), &self.data),
             "copy" => copy::run(self.data.clone(), cmd.arguments(), builder),
             "keys" => keys::run(self.data.clone(), cmd.arguments(), builder),
             "rename" => rename::run(self.data.clone(), cmd.arguments(), builder),
+            "renamenx" => renamenx::run(self.data.clone(), cmd.arguments(), builder),
             "persist" => persist::run(self.data.clone(), cmd.arguments(), builder),
-            "config" => config::run(
-                cmd.arguments(),
-                builder,
-                self.config.clone(),
-                self.logger.clone(),
-            ),
             "type" => key_type::run(cmd.arguments(), builder, &self.data),
             "del" => del::run(builder, cmd.arguments(), &self.data),
+            "unlink" => unlink::run(builder, cmd.arguments(), &self.data),
+            "object" => object::run(builder, cmd.arguments(), &self.data),
+            "randomkey" => randomkey::run(builder, cmd.arguments(), &self.data),
+            "dump" => dump::run(builder, cmd.arguments(), &self.data),
+            "restore" => restore::run(builder, cmd.arguments(), &self.data),
             "sort" => sort::run(builder, cmd.arguments(), &self.data),
             "exists" => exists::run(builder, cmd.arguments(), &self.data),
             "ttl" => ttl::run(builder, cmd.arguments(), &self.data),
@@ -104,15 +262,11 @@ impl Execution {
             "getdel" => getdel::run(cmd.arguments(), builder, self.data.clone()),
             "get" => get::run(cmd.arguments(), builder, self.data.clone()),
             "mget" => mget::run(cmd.arguments(), builder, self.data.clone()),
-            "unsubscribe" => {
-                unsubscribe::run(self.pubsub.clone(), client, builder, cmd.arguments())
-            }
-            "subscribe" => subscribe::run(self.pubsub.clone(), client, builder, cmd.arguments()),
             "publish" => publish::run(self.pubsub.clone(), builder, cmd.arguments()),
-            "punsubscribe" => punsubscribe::run(self.pubsub.clone(), client, builder),
             "pubsub" => pubsub::run(self.pubsub.clone(), builder, cmd.arguments()),
             "flushdb" => flushdb::run(builder, self.data.clone()),
             "dbsize" => dbsize::run(builder, self.data.clone()),
+            "memory" => memory::run(builder, cmd.arguments(), &self.data),
             "lpushx" => lpushx::run(builder, cmd.arguments(), self.data.clone()),
             "lset" => lset::run(builder, cmd.arguments(), self.data.clone()),
             "rpushx" => rpushx::run(builder, cmd.arguments(), self.data.clone()),
@@ -129,26 +283,51 @@ impl Execution {
             "scard" => scard::run(builder, cmd.arguments(), self.data.clone()),
             "sadd" => sadd::run(builder, cmd.arguments(), self.data.clone()),
             "lrange" => lrange::run(builder, cmd.arguments(), self.data.clone()),
-            "monitor" => monitor::run(&self.monitor, client, builder),
-            "quit" => quit::run(&self.monitor, client, builder),
-            _ => Err("Unknown command."),
+            "geoadd" => geoadd::run(builder, cmd.arguments(), self.data.clone()),
+            "geopos" => geopos::run(builder, cmd.arguments(), self.data.clone()),
+            "geodist" => geodist::run(builder, cmd.arguments(), self.data.clone()),
+            "geohash" => geohash::run(builder, cmd.arguments(), self.data.clone()),
+            "geosearch" => geosearch::run(builder, cmd.arguments(), self.data.clone()),
+            _ => Err(unknown_command(cmd)),
         }
     }
+
+    /// Checks that the client is authenticated and that its ACL user can run the command.
+    /// Clients that did not call AUTH run as the default user if it needs no password.
+    fn check_permissions(
+        &self,
+        name: &str,
+        cmd: &Command,
+        client: &Client,
+    ) -> Result<(), RedisError> {
+        let user = client.user().or_else(|| {
+            if self.acl.default_user_nopass() {
+                Some(DEFAULT_USER.to_string())
+            } else {
+                None
+            }
+        });
+        let exempt = matches!(name, "auth" | "hello" | "quit");
+        match user {
+            None if !exempt && name != "ping" => Err(RedisError::NoAuth),
+            Some(user) if !exempt && categories::is_command(name) => {
+                let arguments: Vec<String> =
+                    cmd.arguments().iter().map(|x| x.to_string()).collect();
+                self.acl.check(&user, name, &arguments)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns if the command modifies the database, so it is blocked by CLIENT PAUSE WRITE.
+fn is_write_command(name: &str) -> bool {
+    command_table::lookup(name).is_some_and(|x| x.has_flag("write")) || name == "publish"
 }
 
-fn get_message(cmd: &Command) -> ResponseBuilder {
-    let mut command = cmd.name();
-    if command == "COMMAND" {
-        command = "NEW CONNECTION".to_string();
+fn unknown_command(cmd: &Command) -> RedisError {
+    RedisError::UnknownCommand {
+        name: cmd.name(),
+        arguments: cmd.arguments().iter().map(|x| x.to_string()).collect(),
     }
-    let mut arguments: Vec<String> = cmd
-        .arguments()
-        .into_iter()
-        .map(|x| x.string())
-        .collect::<Result<_, _>>()
-        .unwrap();
-    arguments.insert(0, command);
-    let mut msg = ResponseBuilder::new();
-    msg.add(ProtocolType::String(arguments.join(" ")));
-    msg
 }
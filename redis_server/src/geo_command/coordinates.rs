@@ -0,0 +1,169 @@
+use crate::error::RedisError;
+use redis_protocol::types::ProtocolType;
+
+const GEO_STEP: u32 = 26;
+const GEO_LAT_MIN: f64 = -85.05112878;
+const GEO_LAT_MAX: f64 = 85.05112878;
+const GEO_LONG_MIN: f64 = -180.0;
+const GEO_LONG_MAX: f64 = 180.0;
+const STANDARD_LAT_MIN: f64 = -90.0;
+const STANDARD_LAT_MAX: f64 = 90.0;
+const EARTH_RADIUS_IN_METERS: f64 = 6372797.560856;
+const GEO_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Encodes a longitude, latitude pair into a 52 bit interleaved geohash score.
+/// Returns an error if the pair is outside of the supported range.
+pub fn encode(longitude: f64, latitude: f64) -> Result<u64, RedisError> {
+    if !(GEO_LONG_MIN..=GEO_LONG_MAX).contains(&longitude)
+        || !(GEO_LAT_MIN..=GEO_LAT_MAX).contains(&latitude)
+    {
+        return Err(RedisError::Generic("invalid longitude,latitude pair"));
+    }
+    Ok(encode_in_range(
+        longitude,
+        latitude,
+        (GEO_LAT_MIN, GEO_LAT_MAX),
+    ))
+}
+
+/// Decodes a 52 bit geohash score into the longitude, latitude pair at the center of its area.
+pub fn decode(hash: u64) -> (f64, f64) {
+    let lat_scale = GEO_LAT_MAX - GEO_LAT_MIN;
+    let long_scale = GEO_LONG_MAX - GEO_LONG_MIN;
+    let lat_bits = squash(hash) as f64;
+    let long_bits = squash(hash >> 1) as f64;
+    let cells = (1u64 << GEO_STEP) as f64;
+
+    let lat_min = GEO_LAT_MIN + (lat_bits / cells) * lat_scale;
+    let lat_max = GEO_LAT_MIN + ((lat_bits + 1.0) / cells) * lat_scale;
+    let long_min = GEO_LONG_MIN + (long_bits / cells) * long_scale;
+    let long_max = GEO_LONG_MIN + ((long_bits + 1.0) / cells) * long_scale;
+
+    let longitude = ((long_min + long_max) / 2.0).clamp(GEO_LONG_MIN, GEO_LONG_MAX);
+    let latitude = ((lat_min + lat_max) / 2.0).clamp(GEO_LAT_MIN, GEO_LAT_MAX);
+    (longitude, latitude)
+}
+
+/// Returns the standard 11 characters geohash string for a geohash score.
+pub fn to_geohash_string(hash: u64) -> String {
+    let (longitude, latitude) = decode(hash);
+    let bits = encode_in_range(longitude, latitude, (STANDARD_LAT_MIN, STANDARD_LAT_MAX));
+    let total_bits = GEO_STEP * 2;
+    (0..11)
+        .map(|i| {
+            // There are only 52 bits, the last character is assumed to be zero.
+            let idx = if i == 10 {
+                0
+            } else {
+                (bits >> (total_bits - (i + 1) * 5)) & 0x1f
+            };
+            GEO_ALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// Returns the distance in meters between two points using the haversine formula.
+pub fn distance(long_1: f64, lat_1: f64, long_2: f64, lat_2: f64) -> f64 {
+    let lat_1_rad = lat_1.to_radians();
+    let lat_2_rad = lat_2.to_radians();
+    let u = ((lat_2_rad - lat_1_rad) / 2.0).sin();
+    let v = ((long_2 - long_1).to_radians() / 2.0).sin();
+    let a = u * u + lat_1_rad.cos() * lat_2_rad.cos() * v * v;
+    2.0 * EARTH_RADIUS_IN_METERS * a.sqrt().asin()
+}
+
+/// Returns how many meters are in one of the given unit (m, km, mi or ft).
+pub fn unit_to_meters(unit: &str) -> Result<f64, RedisError> {
+    match &unit.to_ascii_lowercase()[..] {
+        "m" => Ok(1.0),
+        "km" => Ok(1000.0),
+        "mi" => Ok(1609.34),
+        "ft" => Ok(0.3048),
+        _ => Err(RedisError::Generic(
+            "unsupported unit provided. please use M, KM, FT, MI",
+        )),
+    }
+}
+
+/// Parses a protocol argument as a floating point number.
+pub fn parse_float(argument: &ProtocolType) -> Result<f64, RedisError> {
+    argument
+        .clone()
+        .string()?
+        .parse::<f64>()
+        .ok()
+        .filter(|x| x.is_finite())
+        .ok_or(RedisError::NotFloat)
+}
+
+/// Formats a distance the same way redis does, with four decimals.
+pub fn format_distance(distance: f64) -> String {
+    format!("{:.4}", distance)
+}
+
+/// Interleaves the longitude and latitude offsets inside the given latitude range.
+fn encode_in_range(longitude: f64, latitude: f64, lat_range: (f64, f64)) -> u64 {
+    let cells = (1u64 << GEO_STEP) as f64;
+    let lat_offset = (latitude - lat_range.0) / (lat_range.1 - lat_range.0);
+    let long_offset = (longitude - GEO_LONG_MIN) / (GEO_LONG_MAX - GEO_LONG_MIN);
+    let lat_bits = ((lat_offset * cells) as u64).min((1u64 << GEO_STEP) - 1);
+    let long_bits = ((long_offset * cells) as u64).min((1u64 << GEO_STEP) - 1);
+    spread(lat_bits) | (spread(long_bits) << 1)
+}
+
+/// Spreads the lower 32 bits of a number so there is a zero between each of them.
+fn spread(value: u64) -> u64 {
+    let mut x = value & 0xFFFF_FFFF;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & 0x5555_5555_5555_5555
+}
+
+/// Reverse of spread, keeps only the even bits of a number and packs them together.
+fn squash(value: u64) -> u64 {
+    let mut x = value & 0x5555_5555_5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_palermo() {
+        assert_eq!(encode(13.361389, 38.115556).unwrap(), 3479099956230698);
+    }
+
+    #[test]
+    fn test_encode_invalid_pair() {
+        assert!(encode(13.361389, 86.0).is_err());
+        assert!(encode(181.0, 38.115556).is_err());
+    }
+
+    #[test]
+    fn test_decode_palermo() {
+        let (longitude, latitude) = decode(3479099956230698);
+        assert!((longitude - 13.361389).abs() < 0.00001);
+        assert!((latitude - 38.115556).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_geohash_string() {
+        let hash = encode(13.361389, 38.115556).unwrap();
+        assert_eq!(to_geohash_string(hash), "sqc8b49rny0");
+        let hash = encode(15.087269, 37.502669).unwrap();
+        assert_eq!(to_geohash_string(hash), "sqdtr74hyu0");
+    }
+
+    #[test]
+    fn test_distance_palermo_catania() {
+        let distance = distance(13.361389, 38.115556, 15.087269, 37.502669);
+        assert!((distance - 166274.1516).abs() < 1.0);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::RedisError;
+use crate::geo_command::coordinates::{encode, parse_float};
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Adds the specified geospatial items (longitude, latitude, name) to the specified key.
+/// NX only adds new elements, XX only updates existing ones and CH makes the
+/// reply count the changed elements in addition to the added ones.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.len() < 4 {
+        return Err(RedisError::WrongArity("geoadd"));
+    }
+    let key = arguments[0].clone().string()?;
+
+    let mut nx = false;
+    let mut xx = false;
+    let mut ch = false;
+    let mut pos = 1;
+    while pos < arguments.len() {
+        match &arguments[pos].clone().string()?.to_ascii_lowercase()[..] {
+            "nx" => nx = true,
+            "xx" => xx = true,
+            "ch" => ch = true,
+            _ => break,
+        }
+        pos += 1;
+    }
+    if nx && xx {
+        return Err(RedisError::Generic(
+            "XX and NX options at the same time are not compatible",
+        ));
+    }
+    if pos == arguments.len() || !(arguments.len() - pos).is_multiple_of(3) {
+        return Err(RedisError::Syntax);
+    }
+
+    let mut members = Vec::new();
+    for item in arguments[pos..].chunks(3) {
+        let longitude = parse_float(&item[0])?;
+        let latitude = parse_float(&item[1])?;
+        let member = item[2].clone().string()?;
+        members.push((member, encode(longitude, latitude)?));
+    }
+
+    let (added, changed) = data.geoadd(key, members, nx, xx)?;
+    if ch {
+        builder.add(ProtocolType::Integer(added + changed));
+    } else {
+        builder.add(ProtocolType::Integer(added));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn palermo_catania() -> Vec<ProtocolType> {
+        vec![
+            ProtocolType::String("Sicily".to_string()),
+            ProtocolType::String("13.361389".to_string()),
+            ProtocolType::String("38.115556".to_string()),
+            ProtocolType::String("Palermo".to_string()),
+            ProtocolType::String("15.087269".to_string()),
+            ProtocolType::String("37.502669".to_string()),
+            ProtocolType::String("Catania".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_geoadd_two_members() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        run(&mut builder, palermo_catania(), data.clone()).unwrap();
+
+        assert_eq!(builder.serialize(), ":2\r\n");
+        assert_eq!(
+            data.geo_members("Sicily").unwrap().get("Palermo"),
+            Some(&3479099956230698)
+        );
+    }
+
+    #[test]
+    fn test_geoadd_existing_members_returns_zero() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        run(&mut builder, palermo_catania(), data.clone()).unwrap();
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, palermo_catania(), data).unwrap();
+
+        assert_eq!(builder.serialize(), ":0\r\n");
+    }
+
+    #[test]
+    fn test_geoadd_xx_ch_counts_changed() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, palermo_catania(), data.clone()).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("XX".to_string()),
+                ProtocolType::String("CH".to_string()),
+                ProtocolType::String("13.5".to_string()),
+                ProtocolType::String("38.1".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("13.5".to_string()),
+                ProtocolType::String("38.1".to_string()),
+                ProtocolType::String("Agrigento".to_string()),
+            ],
+            data.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), ":1\r\n");
+        assert_eq!(data.geo_members("Sicily").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_geoadd_invalid_pair_fails() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("13.361389".to_string()),
+                ProtocolType::String("89".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+            ],
+            data.clone(),
+        );
+
+        assert_eq!(
+            result,
+            Err(RedisError::Generic("invalid longitude,latitude pair"))
+        );
+        assert!(data.exists_key("Sicily").is_err());
+    }
+
+    #[test]
+    fn test_geoadd_wrong_type_fails() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("Sicily", Value::String("value".to_string()))
+            .unwrap();
+
+        assert!(run(&mut builder, palermo_catania(), data).is_err());
+    }
+}
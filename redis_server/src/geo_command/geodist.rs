@@ -0,0 +1,113 @@
+use crate::error::RedisError;
+use crate::geo_command::coordinates::{decode, distance, format_distance, unit_to_meters};
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Returns the distance between two members of a geospatial index in the given unit (meters by default).
+/// If one or both members are missing nil is returned.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.len() != 3 && arguments.len() != 4 {
+        return Err(RedisError::WrongArity("geodist"));
+    }
+    let key = arguments[0].clone().string()?;
+    let unit = match arguments.get(3) {
+        Some(unit) => unit_to_meters(&unit.clone().string()?)?,
+        None => 1.0,
+    };
+
+    let index = data.geo_members(&key)?;
+    let first = index.get(&arguments[1].clone().string()?);
+    let second = index.get(&arguments[2].clone().string()?);
+    match (first, second) {
+        (Some(first), Some(second)) => {
+            let (long_1, lat_1) = decode(*first);
+            let (long_2, lat_2) = decode(*second);
+            let meters = distance(long_1, lat_1, long_2, lat_2);
+            builder.add(ProtocolType::String(format_distance(meters / unit)));
+        }
+        _ => builder.add(ProtocolType::Nil()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo_command::coordinates::encode;
+
+    fn sicily() -> Arc<DataStorage> {
+        let data = Arc::new(DataStorage::new());
+        data.geoadd(
+            "Sicily".to_string(),
+            vec![
+                ("Palermo".to_string(), encode(13.361389, 38.115556).unwrap()),
+                ("Catania".to_string(), encode(15.087269, 37.502669).unwrap()),
+            ],
+            false,
+            false,
+        )
+        .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_geodist_in_meters() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("Catania".to_string()),
+            ],
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), "$11\r\n166274.1516\r\n");
+    }
+
+    #[test]
+    fn test_geodist_in_kilometers() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("Catania".to_string()),
+                ProtocolType::String("km".to_string()),
+            ],
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), "$8\r\n166.2742\r\n");
+    }
+
+    #[test]
+    fn test_geodist_missing_member() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("Rome".to_string()),
+            ],
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), "$-1\r\n");
+    }
+}
@@ -0,0 +1,70 @@
+use crate::error::RedisError;
+use crate::geo_command::coordinates::to_geohash_string;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Returns the standard geohash string of every specified member of the geospatial index.
+/// Non existing members are reported as nil.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("geohash"));
+    }
+    let key = arguments[0].clone().string()?;
+    let index = data.geo_members(&key)?;
+
+    let mut response = Vec::new();
+    for member in arguments[1..].iter() {
+        match index.get(&member.clone().string()?) {
+            Some(hash) => response.push(ProtocolType::String(to_geohash_string(*hash))),
+            None => response.push(ProtocolType::Nil()),
+        }
+    }
+
+    builder.add(ProtocolType::Array(response));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo_command::coordinates::encode;
+
+    #[test]
+    fn test_geohash_two_members() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.geoadd(
+            "Sicily".to_string(),
+            vec![
+                ("Palermo".to_string(), encode(13.361389, 38.115556).unwrap()),
+                ("Catania".to_string(), encode(15.087269, 37.502669).unwrap()),
+            ],
+            false,
+            false,
+        )
+        .unwrap();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("Catania".to_string()),
+                ProtocolType::String("Rome".to_string()),
+            ],
+            data,
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*3\r\n$11\r\nsqc8b49rny0\r\n$11\r\nsqdtr74hyu0\r\n$-1\r\n"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::RedisError;
+use crate::geo_command::coordinates::decode;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Returns the positions (longitude, latitude) of all the specified members of the geospatial index.
+/// Non existing members are reported as a null array.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("geopos"));
+    }
+    let key = arguments[0].clone().string()?;
+    let index = data.geo_members(&key)?;
+
+    let mut response = Vec::new();
+    for member in arguments[1..].iter() {
+        match index.get(&member.clone().string()?) {
+            Some(hash) => {
+                let (longitude, latitude) = decode(*hash);
+                response.push(ProtocolType::Array(vec![
+                    ProtocolType::String(longitude.to_string()),
+                    ProtocolType::String(latitude.to_string()),
+                ]));
+            }
+            None => response.push(ProtocolType::NilArray()),
+        }
+    }
+
+    builder.add(ProtocolType::Array(response));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo_command::coordinates::encode;
+
+    #[test]
+    fn test_geopos_existing_and_missing_member() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        let hash = encode(13.361389, 38.115556).unwrap();
+        data.geoadd(
+            "Sicily".to_string(),
+            vec![("Palermo".to_string(), hash)],
+            false,
+            false,
+        )
+        .unwrap();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+                ProtocolType::String("NonExisting".to_string()),
+            ],
+            data,
+        )
+        .unwrap();
+
+        let (longitude, latitude) = decode(hash);
+        assert_eq!(
+            builder.serialize(),
+            format!(
+                "*2\r\n*2\r\n${}\r\n{}\r\n${}\r\n{}\r\n*-1\r\n",
+                longitude.to_string().len(),
+                longitude,
+                latitude.to_string().len(),
+                latitude
+            )
+        );
+    }
+
+    #[test]
+    fn test_geopos_missing_key() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("Sicily".to_string()),
+                ProtocolType::String("Palermo".to_string()),
+            ],
+            data,
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), "*1\r\n*-1\r\n");
+    }
+}
@@ -0,0 +1,380 @@
+use crate::error::RedisError;
+use crate::geo_command::coordinates::{
+    decode, distance, encode, format_distance, parse_float, unit_to_meters,
+};
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+enum Shape {
+    Radius(f64),
+    Box(f64, f64),
+}
+
+struct Search {
+    from_member: Option<String>,
+    from_lonlat: Option<(f64, f64)>,
+    shape: Option<Shape>,
+    unit: f64,
+    order: Option<Ordering>,
+    count: Option<usize>,
+    any: bool,
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+}
+
+/// Returns the members of a geospatial index which are within the borders of the area
+/// specified by a center (FROMMEMBER or FROMLONLAT) and a shape (BYRADIUS or BYBOX).
+/// Results can be sorted (ASC or DESC), limited (COUNT [ANY]) and extended with
+/// WITHCOORD, WITHDIST and WITHHASH.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.len() < 2 {
+        return Err(RedisError::WrongArity("geosearch"));
+    }
+    let key = arguments[0].clone().string()?;
+    let search = parse_search(&arguments[1..])?;
+    let index = data.geo_members(&key)?;
+
+    let (center_long, center_lat) = match (&search.from_member, search.from_lonlat) {
+        (Some(member), None) => match index.get(member) {
+            Some(hash) => decode(*hash),
+            None => {
+                return Err(RedisError::Generic(
+                    "could not decode requested zset member",
+                ))
+            }
+        },
+        (None, Some(lonlat)) => lonlat,
+        _ => {
+            return Err(RedisError::Generic(
+                "exactly one of FROMMEMBER or FROMLONLAT can be specified for GEOSEARCH",
+            ))
+        }
+    };
+    let shape = search.shape.as_ref().ok_or(RedisError::Generic(
+        "exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH",
+    ))?;
+
+    let mut found = Vec::new();
+    for (member, hash) in index.iter() {
+        let (long, lat) = decode(*hash);
+        let meters = distance(center_long, center_lat, long, lat);
+        let inside = match shape {
+            Shape::Radius(radius) => meters <= *radius,
+            Shape::Box(width, height) => {
+                distance(center_long, center_lat, center_long, lat) <= height / 2.0
+                    && distance(center_long, lat, long, lat) <= width / 2.0
+            }
+        };
+        if inside {
+            found.push((member.clone(), *hash, meters, long, lat));
+            if search.any && search.order.is_none() && Some(found.len()) == search.count {
+                break;
+            }
+        }
+    }
+
+    // Members at the same distance are ordered by name so replies are deterministic.
+    if let Some(order) = search.order {
+        found.sort_by(|a, b| {
+            let ascending = a.2.partial_cmp(&b.2).unwrap().then_with(|| a.0.cmp(&b.0));
+            if order == Ordering::Greater {
+                ascending.reverse()
+            } else {
+                ascending
+            }
+        });
+    }
+    if let Some(count) = search.count {
+        found.truncate(count);
+    }
+
+    let with_any = search.with_coord || search.with_dist || search.with_hash;
+    let response = found
+        .into_iter()
+        .map(|(member, hash, meters, long, lat)| {
+            if !with_any {
+                return ProtocolType::String(member);
+            }
+            let mut item = vec![ProtocolType::String(member)];
+            if search.with_dist {
+                item.push(ProtocolType::String(format_distance(meters / search.unit)));
+            }
+            if search.with_hash {
+                item.push(ProtocolType::Integer(hash as i64));
+            }
+            if search.with_coord {
+                item.push(ProtocolType::Array(vec![
+                    ProtocolType::String(long.to_string()),
+                    ProtocolType::String(lat.to_string()),
+                ]));
+            }
+            ProtocolType::Array(item)
+        })
+        .collect();
+
+    builder.add(ProtocolType::Array(response));
+    Ok(())
+}
+
+fn parse_search(arguments: &[ProtocolType]) -> Result<Search, RedisError> {
+    let mut search = Search {
+        from_member: None,
+        from_lonlat: None,
+        shape: None,
+        unit: 1.0,
+        order: None,
+        count: None,
+        any: false,
+        with_coord: false,
+        with_dist: false,
+        with_hash: false,
+    };
+    const CENTER_ERROR: RedisError = RedisError::Generic(
+        "exactly one of FROMMEMBER or FROMLONLAT can be specified for GEOSEARCH",
+    );
+    const SHAPE_ERROR: RedisError =
+        RedisError::Generic("exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH");
+
+    let mut pos = 0;
+    while pos < arguments.len() {
+        let remaining = arguments.len() - pos - 1;
+        match &arguments[pos].clone().string()?.to_ascii_lowercase()[..] {
+            "frommember" if remaining >= 1 => {
+                if search.from_member.is_some() || search.from_lonlat.is_some() {
+                    return Err(CENTER_ERROR);
+                }
+                search.from_member = Some(arguments[pos + 1].clone().string()?);
+                pos += 1;
+            }
+            "fromlonlat" if remaining >= 2 => {
+                if search.from_member.is_some() || search.from_lonlat.is_some() {
+                    return Err(CENTER_ERROR);
+                }
+                let longitude = parse_float(&arguments[pos + 1])?;
+                let latitude = parse_float(&arguments[pos + 2])?;
+                // Validates the pair the same way GEOADD does.
+                encode(longitude, latitude)?;
+                search.from_lonlat = Some((longitude, latitude));
+                pos += 2;
+            }
+            "byradius" if remaining >= 2 => {
+                if search.shape.is_some() {
+                    return Err(SHAPE_ERROR);
+                }
+                let radius = parse_float(&arguments[pos + 1])?;
+                if radius < 0.0 {
+                    return Err(RedisError::Generic("radius cannot be negative"));
+                }
+                search.unit = unit_to_meters(&arguments[pos + 2].clone().string()?)?;
+                search.shape = Some(Shape::Radius(radius * search.unit));
+                pos += 2;
+            }
+            "bybox" if remaining >= 3 => {
+                if search.shape.is_some() {
+                    return Err(SHAPE_ERROR);
+                }
+                let width = parse_float(&arguments[pos + 1])?;
+                let height = parse_float(&arguments[pos + 2])?;
+                if width < 0.0 || height < 0.0 {
+                    return Err(RedisError::Generic("height or width cannot be negative"));
+                }
+                search.unit = unit_to_meters(&arguments[pos + 3].clone().string()?)?;
+                search.shape = Some(Shape::Box(width * search.unit, height * search.unit));
+                pos += 3;
+            }
+            "asc" => search.order = Some(Ordering::Less),
+            "desc" => search.order = Some(Ordering::Greater),
+            "count" if remaining >= 1 => {
+                let count = arguments[pos + 1]
+                    .clone()
+                    .string()?
+                    .parse::<i64>()
+                    .ok()
+                    .ok_or(RedisError::NotInteger)?;
+                if count <= 0 {
+                    return Err(RedisError::Generic("COUNT must be > 0"));
+                }
+                search.count = Some(count as usize);
+                pos += 1;
+                if remaining >= 2
+                    && arguments[pos + 1]
+                        .clone()
+                        .string()?
+                        .eq_ignore_ascii_case("any")
+                {
+                    search.any = true;
+                    pos += 1;
+                }
+            }
+            "withcoord" => search.with_coord = true,
+            "withdist" => search.with_dist = true,
+            "withhash" => search.with_hash = true,
+            _ => return Err(RedisError::Syntax),
+        }
+        pos += 1;
+    }
+
+    if search.any && search.count.is_none() {
+        return Err(RedisError::Generic(
+            "the ANY argument requires COUNT argument",
+        ));
+    }
+    // A COUNT without ANY needs the closest elements, so the result is sorted ascending.
+    if search.count.is_some() && !search.any && search.order.is_none() {
+        search.order = Some(Ordering::Less);
+    }
+    Ok(search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sicily() -> Arc<DataStorage> {
+        let data = Arc::new(DataStorage::new());
+        data.geoadd(
+            "Sicily".to_string(),
+            vec![
+                ("Palermo".to_string(), encode(13.361389, 38.115556).unwrap()),
+                ("Catania".to_string(), encode(15.087269, 37.502669).unwrap()),
+                ("edge1".to_string(), encode(12.758489, 38.788135).unwrap()),
+                ("edge2".to_string(), encode(17.241510, 38.788135).unwrap()),
+            ],
+            false,
+            false,
+        )
+        .unwrap();
+        data
+    }
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_geosearch_by_radius_with_dist() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            arguments(&[
+                "Sicily",
+                "FROMLONLAT",
+                "15",
+                "37",
+                "BYRADIUS",
+                "200",
+                "km",
+                "ASC",
+                "WITHDIST",
+            ]),
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*2\r\n*2\r\n$7\r\nCatania\r\n$7\r\n56.4413\r\n*2\r\n$7\r\nPalermo\r\n$8\r\n190.4424\r\n"
+        );
+    }
+
+    #[test]
+    fn test_geosearch_by_box_desc() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            arguments(&[
+                "Sicily",
+                "FROMLONLAT",
+                "15",
+                "37",
+                "BYBOX",
+                "400",
+                "400",
+                "km",
+                "DESC",
+            ]),
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*4\r\n$5\r\nedge1\r\n$5\r\nedge2\r\n$7\r\nPalermo\r\n$7\r\nCatania\r\n"
+        );
+    }
+
+    #[test]
+    fn test_geosearch_from_member_with_count() {
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            arguments(&[
+                "Sicily",
+                "FROMMEMBER",
+                "Palermo",
+                "BYRADIUS",
+                "500",
+                "km",
+                "COUNT",
+                "2",
+            ]),
+            sicily(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*2\r\n$7\r\nPalermo\r\n$5\r\nedge1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_geosearch_missing_shape_fails() {
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(
+            &mut builder,
+            arguments(&["Sicily", "FROMMEMBER", "Palermo"]),
+            sicily(),
+        );
+
+        assert_eq!(
+            result,
+            Err(RedisError::Generic(
+                "exactly one of BYRADIUS and BYBOX can be specified for GEOSEARCH"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_geosearch_missing_member_fails() {
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(
+            &mut builder,
+            arguments(&["Sicily", "FROMMEMBER", "Rome", "BYRADIUS", "10", "km"]),
+            sicily(),
+        );
+
+        assert_eq!(
+            result,
+            Err(RedisError::Generic(
+                "could not decode requested zset member"
+            ))
+        );
+    }
+}
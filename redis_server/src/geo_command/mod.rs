@@ -0,0 +1,6 @@
+pub mod coordinates;
+pub mod geoadd;
+pub mod geodist;
+pub mod geohash;
+pub mod geopos;
+pub mod geosearch;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,9 +9,9 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("copy"));
     }
 
     let src = arguments[0].clone().string()?;
@@ -28,7 +29,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,7 +9,7 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut string_arguments = vec![];
 
     for argument in arguments {
@@ -30,7 +31,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
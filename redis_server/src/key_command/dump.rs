@@ -0,0 +1,65 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::dump;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Serializes the value stored at key and returns it, so it can be loaded back
+/// with RESTORE in this or another server. Returns nil if the key does not exist.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.len() != 1 {
+        return Err(RedisError::WrongArity("dump"));
+    }
+    let key = arguments[0].clone().string()?;
+    match data.get(&key) {
+        Some(value) => builder.add(ProtocolType::String(dump::serialize(&value))),
+        None => builder.add(ProtocolType::Nil()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    #[test]
+    fn test_dump_existing_key() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("key", Value::String("value".to_string())).unwrap();
+
+        run(
+            &mut builder,
+            vec![ProtocolType::String("key".to_string())],
+            &data,
+        )
+        .unwrap();
+
+        let payload = dump::serialize(&Value::String("value".to_string()));
+        assert_eq!(
+            builder.serialize(),
+            format!("${}\r\n{}\r\n", payload.len(), payload)
+        );
+    }
+
+    #[test]
+    fn test_dump_missing_key() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            vec![ProtocolType::String("key".to_string())],
+            &data,
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), "$-1\r\n");
+    }
+}
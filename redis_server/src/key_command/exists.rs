@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,14 +9,16 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut string_arguments = vec![];
 
     for argument in arguments {
         match argument.clone().string() {
             Ok(s) => string_arguments.push(s),
             Err(_s) => {
-                return Err("While parsing argument in exists command");
+                return Err(RedisError::Generic(
+                    "While parsing argument in exists command",
+                ));
             }
         };
     }
@@ -35,7 +38,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
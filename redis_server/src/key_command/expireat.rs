@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -10,15 +11,17 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("expireat"));
     }
 
     let key = match arguments[0].clone().string() {
         Ok(s) => s,
         Err(_s) => {
-            return Err("While parsing key in set_expiration in expire command");
+            return Err(RedisError::Generic(
+                "While parsing key in set_expiration in expire command",
+            ));
         }
     };
 
@@ -26,7 +29,9 @@ pub fn run(
         Ok(s) => s,
         Err(_s) => {
             println!("{:?}", _s);
-            return Err("While parsing seconds in set_expiration in expire command");
+            return Err(RedisError::Generic(
+                "While parsing seconds in set_expiration in expire command",
+            ));
         }
     };
 
@@ -42,10 +47,10 @@ pub fn run(
         let actual_time = SystemTime::now();
         let expiration_time = actual_time
             .checked_add(Duration::from_secs(seconds as u64))
-            .ok_or("Failed to calculate expiration time")?
+            .ok_or(RedisError::Generic("Failed to calculate expiration time"))?
             .duration_since(UNIX_EPOCH)
             .ok()
-            .ok_or("Failed to calculate expiration time")?;
+            .ok_or(RedisError::Generic("Failed to calculate expiration time"))?;
         match data.set_expiration_to_key(Some(expiration_time), &key) {
             Ok(s) => builder.add(ProtocolType::Integer((s as i32).into())),
             Err(_s) => builder.add(ProtocolType::Integer(0)),
@@ -56,7 +61,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
@@ -10,9 +11,9 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("ERR wrong number of arguments for 'type' command");
+        return Err(RedisError::WrongArity("type"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -23,13 +24,13 @@ pub fn run(
         Some(Value::String(_)) => builder.add(ProtocolType::SimpleString("string".to_string())),
         Some(Value::Vec(_)) => builder.add(ProtocolType::SimpleString("vec".to_string())),
         Some(Value::HashSet(_)) => builder.add(ProtocolType::SimpleString("set".to_string())),
+        Some(Value::Geo(_)) => builder.add(ProtocolType::SimpleString("zset".to_string())),
         None => builder.add(ProtocolType::SimpleString("none".to_string())),
     }
     Ok(())
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,9 +10,9 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("keys"));
     }
 
     // We need to append ^$ to the regex in order to force the engine to match the start and end of the word.
@@ -22,7 +23,9 @@ pub fn run(
     pattern_str = pattern_str.replace("?", ".");
     pattern_str = pattern_str.replace("*", ".*");
     let pattern = format!("^{}$", pattern_str);
-    let re = Regex::new(&pattern).ok().ok_or("Error parsing the regex")?;
+    let re = Regex::new(&pattern)
+        .ok()
+        .ok_or(RedisError::Generic("Error parsing the regex"))?;
     let all_keys = db.get_keys();
 
     builder.add(ProtocolType::Array(
@@ -36,7 +39,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
@@ -45,7 +47,7 @@ mod tests {
 
     fn get_test_data() -> (Arc<DataStorage>, ResponseBuilder) {
         let data = Arc::new(DataStorage::new());
-        for x in vec![
+        for x in [
             "firstname",
             "Jack",
             "lastname",
@@ -57,7 +59,7 @@ mod tests {
         ] {
             data.set(x, Value::String("value".to_string())).unwrap();
         }
-        return (data, ResponseBuilder::new());
+        (data, ResponseBuilder::new())
     }
 
     fn run_command(data: Arc<DataStorage>, builder: &mut ResponseBuilder, pattern: &str) {
@@ -1,12 +1,18 @@
 pub mod copy;
 pub mod del;
+pub mod dump;
 pub mod exists;
 pub mod expire;
 pub mod expireat;
 pub mod key_type;
 pub mod keys;
+pub mod object;
 pub mod persist;
+pub mod randomkey;
 pub mod rename;
+pub mod renamenx;
+pub mod restore;
 pub mod sort;
 pub mod touch;
 pub mod ttl;
+pub mod unlink;
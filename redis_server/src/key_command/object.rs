@@ -0,0 +1,201 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::data_storage::Value;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MAX_LISTPACK_ENTRIES: usize = 128;
+const MAX_LISTPACK_VALUE: usize = 64;
+const MAX_INTSET_ENTRIES: usize = 512;
+const MAX_EMBSTR_LEN: usize = 44;
+const MAX_FREQ: u64 = 255;
+
+/// Inspects the internals of the value stored at key, without modifying its last access.
+/// Supported subcommands are ENCODING, IDLETIME, FREQ and HELP.
+/// Nil is returned if the key does not exist.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("object"));
+    }
+    let subcommand = arguments[0].clone().string()?.to_ascii_lowercase();
+    if subcommand == "help" {
+        builder.add(help());
+        return Ok(());
+    }
+    if arguments.len() != 2 {
+        return Err(RedisError::Generic(
+            "Unknown subcommand or wrong number of arguments. Try OBJECT HELP.",
+        ));
+    }
+
+    let key = arguments[1].clone().string()?;
+    let entry = match data.peek_entry(&key) {
+        Some(entry) => entry,
+        None => {
+            builder.add(ProtocolType::Nil());
+            return Ok(());
+        }
+    };
+
+    match &subcommand[..] {
+        "encoding" => builder.add(ProtocolType::String(encoding(&entry.value()?).to_string())),
+        "idletime" => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .ok_or(RedisError::Generic("Cannot get actual timestamp"))?;
+            let idle = now.saturating_sub(entry.last_access()?);
+            builder.add(ProtocolType::Integer(idle.as_secs() as i64));
+        }
+        "freq" => builder.add(ProtocolType::Integer(
+            entry.access_count().min(MAX_FREQ) as i64
+        )),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try OBJECT HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name redis would use for the internal encoding of the value.
+fn encoding(value: &Value) -> &'static str {
+    match value {
+        Value::String(string) => {
+            if string.len() <= 20 && string.parse::<i64>().is_ok() {
+                "int"
+            } else if string.len() <= MAX_EMBSTR_LEN {
+                "embstr"
+            } else {
+                "raw"
+            }
+        }
+        Value::Vec(vec) => {
+            if is_small(vec.iter(), vec.len()) {
+                "listpack"
+            } else {
+                "quicklist"
+            }
+        }
+        Value::HashSet(set) => {
+            if set.len() <= MAX_INTSET_ENTRIES && set.iter().all(|x| x.parse::<i64>().is_ok()) {
+                "intset"
+            } else if is_small(set.iter(), set.len()) {
+                "listpack"
+            } else {
+                "hashtable"
+            }
+        }
+        Value::Geo(index) => {
+            if is_small(index.keys(), index.len()) {
+                "listpack"
+            } else {
+                "skiplist"
+            }
+        }
+    }
+}
+
+/// Small collections of short values are stored by redis in a compact listpack.
+fn is_small<'a>(mut values: impl Iterator<Item = &'a String>, len: usize) -> bool {
+    len <= MAX_LISTPACK_ENTRIES && values.all(|x| x.len() <= MAX_LISTPACK_VALUE)
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "OBJECT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "ENCODING <key>",
+        "    Return the kind of internal representation used in order to store the value",
+        "    associated with a <key>.",
+        "FREQ <key>",
+        "    Return the access frequency index of the <key>.",
+        "IDLETIME <key>",
+        "    Return the idle time of the <key>, that is the approximated number of",
+        "    seconds elapsed since the last access to the key.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn query(data: &Arc<DataStorage>, subcommand: &str, key: &str) -> String {
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String(subcommand.to_string()),
+                ProtocolType::String(key.to_string()),
+            ],
+            data,
+        )
+        .unwrap();
+        builder.serialize()
+    }
+
+    #[test]
+    fn test_object_encoding() {
+        let data = Arc::new(DataStorage::new());
+        data.set("int", Value::String("123".to_string())).unwrap();
+        data.set("embstr", Value::String("hola".to_string()))
+            .unwrap();
+        data.set("raw", Value::String("a".repeat(45))).unwrap();
+        data.set("list", Value::Vec(vec!["a".to_string()])).unwrap();
+        let mut set = HashSet::new();
+        set.insert("1".to_string());
+        data.set("intset", Value::HashSet(set)).unwrap();
+
+        assert_eq!(query(&data, "ENCODING", "int"), "$3\r\nint\r\n");
+        assert_eq!(query(&data, "ENCODING", "embstr"), "$6\r\nembstr\r\n");
+        assert_eq!(query(&data, "ENCODING", "raw"), "$3\r\nraw\r\n");
+        assert_eq!(query(&data, "ENCODING", "list"), "$8\r\nlistpack\r\n");
+        assert_eq!(query(&data, "ENCODING", "intset"), "$6\r\nintset\r\n");
+        assert_eq!(query(&data, "ENCODING", "missing"), "$-1\r\n");
+    }
+
+    #[test]
+    fn test_object_idletime_and_freq() {
+        let data = Arc::new(DataStorage::new());
+        data.set("key", Value::String("value".to_string())).unwrap();
+
+        assert_eq!(query(&data, "IDLETIME", "key"), ":0\r\n");
+        assert_eq!(query(&data, "FREQ", "key"), ":0\r\n");
+        data.get("key");
+        data.get("key");
+        assert_eq!(query(&data, "FREQ", "key"), ":2\r\n");
+    }
+
+    #[test]
+    fn test_object_unknown_subcommand() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(
+            &mut builder,
+            vec![
+                ProtocolType::String("REFCOUNT".to_string()),
+                ProtocolType::String("key".to_string()),
+                ProtocolType::String("other".to_string()),
+            ],
+            &data,
+        );
+
+        assert!(result.is_err());
+    }
+}
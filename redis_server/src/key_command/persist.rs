@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,15 +10,15 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("persist"));
     }
 
     let src = arguments[0].clone().string()?;
 
     let mut result = 0;
-    let (duration_maybe, _) = db.get_with_expiration(&src).ok_or("Key not found")?;
+    let (duration_maybe, _) = db.get_with_expiration(&src).ok_or(RedisError::NoSuchKey)?;
     if duration_maybe.is_some() {
         result = 1;
         db.set_expiration_to_key(None, &src)?;
@@ -27,7 +28,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
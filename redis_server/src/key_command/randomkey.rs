@@ -0,0 +1,53 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Returns a random key from the database, or nil when the database is empty.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if !arguments.is_empty() {
+        return Err(RedisError::WrongArity("randomkey"));
+    }
+    match data.random_key() {
+        Some(key) => builder.add(ProtocolType::String(key)),
+        None => builder.add(ProtocolType::Nil()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    #[test]
+    fn test_randomkey_empty_database() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        run(&mut builder, vec![], &data).unwrap();
+
+        assert_eq!(builder.serialize(), "$-1\r\n");
+    }
+
+    #[test]
+    fn test_randomkey_returns_existing_key() {
+        let data = Arc::new(DataStorage::new());
+        data.set("key1", Value::String("value".to_string()))
+            .unwrap();
+        data.set("key2", Value::String("value".to_string()))
+            .unwrap();
+
+        for _ in 0..10 {
+            let mut builder = ResponseBuilder::new();
+            run(&mut builder, vec![], &data).unwrap();
+            let response = builder.serialize();
+            assert!(response == "$4\r\nkey1\r\n" || response == "$4\r\nkey2\r\n");
+        }
+    }
+}
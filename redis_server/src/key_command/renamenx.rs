@@ -0,0 +1,75 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Renames key to newkey if newkey does not yet exist. It returns an error when key does not exist.
+/// Returns 1 if key was renamed and 0 if newkey already exists.
+pub fn run(
+    db: Arc<DataStorage>,
+    arguments: Vec<ProtocolType>,
+    builder: &mut ResponseBuilder,
+) -> Result<(), RedisError> {
+    if arguments.len() != 2 {
+        return Err(RedisError::WrongArity("renamenx"));
+    }
+
+    let src = arguments[0].clone().string()?;
+    let dst = arguments[1].clone().string()?;
+
+    let renamed = db.renamenx(&src, &dst).or(Err(RedisError::NoSuchKey))?;
+    builder.add(ProtocolType::Integer(renamed as i64));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn arguments() -> Vec<ProtocolType> {
+        vec![
+            ProtocolType::String("key".to_string()),
+            ProtocolType::String("new_key".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_renamenx() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("key", Value::String("value".to_string())).unwrap();
+
+        run(data.clone(), arguments(), &mut builder).unwrap();
+
+        assert_eq!(data.get("new_key").unwrap().string().unwrap(), "value");
+        assert!(data.get("key").is_none());
+        assert_eq!(builder.serialize(), ":1\r\n");
+    }
+
+    #[test]
+    fn test_renamenx_existing_destination() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("key", Value::String("value".to_string())).unwrap();
+        data.set("new_key", Value::String("other".to_string()))
+            .unwrap();
+
+        run(data.clone(), arguments(), &mut builder).unwrap();
+
+        assert_eq!(data.get("new_key").unwrap().string().unwrap(), "other");
+        assert_eq!(data.get("key").unwrap().string().unwrap(), "value");
+        assert_eq!(builder.serialize(), ":0\r\n");
+    }
+
+    #[test]
+    fn test_renamenx_missing_key() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(data, arguments(), &mut builder);
+
+        assert_eq!(result, Err(RedisError::NoSuchKey));
+    }
+}
@@ -0,0 +1,143 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::dump;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Creates a key associated with a value obtained by deserializing the payload returned by DUMP.
+/// The ttl is in milliseconds, 0 means no expiration. With ABSTTL the ttl is an absolute
+/// unix timestamp in milliseconds. An error is returned if the key exists, unless REPLACE is given.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.len() < 3 {
+        return Err(RedisError::WrongArity("restore"));
+    }
+    let key = arguments[0].clone().string()?;
+    let ttl = arguments[1]
+        .clone()
+        .string()?
+        .parse::<i64>()
+        .ok()
+        .ok_or(RedisError::NotInteger)?;
+    if ttl < 0 {
+        return Err(RedisError::Generic("Invalid TTL value, must be >= 0"));
+    }
+    let payload = arguments[2].clone().string()?;
+
+    let mut replace = false;
+    let mut absttl = false;
+    for argument in arguments[3..].iter() {
+        match &argument.clone().string()?.to_ascii_lowercase()[..] {
+            "replace" => replace = true,
+            "absttl" => absttl = true,
+            _ => return Err(RedisError::Syntax),
+        }
+    }
+
+    let value = dump::deserialize(&payload)?;
+    let expiration = match (ttl, absttl) {
+        (0, _) => None,
+        (ttl, true) => Some(Duration::from_millis(ttl as u64)),
+        (ttl, false) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .ok_or(RedisError::Generic("Cannot get actual timestamp"))?;
+            Some(now + Duration::from_millis(ttl as u64))
+        }
+    };
+
+    data.restore(&key, value, expiration, replace)?;
+    builder.add(ProtocolType::SimpleString("OK".to_string()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn arguments(key: &str, ttl: &str, payload: &str, options: &[&str]) -> Vec<ProtocolType> {
+        let mut arguments = vec![
+            ProtocolType::String(key.to_string()),
+            ProtocolType::String(ttl.to_string()),
+            ProtocolType::String(payload.to_string()),
+        ];
+        for option in options {
+            arguments.push(ProtocolType::String(option.to_string()));
+        }
+        arguments
+    }
+
+    #[test]
+    fn test_restore_new_key() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        let payload = dump::serialize(&Value::Vec(vec!["a".to_string(), "b".to_string()]));
+
+        run(&mut builder, arguments("key", "0", &payload, &[]), &data).unwrap();
+
+        assert_eq!(builder.serialize(), "+OK\r\n");
+        assert_eq!(
+            data.get("key").unwrap().array().unwrap(),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_restore_with_ttl() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        let payload = dump::serialize(&Value::String("value".to_string()));
+
+        run(
+            &mut builder,
+            arguments("key", "10000", &payload, &[]),
+            &data,
+        )
+        .unwrap();
+
+        let (expiration, _) = data.get_with_expiration("key").unwrap();
+        assert!(expiration.is_some());
+    }
+
+    #[test]
+    fn test_restore_existing_key() {
+        let data = Arc::new(DataStorage::new());
+        let payload = dump::serialize(&Value::String("new".to_string()));
+        data.set("key", Value::String("old".to_string())).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        let result = run(&mut builder, arguments("key", "0", &payload, &[]), &data);
+        assert_eq!(result, Err(RedisError::BusyKey));
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments("key", "0", &payload, &["REPLACE"]),
+            &data,
+        )
+        .unwrap();
+        assert_eq!(data.get("key").unwrap().string().unwrap(), "new");
+    }
+
+    #[test]
+    fn test_restore_invalid_payload() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(&mut builder, arguments("key", "0", "garbage", &[]), &data);
+
+        assert_eq!(
+            result,
+            Err(RedisError::Generic(
+                "DUMP payload version or checksum are wrong"
+            ))
+        );
+    }
+}
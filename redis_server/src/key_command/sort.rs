@@ -1,105 +1,254 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
+use std::cmp::Ordering;
 use std::sync::Arc;
 
-/// Returns a sorted vec stored at the given key. If the keys holds a string value
-/// it returns an error.
-/// It has two optionals params:[desc -> descendent, store -> to store the result at the specified key]
+/// Options accepted by the SORT command.
+struct SortOptions {
+    by: Option<String>,
+    get: Vec<String>,
+    limit: Option<(i64, i64)>,
+    desc: bool,
+    alpha: bool,
+    store: Option<String>,
+}
+
+/// Returns or stores the elements contained in the list, set or geo index at key, sorted.
+/// If the key holds a string value it returns an error. A missing key is sorted as an empty list.
+/// Supports the optional params of redis:
+/// [BY pattern] [LIMIT offset count] [GET pattern [GET pattern ...]] [ASC|DESC] [ALPHA] [STORE destination]
+/// A BY pattern without `*` (like `nosort`) skips the sorting. Elements are compared as numbers
+/// unless ALPHA is given, in which case they are compared lexicographically.
+/// When STORE is given the result is saved as a list and its length is returned.
 pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
-    let mut sort_values: Option<Vec<String>> = None;
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("sort"));
+    }
     let key = arguments[0].clone().string()?;
+    let options = parse_options(&arguments[1..])?;
 
-    if arguments.len() == 1 {
-        sort_values = Some(basic_sort(key, data)?);
-    } else {
-        for i in 1..arguments.len() {
-            let second_argument: &str = &arguments[i].to_string().to_ascii_lowercase()[..];
-
-            match second_argument {
-                "desc" => {
-                    sort_values = Some(inverse_sort(key.clone(), data)?);
-                }
-                "store" => {
-                    if i == arguments.len() - 1 {
-                        return Err("No new key specified.");
-                    }
-                    let values = basic_sort(key.clone(), data)?;
-                    let new_key = arguments[i + 1].clone().string()?;
-                    data.set(&new_key, Value::Vec(values.clone()))?;
-                    sort_values = Some(values);
-                }
-                _ => (),
-            }
-        }
+    let mut values = get_values(data, key)?;
+    let dont_sort = matches!(&options.by, Some(pattern) if !pattern.contains('*'));
+    if !dont_sort {
+        sort_values(&mut values, &options, data)?;
     }
+    let values = apply_limit(values, options.limit);
 
-    if let Some(vec) = sort_values {
-        send_result(builder, vec)
+    let result: Vec<Option<String>> = if options.get.is_empty() {
+        values.into_iter().map(Some).collect()
     } else {
-        return Err("Wrong arguments");
+        values
+            .iter()
+            .flat_map(|value| {
+                options
+                    .get
+                    .iter()
+                    .map(move |pattern| lookup_pattern(data, pattern, value))
+            })
+            .collect()
+    };
+
+    match options.store {
+        Some(destination) => {
+            let len = result.len();
+            if result.is_empty() {
+                let _ = data.delete_key(&destination);
+            } else {
+                let stored = result.into_iter().map(|x| x.unwrap_or_default()).collect();
+                data.set(&destination, Value::Vec(stored))?;
+            }
+            builder.add(ProtocolType::Integer(len as i64));
+        }
+        None => send_result(builder, result),
     }
     Ok(())
 }
 
-fn basic_sort(key: String, data: &Arc<DataStorage>) -> Result<Vec<String>, &'static str> {
-    let mut values = get_values(data, key)?;
-    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
-    let new_values = values.into_iter().map(|x| x.to_string()).collect();
-    Ok(new_values)
+fn parse_options(arguments: &[ProtocolType]) -> Result<SortOptions, RedisError> {
+    let mut options = SortOptions {
+        by: None,
+        get: Vec::new(),
+        limit: None,
+        desc: false,
+        alpha: false,
+        store: None,
+    };
+
+    let mut i = 0;
+    while i < arguments.len() {
+        let remaining = arguments.len() - i - 1;
+        match &arguments[i].to_string().to_ascii_lowercase()[..] {
+            "asc" => options.desc = false,
+            "desc" => options.desc = true,
+            "alpha" => options.alpha = true,
+            "by" if remaining >= 1 => {
+                options.by = Some(arguments[i + 1].clone().string()?);
+                i += 1;
+            }
+            "get" if remaining >= 1 => {
+                options.get.push(arguments[i + 1].clone().string()?);
+                i += 1;
+            }
+            "store" if remaining >= 1 => {
+                options.store = Some(arguments[i + 1].clone().string()?);
+                i += 1;
+            }
+            "limit" if remaining >= 2 => {
+                let offset = parse_integer(&arguments[i + 1])?;
+                let count = parse_integer(&arguments[i + 2])?;
+                options.limit = Some((offset, count));
+                i += 2;
+            }
+            _ => return Err(RedisError::Syntax),
+        }
+        i += 1;
+    }
+    Ok(options)
 }
 
-fn inverse_sort(key: String, data: &Arc<DataStorage>) -> Result<Vec<String>, &'static str> {
-    let mut values = get_values(data, key)?;
-    values.sort_by(|a, b| b.partial_cmp(a).unwrap());
-    let new_values = values.into_iter().map(|x| x.to_string()).collect();
-    Ok(new_values)
+fn parse_integer(argument: &ProtocolType) -> Result<i64, RedisError> {
+    argument
+        .clone()
+        .string()?
+        .parse::<i64>()
+        .ok()
+        .ok_or(RedisError::NotInteger)
 }
 
-fn get_values(data: &Arc<DataStorage>, key: String) -> Result<Vec<f64>, &'static str> {
+fn get_values(data: &Arc<DataStorage>, key: String) -> Result<Vec<String>, RedisError> {
     let values = data.get(&key);
     match values {
-        None => Err("None"),
-        Some(Value::String(_)) => {
-            Err("WRONGTYPE Operation against a key holding the wrong kind of value")
+        None => Ok(Vec::new()),
+        Some(Value::String(_)) => Err(RedisError::WrongType),
+        Some(Value::Vec(vec)) => Ok(vec),
+        Some(Value::HashSet(set)) => Ok(set.into_iter().collect()),
+        Some(Value::Geo(index)) => {
+            let mut members: Vec<(String, u64)> = index.into_iter().collect();
+            members.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+            Ok(members.into_iter().map(|(member, _)| member).collect())
         }
-        Some(Value::Vec(vec)) => parse_to_int(vec),
-        Some(Value::HashSet(set)) => {
-            let mut sorted_vec = Vec::new();
-            for element in set.iter() {
-                sorted_vec.push(element.clone());
+    }
+}
+
+/// Sorts the values in place, by their own value or by the weights found with the BY pattern.
+fn sort_values(
+    values: &mut Vec<String>,
+    options: &SortOptions,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    let weights: Vec<Option<String>> = match &options.by {
+        Some(pattern) => values
+            .iter()
+            .map(|value| lookup_pattern(data, pattern, value))
+            .collect(),
+        None => values.iter().cloned().map(Some).collect(),
+    };
+
+    let mut elements: Vec<(String, Option<String>)> = values.drain(..).zip(weights).collect();
+
+    if options.alpha {
+        let by_pattern = options.by.is_some();
+        elements.sort_by(|a, b| {
+            let cmp = if by_pattern {
+                a.1.cmp(&b.1)
+            } else {
+                a.0.cmp(&b.0)
+            };
+            direction(cmp, options.desc)
+        });
+        values.extend(elements.into_iter().map(|(value, _)| value));
+        return Ok(());
+    }
+
+    let mut scored = Vec::new();
+    for (value, weight) in elements.into_iter() {
+        let score = match weight {
+            Some(weight) => parse_to_float(&weight)?,
+            None => 0.0,
+        };
+        scored.push((value, score));
+    }
+    // Elements with the same score are compared lexicographically so the result is deterministic.
+    scored.sort_by(|a, b| {
+        let cmp =
+            a.1.partial_cmp(&b.1)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0));
+        direction(cmp, options.desc)
+    });
+    values.extend(scored.into_iter().map(|(value, _)| value));
+    Ok(())
+}
+
+fn direction(cmp: Ordering, desc: bool) -> Ordering {
+    if desc {
+        cmp.reverse()
+    } else {
+        cmp
+    }
+}
+
+fn parse_to_float(value: &str) -> Result<f64, RedisError> {
+    match value.parse::<f64>() {
+        Ok(nmb) if !nmb.is_nan() => Ok(nmb),
+        _ => Err(RedisError::Generic(
+            "One or more scores can't be converted into double",
+        )),
+    }
+}
+
+/// Applies LIMIT offset count the same way redis does. A negative count returns every
+/// element after the offset.
+fn apply_limit(values: Vec<String>, limit: Option<(i64, i64)>) -> Vec<String> {
+    match limit {
+        None => values,
+        Some((offset, count)) => {
+            let start = offset.max(0) as usize;
+            let iter = values.into_iter().skip(start);
+            if count < 0 {
+                iter.collect()
+            } else {
+                iter.take(count as usize).collect()
             }
-            parse_to_int(sorted_vec)
         }
     }
 }
 
-fn parse_to_int(vec: Vec<String>) -> Result<Vec<f64>, &'static str> {
-    let mut new_vec: Vec<f64> = Vec::new();
-    for i in vec.into_iter() {
-        match i.parse() {
-            Ok(nmb) => new_vec.push(nmb),
-            Err(_) => return Err("ERR One or more scores can't be converted into double"),
-        }
+/// Replaces the first `*` of the pattern with the value and returns the string stored at
+/// the resulting key. The `#` pattern returns the value itself.
+fn lookup_pattern(data: &Arc<DataStorage>, pattern: &str, value: &str) -> Option<String> {
+    if pattern == "#" {
+        return Some(value.to_string());
+    }
+    if !pattern.contains('*') {
+        return None;
+    }
+    let key = pattern.replacen('*', value, 1);
+    match data.get(&key) {
+        Some(Value::String(string)) => Some(string),
+        _ => None,
     }
-    Ok(new_vec)
 }
 
-fn send_result(builder: &mut ResponseBuilder, values: Vec<String>) {
+fn send_result(builder: &mut ResponseBuilder, values: Vec<Option<String>>) {
     let mut protocol_vec = Vec::new();
-    for element in values.iter() {
-        protocol_vec.push(ProtocolType::String(element.to_string()));
+    for element in values.into_iter() {
+        match element {
+            Some(value) => protocol_vec.push(ProtocolType::String(value)),
+            None => protocol_vec.push(ProtocolType::Nil()),
+        }
     }
     builder.add(ProtocolType::Array(protocol_vec));
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
@@ -123,10 +272,7 @@ mod tests {
                 assert_eq!(true, false)
             }
             Err(msj) => {
-                assert_eq!(
-                    msj,
-                    "WRONGTYPE Operation against a key holding the wrong kind of value"
-                )
+                assert_eq!(msj, RedisError::WrongType)
             }
         }
     }
@@ -135,13 +281,13 @@ mod tests {
     fn test_sort_vec() {
         let data = Arc::new(DataStorage::new());
         let mut builder = ResponseBuilder::new();
-        let mut vc = Vec::new();
-
-        vc.push("1".to_string());
-        vc.push("10".to_string());
-        vc.push("5".to_string());
-        vc.push("30".to_string());
-        vc.push("100".to_string());
+        let vc = vec![
+            "1".to_string(),
+            "10".to_string(),
+            "5".to_string(),
+            "30".to_string(),
+            "100".to_string(),
+        ];
 
         data.set("key", Value::Vec(vc)).unwrap();
 
@@ -179,29 +325,182 @@ mod tests {
         );
 
         match result {
-            Ok(_) => assert!(false),
-            Err(msg) => assert_eq!(msg, "ERR One or more scores can't be converted into double"),
+            Ok(_) => panic!("Sorting non numeric values should fail"),
+            Err(msg) => assert_eq!(
+                msg,
+                RedisError::Generic("One or more scores can't be converted into double")
+            ),
         }
     }
 
     #[test]
-    fn test_unnable_to_sort_unexistent_key() {
+    fn test_sort_unexistent_key() {
         let data = Arc::new(DataStorage::new());
         let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            vec![ProtocolType::String("missing".to_string())],
+            &data,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), "*0\r\n");
 
-        let run_result = run(
+        data.set("dst", Value::Vec(vec!["1".to_string()])).unwrap();
+        let mut builder = ResponseBuilder::new();
+        run(
             &mut builder,
-            vec![ProtocolType::String("key".to_string())],
-            &data.clone(),
+            vec![
+                ProtocolType::String("missing".to_string()),
+                ProtocolType::String("STORE".to_string()),
+                ProtocolType::String("dst".to_string()),
+            ],
+            &data,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), ":0\r\n");
+        assert!(data.get("dst").is_none());
+    }
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    fn numbers_with_weights() -> Arc<DataStorage> {
+        let data = Arc::new(DataStorage::new());
+        data.set(
+            "key",
+            Value::Vec(vec!["1".to_string(), "2".to_string(), "3".to_string()]),
+        )
+        .unwrap();
+        data.set("weight_1", Value::String("30".to_string()))
+            .unwrap();
+        data.set("weight_2", Value::String("10".to_string()))
+            .unwrap();
+        data.set("weight_3", Value::String("20".to_string()))
+            .unwrap();
+        data.set("object_1", Value::String("one".to_string()))
+            .unwrap();
+        data.set("object_3", Value::String("three".to_string()))
+            .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_sort_alpha_desc() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set(
+            "key",
+            Value::Vec(vec!["b".to_string(), "c".to_string(), "a".to_string()]),
+        )
+        .unwrap();
+
+        run(&mut builder, arguments(&["key", "ALPHA", "DESC"]), &data).unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*3\r\n$1\r\nc\r\n$1\r\nb\r\n$1\r\na\r\n"
         );
+    }
 
-        match run_result {
-            Ok(_) => {
-                assert_eq!(true, false)
-            }
-            Err(msj) => {
-                assert_eq!(msj, "None")
-            }
-        }
+    #[test]
+    fn test_sort_with_limit() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set(
+            "key",
+            Value::Vec(vec![
+                "4".to_string(),
+                "1".to_string(),
+                "3".to_string(),
+                "2".to_string(),
+            ]),
+        )
+        .unwrap();
+
+        run(&mut builder, arguments(&["key", "LIMIT", "1", "2"]), &data).unwrap();
+
+        assert_eq!(builder.serialize(), "*2\r\n$1\r\n2\r\n$1\r\n3\r\n");
+    }
+
+    #[test]
+    fn test_sort_by_external_keys() {
+        let data = numbers_with_weights();
+        let mut builder = ResponseBuilder::new();
+
+        run(&mut builder, arguments(&["key", "BY", "weight_*"]), &data).unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*3\r\n$1\r\n2\r\n$1\r\n3\r\n$1\r\n1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_nosort_keeps_order() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set(
+            "key",
+            Value::Vec(vec!["b".to_string(), "c".to_string(), "a".to_string()]),
+        )
+        .unwrap();
+
+        run(&mut builder, arguments(&["key", "BY", "nosort"]), &data).unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*3\r\n$1\r\nb\r\n$1\r\nc\r\n$1\r\na\r\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_get_patterns() {
+        let data = numbers_with_weights();
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            arguments(&["key", "DESC", "GET", "#", "GET", "object_*"]),
+            &data,
+        )
+        .unwrap();
+
+        assert_eq!(
+            builder.serialize(),
+            "*6\r\n$1\r\n3\r\n$5\r\nthree\r\n$1\r\n2\r\n$-1\r\n$1\r\n1\r\n$3\r\none\r\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_store_desc() {
+        let data = numbers_with_weights();
+        let mut builder = ResponseBuilder::new();
+
+        run(
+            &mut builder,
+            arguments(&["key", "DESC", "STORE", "destination"]),
+            &data,
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), ":3\r\n");
+        assert_eq!(
+            data.get("destination").unwrap().array().unwrap(),
+            vec!["3".to_string(), "2".to_string(), "1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_sort_invalid_option_fails() {
+        let data = numbers_with_weights();
+        let mut builder = ResponseBuilder::new();
+
+        let result = run(&mut builder, arguments(&["key", "LIMIT", "1"]), &data);
+
+        assert_eq!(result, Err(RedisError::Syntax));
     }
 }
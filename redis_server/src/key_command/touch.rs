@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::logging::logger::Logger;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
@@ -12,12 +13,14 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
     logger: Arc<Logger>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut keys_touched = 0;
     let now_res = SystemTime::now().duration_since(UNIX_EPOCH);
 
     if now_res.is_err() {
-        return Err("An error ocurred while getting the actual timestamp.");
+        return Err(RedisError::Generic(
+            "An error ocurred while getting the actual timestamp.",
+        ));
     }
 
     let now = now_res.unwrap();
@@ -37,7 +40,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
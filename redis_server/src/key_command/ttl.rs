@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,9 +12,9 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("ERR wrong number of arguments for 'ttl' command");
+        return Err(RedisError::WrongArity("ttl"));
     }
 
     let key = arguments[0].clone().string()?;
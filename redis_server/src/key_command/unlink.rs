@@ -0,0 +1,101 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::data_storage::Value;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+
+/// Values with more elements than this are freed in a background thread.
+const LAZYFREE_THRESHOLD: usize = 64;
+
+/// Queue of the background thread that frees large values, started by the first
+/// UNLINK that needs it. A single thread is used, like the lazyfree thread of redis.
+static LAZYFREE: OnceLock<Sender<Vec<Value>>> = OnceLock::new();
+
+/// Removes the specified keys like DEL does, but the memory of large values is
+/// reclaimed in a different thread so the command returns without blocking.
+/// Returns the number of keys that were removed.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("unlink"));
+    }
+    let keys: Vec<String> = arguments
+        .into_iter()
+        .map(|x| x.string())
+        .collect::<Result<_, _>>()?;
+
+    let removed = data.unlink(&keys)?;
+    let count = removed.len();
+    let (large, small): (Vec<Value>, Vec<Value>) = removed
+        .into_iter()
+        .partition(|value| free_effort(value) > LAZYFREE_THRESHOLD);
+    drop(small);
+    if !large.is_empty() {
+        lazyfree(large);
+    }
+
+    builder.add(ProtocolType::Integer(count as i64));
+    Ok(())
+}
+
+/// Sends the values to the background thread to be freed.
+/// If the thread is gone they are freed by the caller.
+fn lazyfree(values: Vec<Value>) {
+    let queue = LAZYFREE.get_or_init(|| {
+        let (queue, pending) = mpsc::channel::<Vec<Value>>();
+        thread::spawn(move || pending.into_iter().for_each(drop));
+        queue
+    });
+    if let Err(mpsc::SendError(values)) = queue.send(values) {
+        drop(values);
+    }
+}
+
+/// Returns the amount of allocations needed to free the value.
+fn free_effort(value: &Value) -> usize {
+    match value {
+        Value::String(_) => 1,
+        Value::Vec(vec) => vec.len(),
+        Value::HashSet(set) => set.len(),
+        Value::Geo(index) => index.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlink_existing_and_missing_keys() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("key1", Value::String("value".to_string()))
+            .unwrap();
+        data.set(
+            "key2",
+            Value::Vec((0..1000).map(|x| x.to_string()).collect()),
+        )
+        .unwrap();
+
+        run(
+            &mut builder,
+            vec![
+                ProtocolType::String("key1".to_string()),
+                ProtocolType::String("key2".to_string()),
+                ProtocolType::String("key3".to_string()),
+            ],
+            &data,
+        )
+        .unwrap();
+
+        assert_eq!(builder.serialize(), ":2\r\n");
+        assert!(data.is_empty());
+        assert!(LAZYFREE.get().is_some());
+    }
+}
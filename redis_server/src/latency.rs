@@ -0,0 +1,163 @@
+use redis_protocol::types::ProtocolType;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Most samples kept for each event, like redis does
+const HISTORY_LEN: usize = 160;
+
+/// Event of the commands slower than the threshold
+pub const COMMAND_EVENT: &str = "command";
+/// Event of the periodic save of the database to disk
+pub const SAVE_EVENT: &str = "snapshot-save";
+
+/// A latency spike of an event, in milliseconds, at a unix time in seconds.
+#[derive(Clone, Copy)]
+struct Sample {
+    time: u64,
+    latency: u64,
+}
+
+/// The samples of an event, newest last, and its highest latency.
+struct Event {
+    history: VecDeque<Sample>,
+    max: u64,
+}
+
+/// Struct LatencyMonitor. Keeps the history of the events that took longer than the
+/// `latency-monitor-threshold`, grouped by the name of the event.
+pub struct LatencyMonitor {
+    events: Mutex<BTreeMap<String, Event>>,
+}
+
+#[allow(clippy::new_without_default)]
+impl LatencyMonitor {
+    pub fn new() -> Self {
+        LatencyMonitor {
+            events: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Event>> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records an event that took the given time, if it is over the threshold in milliseconds.
+    /// A threshold of zero disables the latency monitor. Spikes of the same event in the
+    /// same second are kept as a single sample with the highest latency.
+    pub fn record(&self, event: &str, duration: Duration, threshold: u64) {
+        let latency = duration.as_millis() as u64;
+        if threshold == 0 || latency < threshold {
+            return;
+        }
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or(0);
+
+        let mut events = self.lock();
+        let event = events.entry(event.to_string()).or_insert_with(|| Event {
+            history: VecDeque::new(),
+            max: 0,
+        });
+        event.max = event.max.max(latency);
+        match event.history.back_mut() {
+            Some(last) if last.time == time => last.latency = last.latency.max(latency),
+            _ => {
+                event.history.push_back(Sample { time, latency });
+                if event.history.len() > HISTORY_LEN {
+                    event.history.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Returns the name, time and latency of the latest sample, and the highest latency of each event
+    pub fn latest(&self) -> ProtocolType {
+        ProtocolType::Array(
+            self.lock()
+                .iter()
+                .filter_map(|(name, event)| {
+                    let last = event.history.back()?;
+                    Some(ProtocolType::Array(vec![
+                        ProtocolType::String(name.clone()),
+                        ProtocolType::Integer(last.time as i64),
+                        ProtocolType::Integer(last.latency as i64),
+                        ProtocolType::Integer(event.max as i64),
+                    ]))
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the time and latency of every sample of an event, oldest first
+    pub fn history(&self, event: &str) -> ProtocolType {
+        ProtocolType::Array(
+            self.lock()
+                .get(event)
+                .map(|event| {
+                    event
+                        .history
+                        .iter()
+                        .map(|sample| {
+                            ProtocolType::Array(vec![
+                                ProtocolType::Integer(sample.time as i64),
+                                ProtocolType::Integer(sample.latency as i64),
+                            ])
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Removes the given events, or every event if none is given, and returns how many were removed
+    pub fn reset(&self, events: &[String]) -> usize {
+        let mut lock = self.lock();
+        if events.is_empty() {
+            let removed = lock.len();
+            lock.clear();
+            return removed;
+        }
+        events.iter().filter(|x| lock.remove(*x).is_some()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_over_threshold() {
+        let monitor = LatencyMonitor::new();
+        monitor.record(COMMAND_EVENT, Duration::from_millis(5), 10);
+        monitor.record(COMMAND_EVENT, Duration::from_millis(50), 0);
+        assert_eq!(monitor.latest().serialize(), "*0\r\n");
+
+        monitor.record(COMMAND_EVENT, Duration::from_millis(20), 10);
+        monitor.record(COMMAND_EVENT, Duration::from_millis(30), 10);
+        monitor.record(SAVE_EVENT, Duration::from_millis(15), 10);
+
+        let latest = monitor.latest().serialize();
+        assert!(latest.starts_with("*2\r\n*4\r\n$7\r\ncommand\r\n:"));
+        assert!(latest.contains(":30\r\n:30\r\n*4\r\n$13\r\nsnapshot-save\r\n"));
+        let history = monitor.history(COMMAND_EVENT).serialize();
+        assert!(history.contains("*2\r\n:"));
+        assert!(history.ends_with(":30\r\n"));
+        assert_eq!(monitor.history("missing").serialize(), "*0\r\n");
+    }
+
+    #[test]
+    fn test_reset() {
+        let monitor = LatencyMonitor::new();
+        monitor.record(COMMAND_EVENT, Duration::from_millis(20), 10);
+        monitor.record(SAVE_EVENT, Duration::from_millis(20), 10);
+
+        assert_eq!(
+            monitor.reset(&[SAVE_EVENT.to_string(), "missing".to_string()]),
+            1
+        );
+        assert_eq!(monitor.reset(&[]), 1);
+        assert_eq!(monitor.latest().serialize(), "*0\r\n");
+    }
+}
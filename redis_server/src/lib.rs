@@ -1,15 +1,28 @@
+mod acl;
 mod client;
+mod client_registry;
+mod cluster;
+mod command_table;
 pub mod config;
+pub mod error;
 mod execution;
+mod geo_command;
 mod key_command;
+mod latency;
 mod listener_thread;
 mod lists_command;
 pub mod logging;
 pub mod monitor;
+mod panic_guard;
 mod pubsub;
 mod pubsub_command;
+mod replication;
 pub mod server;
 mod server_command;
 mod set_command;
+mod slowlog;
+pub mod stats;
 pub mod storage;
 mod string_command;
+#[cfg(test)]
+pub(crate) mod test_util;
@@ -1,19 +1,38 @@
 use crate::config::configuration::Configuration;
+use crate::error::RedisError;
 use crate::execution::Execution;
 use crate::logging::logger::Logger;
+use crate::panic_guard;
 use redis_protocol::command::Command;
 
 use crate::server::THREADS;
+use crate::slowlog::SlowLog;
+use crate::stats::{CallOutcome, ServerStats};
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use threadpool::threadpool::ThreadPool;
 
-use std::net::TcpListener;
+use mio::unix::SourceFd;
+use mio::{Events, Interest, Poll, Registry, Token, Waker};
+use std::io::{self, ErrorKind, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant, SystemTime};
 
 use crate::client::Client;
+use crate::client_registry::ClientRegistry;
+use std::collections::HashSet;
 use std::sync::mpsc::{Receiver, Sender, TryRecvError};
 use std::sync::{Arc, Mutex};
 
+const LISTENER: Token = Token(0);
+/// Token of the waker, used by the workers to resume the paused clients
+const WAKER: Token = Token(usize::MAX);
+const EVENTS_CAPACITY: usize = 1024;
+/// Longest wait for events, so changes of the timeout configuration are noticed
+const MAX_WAIT: Duration = Duration::from_secs(1);
+const MAX_CLIENTS_ERROR: &str = "-ERR max number of clients reached\r\n";
+
 /// Struct which listens for connections and executes the given commands.
 pub struct ListenerThread {
     pool: ThreadPool,
@@ -21,6 +40,10 @@ pub struct ListenerThread {
     execution: Arc<Execution>,
     logger: Arc<Logger>,
     config: Arc<Mutex<Configuration>>,
+    stats: Arc<ServerStats>,
+    clients: Arc<ClientRegistry>,
+    slowlog: Arc<SlowLog>,
+    paused: Arc<Mutex<HashSet<u64>>>,
 }
 
 impl ListenerThread {
@@ -29,6 +52,9 @@ impl ListenerThread {
         execution: Arc<Execution>,
         logger: Arc<Logger>,
         config: Arc<Mutex<Configuration>>,
+        stats: Arc<ServerStats>,
+        clients: Arc<ClientRegistry>,
+        slowlog: Arc<SlowLog>,
     ) -> Self {
         let pool = ThreadPool::new(THREADS);
         ListenerThread {
@@ -37,10 +63,18 @@ impl ListenerThread {
             execution,
             logger,
             config,
+            stats,
+            clients,
+            slowlog,
+            paused: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
     /// Listen for connections on the configured settings.
+    /// A single thread waits for events of every socket and hands the clients
+    /// with pending input to the pool, which reads and executes their commands.
+    /// Clients with commands delayed by CLIENT PAUSE are handed to the pool again
+    /// once the pause ends or is stopped, instead of waiting in a worker.
     pub fn run(&self, _ttl: u32, sx: Sender<()>, rx: Receiver<()>) {
         println!("Trying to bind on address {}", self.addr);
         let listener = match TcpListener::bind(&self.addr) {
@@ -50,53 +84,219 @@ impl ListenerThread {
                 panic!("{}", e);
             }
         };
+        let mut poll = match Self::create_poll(&listener) {
+            Ok(poll) => poll,
+            Err(e) => {
+                self.print_and_log(format!("Failed to create event loop with error: '{}'", e));
+                panic!("{}", e);
+            }
+        };
+        let registry = Arc::new(poll.registry().try_clone().unwrap());
+        let waker = match Waker::new(poll.registry(), WAKER) {
+            Ok(waker) => Arc::new(waker),
+            Err(e) => {
+                self.print_and_log(format!("Failed to create event loop with error: '{}'", e));
+                panic!("{}", e);
+            }
+        };
         self.print_and_log(format!(
             "REDIS server started on address '{}'...",
             self.addr
         ));
         sx.send(()).unwrap();
 
-        for stream in listener.incoming() {
+        let mut events = Events::with_capacity(EVENTS_CAPACITY);
+        let mut next_timeout_check = Instant::now();
+        loop {
             match rx.try_recv() {
                 Ok(_) | Err(TryRecvError::Disconnected) => {
                     self.print_and_log("Terminating.".to_string());
+                    self.close_all();
                     break;
                 }
                 Err(TryRecvError::Empty) => {}
             }
 
-            let client = Arc::new(Client::new(stream.unwrap()));
-            let exec = self.execution.clone();
-            let logger = self.logger.clone();
-            let config = self.config.clone();
-            self.pool.spawn(move || {
-                ListenerThread::handle_connection(client, exec, logger, config);
-            });
+            let mut wait = next_timeout_check.saturating_duration_since(Instant::now());
+            if let Some(until) = self.pause_deadline() {
+                wait = wait.min(until.duration_since(SystemTime::now()).unwrap_or_default());
+            }
+            if let Err(e) = poll.poll(&mut events, Some(wait)) {
+                if e.kind() != ErrorKind::Interrupted {
+                    self.print_and_log(format!("Failed to poll events with error: '{}'", e));
+                }
+                continue;
+            }
+            for event in events.iter() {
+                match event.token() {
+                    LISTENER => self.accept_clients(&listener, &registry),
+                    WAKER => {}
+                    Token(token) => self.dispatch(token as u64 - 1, &registry, &waker),
+                }
+            }
+            self.resume_paused_clients(&registry, &waker);
+
+            if Instant::now() >= next_timeout_check {
+                next_timeout_check = Instant::now() + self.close_idle_clients();
+            }
         }
     }
 
-    /// Handles a socket connection and executes the command extracted from it.
-    fn handle_connection(
-        client: Arc<Client>,
-        execution: Arc<Execution>,
-        logger: Arc<Logger>,
-        config: Arc<Mutex<Configuration>>,
-    ) {
-        let config_lock = config.lock().unwrap();
-        let timeout = config_lock.get_timeout();
-        let verbose = config_lock.get_verbose();
-        drop(config_lock);
-        let commands_result = client.parse_commands(timeout as u64);
-        if let Err(e) = commands_result {
-            if verbose == 1 {
-                println!("{}", &e);
+    /// Creates the event loop, with the listener socket registered in it
+    fn create_poll(listener: &TcpListener) -> io::Result<Poll> {
+        listener.set_nonblocking(true)?;
+        let poll = Poll::new()?;
+        poll.registry().register(
+            &mut SourceFd(&listener.as_raw_fd()),
+            LISTENER,
+            Interest::READABLE,
+        )?;
+        Ok(poll)
+    }
+
+    /// Accepts every pending connection
+    fn accept_clients(&self, listener: &TcpListener, registry: &Arc<Registry>) {
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => self.accept(stream, registry),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.print_and_log(format!("Failed to accept connection: '{}'", e));
+                    break;
+                }
             }
-            logger.log(&e).unwrap();
+        }
+    }
+
+    /// Registers a new connection in the event loop,
+    /// or rejects it if there are already maxclients clients connected.
+    fn accept(&self, mut stream: TcpStream, registry: &Arc<Registry>) {
+        let maxclients = self.config.lock().unwrap().get_maxclients();
+        if self.stats.connected_clients() >= maxclients {
+            let _ = stream.write_all(MAX_CLIENTS_ERROR.as_bytes());
+            self.print_and_log("Connection rejected: max number of clients reached".to_string());
+            return;
+        }
+
+        let client = Arc::new(Client::new(stream));
+        if let Err(e) = self.clients.add(client.clone()) {
+            self.print_and_log(e.to_string());
+            return;
+        }
+        self.stats.client_connected();
+        if let Err(e) = registry.register(
+            &mut SourceFd(&client.raw_fd()),
+            Self::token(&client),
+            Interest::READABLE,
+        ) {
+            self.print_and_log(format!("Failed to register connection: '{}'", e));
+            Self::disconnect(&client, registry, &self.clients, &self.stats);
+        }
+    }
+
+    /// Returns the end of the pause, if there are clients waiting for it
+    fn pause_deadline(&self) -> Option<SystemTime> {
+        if self.paused.lock().unwrap().is_empty() {
+            return None;
+        }
+        self.clients.paused_until(true)
+    }
+
+    /// Hands the clients with postponed commands to the pool again once the pause is over.
+    /// Their commands are postponed again if another pause started meanwhile.
+    fn resume_paused_clients(&self, registry: &Arc<Registry>, waker: &Arc<Waker>) {
+        if self.clients.paused_until(true).is_some() {
+            return;
+        }
+        let paused: Vec<u64> = self.paused.lock().unwrap().drain().collect();
+        for id in paused {
+            self.dispatch(id, registry, waker);
+        }
+    }
+
+    /// Hands a client with pending input to the pool.
+    /// Clients already being served are skipped, the worker serving them
+    /// re-arms their events once it finishes. A panic while serving a client
+    /// is logged and closes its connection, so it never stays busy.
+    fn dispatch(&self, id: u64, registry: &Arc<Registry>, waker: &Arc<Waker>) {
+        let client = match self.clients.get(id) {
+            Some(client) => client,
+            None => return,
+        };
+        if !client.try_start_serving() {
             return;
         }
+        let exec = self.execution.clone();
+        let logger = self.logger.clone();
+        let config = self.config.clone();
+        let stats = self.stats.clone();
+        let clients = self.clients.clone();
+        let slowlog = self.slowlog.clone();
+        let registry = registry.clone();
+        let waker = waker.clone();
+        let paused = self.paused.clone();
+        self.pool.spawn(move || {
+            let result = panic_guard::catch(|| {
+                Self::handle_input(&client, exec, logger.clone(), config.clone(), &slowlog)
+            });
+            // The input of the client can not be trusted after a panic while reading it
+            let postponed = matches!(result, Ok(true));
+            if let Err(report) = result {
+                let _ = logger.log(&format!(
+                    "Panic while serving client {}: {}",
+                    client.id(),
+                    report
+                ));
+                client.close();
+            }
+            if client.is_closed() {
+                Self::disconnect(&client, &registry, &clients, &stats);
+                let msg = format!("Client {} closed the connection", client.id());
+                if config.lock().unwrap().get_verbose() == 1 {
+                    println!("{}", &msg);
+                }
+                logger.log(&msg).unwrap();
+                return;
+            }
+            client.finish_serving();
+            if postponed {
+                paused.lock().unwrap().insert(client.id());
+            }
+            // Commands like CLIENT UNPAUSE end the pause before its deadline
+            if !paused.lock().unwrap().is_empty() && clients.paused_until(true).is_none() {
+                let _ = waker.wake();
+            }
+            if let Err(e) = registry.reregister(
+                &mut SourceFd(&client.raw_fd()),
+                Self::token(&client),
+                Interest::READABLE,
+            ) {
+                logger
+                    .log(&format!("Failed to register connection: '{}'", e))
+                    .unwrap();
+            }
+        });
+    }
 
-        let commands = commands_result.unwrap();
-        for command in commands {
+    /// Reads the available input of a client and executes the commands extracted from it.
+    /// Returns true if a command was paused by CLIENT PAUSE: it is postponed, together
+    /// with the commands after it, until the pause ends.
+    fn handle_input(
+        client: &Arc<Client>,
+        execution: Arc<Execution>,
+        logger: Arc<Logger>,
+        config: Arc<Mutex<Configuration>>,
+        slowlog: &SlowLog,
+    ) -> bool {
+        let (commands, error) = client.read_commands();
+        let mut commands = commands.into_iter();
+        while let Some(command) = commands.next() {
+            if execution.paused_until(&command).is_some() {
+                let postponed = std::iter::once(command).chain(commands).collect();
+                client.postpone(postponed, error);
+                return true;
+            }
             Self::log_command(&command, logger.clone(), config.clone());
             Self::execute_command(
                 &command,
@@ -104,12 +304,83 @@ impl ListenerThread {
                 execution.clone(),
                 logger.clone(),
                 config.clone(),
+                slowlog,
             );
         }
 
-        if !client.is_closed() {
-            Self::handle_connection(client, execution, logger, config);
+        // Like redis, a malformed request is answered with an error and the connection closed
+        if let Some(e) = error {
+            if config.lock().unwrap().get_verbose() == 1 {
+                println!("{}", &e);
+            }
+            logger.log(&e).unwrap();
+            let _ = client.send(&ProtocolType::Error(format!("ERR {}", e)).serialize());
+            client.close();
         }
+        false
+    }
+
+    /// Removes a client from the event loop and the registry, and closes its socket
+    fn disconnect(
+        client: &Arc<Client>,
+        registry: &Registry,
+        clients: &ClientRegistry,
+        stats: &ServerStats,
+    ) {
+        let _ = registry.deregister(&mut SourceFd(&client.raw_fd()));
+        client.close();
+        if clients.remove(client.id()).is_ok() {
+            stats.client_disconnected();
+        }
+    }
+
+    /// Closes the connection of the clients idle for more than the configured timeout,
+    /// and returns the time until the next client reaches it.
+    /// Clients in pubsub or monitor mode, and replicas, are never closed.
+    fn close_idle_clients(&self) -> Duration {
+        let timeout = self.config.lock().unwrap().get_timeout();
+        if timeout == 0 {
+            return MAX_WAIT;
+        }
+        let timeout = Duration::from_secs(timeout as u64);
+        let clients = match self.clients.list() {
+            Ok(clients) => clients,
+            Err(e) => {
+                self.print_and_log(e.to_string());
+                return MAX_WAIT;
+            }
+        };
+
+        let mut next_deadline = MAX_WAIT;
+        for client in clients.iter().filter(|x| {
+            !x.is_closed() && !x.in_pubsub_mode() && !x.in_monitor_mode() && !x.in_replica_mode()
+        }) {
+            match timeout.checked_sub(client.idle()) {
+                Some(remaining) if !remaining.is_zero() => {
+                    next_deadline = next_deadline.min(remaining)
+                }
+                _ => {
+                    if let Err(e) = client.kill() {
+                        self.print_and_log(e.to_string());
+                    }
+                }
+            }
+        }
+        next_deadline
+    }
+
+    /// Closes every connection when the server terminates
+    fn close_all(&self) {
+        if let Ok(clients) = self.clients.list() {
+            for client in clients {
+                client.close();
+            }
+        }
+    }
+
+    /// Returns the event loop token of a client. The token 0 belongs to the listener.
+    fn token(client: &Client) -> Token {
+        Token(client.id() as usize + 1)
     }
 
     /// Logs a given command
@@ -132,18 +403,56 @@ impl ListenerThread {
     }
 
     /// Executed a given command.
+    /// A panic while running the command is logged and answered with an error,
+    /// keeping the connection open. Commands slower than `slowlog-log-slower-than`
+    /// are recorded in the slow log, and every call in the command statistics.
     fn execute_command(
         command: &Command,
         client: Arc<Client>,
         execution: Arc<Execution>,
         logger: Arc<Logger>,
         config: Arc<Mutex<Configuration>>,
+        slowlog: &SlowLog,
     ) {
         let mut response = ResponseBuilder::new();
 
-        if let Err(e) = execution.run(command, &mut response, client.clone()) {
-            logger.log("Error").unwrap();
-            response.add(ProtocolType::Error(e.to_string()));
+        let start = Instant::now();
+        let result = panic_guard::catch(|| execution.run(command, &mut response, client.clone()));
+        let duration = start.elapsed();
+        let (threshold, max_len) = {
+            let config = config.lock().unwrap();
+            (
+                config.get_slowlog_log_slower_than(),
+                config.get_slowlog_max_len() as usize,
+            )
+        };
+        slowlog.record(command, &client, duration, threshold, max_len);
+        let outcome = match &result {
+            Ok(Ok(())) => CallOutcome::Succeeded,
+            Ok(Err(e)) if e.is_rejection() => CallOutcome::Rejected,
+            _ => CallOutcome::Failed,
+        };
+        execution.record_call(command, duration, outcome);
+
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                logger.log("Error").unwrap();
+                response.add(ProtocolType::Error(e.to_string()));
+            }
+            Err(report) => {
+                logger
+                    .log(&format!(
+                        "Panic while executing command '{}': {}",
+                        command.name(),
+                        report
+                    ))
+                    .unwrap();
+                response = ResponseBuilder::new();
+                response.add(ProtocolType::Error(
+                    RedisError::Generic("internal error while executing the command").to_string(),
+                ));
+            }
         }
         Self::write_response(client, &response, logger, config);
     }
@@ -160,7 +469,9 @@ impl ListenerThread {
             println!("{}", &response.to_string());
         }
         logger.log(&response.to_string()).unwrap();
-        client.send(&response.serialize()).unwrap();
+        if let Err(e) = client.send_response(response) {
+            logger.log(e).unwrap();
+        }
     }
 
     /// Prints and logs a message
@@ -1,9 +1,9 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
-use std::usize;
 
 /// Returns the element at index index in the list stored at key.
 /// The index is zero-based, so 0 means the first element, 1 the second element and so on.
@@ -14,9 +14,9 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("ERR wrong number of arguments for 'lindex' command");
+        return Err(RedisError::WrongArity("lindex"));
     }
     let string_key = arguments[0].clone().string()?;
     let string_index = arguments[1].clone().string()?;
@@ -30,9 +30,7 @@ pub fn run(
     let result = data.get(&string_key);
     match result {
         Some(value) => match value {
-            Value::String(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
+            Value::String(_) => return Err(RedisError::WrongType),
             Value::Vec(list) => {
                 let usize_index;
                 if i8_index >= 0 {
@@ -45,15 +43,14 @@ pub fn run(
                         usize_index = list.len() - positive_index;
                     }
                 }
-                let element = list.get(usize_index as usize);
+                let element = list.get(usize_index);
                 match element {
                     Some(res) => builder.add(ProtocolType::String(res.to_string())),
                     None => builder.add(ProtocolType::Nil()),
                 }
             }
-            Value::HashSet(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
+            Value::HashSet(_) => return Err(RedisError::WrongType),
+            Value::Geo(_) => return Err(RedisError::WrongType),
         },
 
         None => builder.add(ProtocolType::Nil()),
@@ -62,7 +59,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
@@ -108,10 +104,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 
@@ -132,10 +125,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 
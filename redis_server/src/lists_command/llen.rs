@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
@@ -11,23 +12,20 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("ERR wrong number of arguments");
+        return Err(RedisError::WrongArity("llen"));
     }
     let string_key = arguments[0].clone().string()?;
     let result = data.get(&string_key);
     match result {
         Some(value) => match value {
-            Value::String(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
+            Value::String(_) => return Err(RedisError::WrongType),
             Value::Vec(list) => {
                 builder.add(ProtocolType::Integer(list.len() as i64));
             }
-            Value::HashSet(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
+            Value::HashSet(_) => return Err(RedisError::WrongType),
+            Value::Geo(_) => return Err(RedisError::WrongType),
         },
 
         None => builder.add(ProtocolType::Integer(0)),
@@ -36,7 +34,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
@@ -90,10 +87,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 
@@ -111,10 +105,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 }
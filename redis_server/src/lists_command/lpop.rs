@@ -1,45 +1,49 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
-use std::usize;
 
 /// Removes and returns the first elements of the list stored at key.
 /// By default, the command pops a single element from the beginning of the list.
-/// When provided with the optional count argument, the reply will consist of up to count elements, depending on the list's length.
+/// When provided with the optional count argument, the reply will consist of up to count elements, depending on the list's length,
+/// or a null array if the key does not exist.
 pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() > 2 || arguments.is_empty() {
-        return Err("ERR wrong number of arguments for 'lpop' command");
+        return Err(RedisError::WrongArity("lpop"));
     }
 
     let key = arguments[0].clone().string()?;
-    let mut count = 1;
-    if arguments.len() > 1 {
-        count = arguments[1].clone().integer()?;
+    if arguments.len() == 1 {
+        let res = match data.lpop(key, 1)?.into_iter().next() {
+            Some(value) => ProtocolType::String(value),
+            None => ProtocolType::Nil(),
+        };
+        builder.add(res);
+        return Ok(());
     }
 
+    let count = arguments[1].integer().map_err(|_| RedisError::NotInteger)?;
+    if count < 0 {
+        return Err(RedisError::Generic(
+            "value is out of range, must be positive",
+        ));
+    }
     let vals = data.lpop(key, count as usize)?;
     let res = if vals.is_empty() {
-        ProtocolType::Nil()
-    } else if vals.len() == 1 {
-        ProtocolType::String(vals[0].clone())
+        ProtocolType::NilArray()
     } else {
-        ProtocolType::Array(
-            vals.into_iter()
-                .map(ProtocolType::String)
-                .collect::<Vec<ProtocolType>>(),
-        )
+        ProtocolType::Array(vals.into_iter().map(ProtocolType::String).collect())
     };
     builder.add(res);
     Ok(())
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
@@ -132,4 +136,24 @@ mod tests {
 
         assert_eq!(ProtocolType::Nil().serialize(), builder.serialize());
     }
+
+    #[test]
+    fn pop_count_no_list() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("Test", Value::Vec(["1".to_string()].to_vec()))
+            .unwrap();
+
+        let arguments = |key: &str, count: &str| {
+            vec![
+                ProtocolType::String(key.to_string()),
+                ProtocolType::String(count.to_string()),
+            ]
+        };
+        assert!(run(arguments("Test", "-1"), &mut builder, data.clone()).is_err());
+        run(arguments("Test", "1"), &mut builder, data.clone()).unwrap();
+        run(arguments("Missing", "2"), &mut builder, data).unwrap();
+
+        assert_eq!("*1\r\n$1\r\n1\r\n*-1\r\n", builder.serialize());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,14 +12,20 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 3 {
-        return Err("Wrong quantity of arguments.");
+        return Err(RedisError::WrongArity("lrange"));
     }
 
     let key = arguments[0].clone().string()?;
-    let first_index = arguments[1].clone().integer()?;
-    let second_index = arguments[2].clone().integer()?;
+    let first_index = arguments[1]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
+    let second_index = arguments[2]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
 
     let values = data.lrange(key, first_index, second_index);
 
@@ -35,15 +42,11 @@ pub fn run(
                 Ok(())
             }
         },
-        Err(s) => {
-            builder.add(ProtocolType::Error(s.to_string()));
-            Err(s)
-        }
+        Err(s) => Err(s),
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -12,13 +13,16 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 3 {
-        return Err("Wrong quantity of arguments.");
+        return Err(RedisError::WrongArity("lrem"));
     }
 
     let key = arguments[0].clone().string()?;
-    let index = arguments[1].clone().integer()?;
+    let index = arguments[1]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
     let value = arguments[2].clone().string()?;
 
     let result = data.lrem(key, index, value);
@@ -33,7 +37,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
@@ -66,14 +69,11 @@ mod tests {
 
         let vector = match value {
             Value::Vec(i) => Ok(i),
-            _ => Err("not vector value"),
+            _ => Err(RedisError::WrongType),
         };
 
         assert_eq!("+2\r\n", builder.serialize());
-        assert_eq!(
-            true,
-            vec_compare(&vector.unwrap(), &["1".to_string()].to_vec())
-        );
+        assert!(vec_compare(&vector.unwrap(), ["1".to_string()].as_ref()));
     }
 
     #[test]
@@ -101,17 +101,14 @@ mod tests {
 
         let vector = match value {
             Value::Vec(i) => Ok(i),
-            _ => Err("not vector value"),
+            _ => Err(RedisError::WrongType),
         };
 
         assert_eq!("+1\r\n", builder.serialize());
-        assert_eq!(
-            true,
-            vec_compare(
-                &vector.unwrap(),
-                &["1".to_string(), "2".to_string()].to_vec()
-            )
-        );
+        assert!(vec_compare(
+            &vector.unwrap(),
+            ["1".to_string(), "2".to_string()].as_ref()
+        ));
     }
 
     #[test]
@@ -139,17 +136,14 @@ mod tests {
 
         let vector = match value {
             Value::Vec(i) => Ok(i),
-            _ => Err("not vector value"),
+            _ => Err(RedisError::WrongType),
         };
 
         assert_eq!("+2\r\n", builder.serialize());
-        assert_eq!(
-            true,
-            vec_compare(&vector.unwrap(), &["2".to_string()].to_vec())
-        );
+        assert!(vec_compare(&vector.unwrap(), ["2".to_string()].as_ref()));
     }
 
-    fn vec_compare(va: &Vec<String>, vb: &Vec<String>) -> bool {
-        (va.len() == vb.len()) && va.iter().zip(vb).all(|(a, b)| (a == b))
+    fn vec_compare(va: &[String], vb: &[String]) -> bool {
+        (va.len() == vb.len()) && va.iter().zip(vb).all(|(a, b)| a == b)
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,13 +10,16 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 3 {
-        return Err("Wrong quantity of arguments.");
+        return Err(RedisError::WrongArity("lset"));
     }
 
     let key = arguments[0].clone().string()?;
-    let index = arguments[1].clone().integer()?;
+    let index = arguments[1]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
     let value = arguments[2].clone().string()?;
 
     let result = data.lset(key, index, value);
@@ -30,7 +34,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
@@ -63,7 +66,7 @@ mod tests {
 
         let vector = match value {
             Value::Vec(i) => Ok(i),
-            _ => Err("not vector value"),
+            _ => Err(RedisError::WrongType),
         };
 
         assert_eq!("+OK\r\n", builder.serialize());
@@ -95,7 +98,7 @@ mod tests {
 
         let vector = match value {
             Value::Vec(i) => Ok(i),
-            _ => Err("not vector value"),
+            _ => Err(RedisError::WrongType),
         };
 
         assert_eq!("+OK\r\n", builder.serialize());
@@ -1,35 +1,42 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
 
 /// Removes and returns the last elements of the list stored at key.
+/// Without count it replies a single element, or nil. With count it always replies an array,
+/// or a null array if the key does not exist.
 pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() > 2 || arguments.is_empty() {
-        return Err("ERR wrong number of arguments");
+        return Err(RedisError::WrongArity("rpop"));
     }
 
     let key = arguments[0].clone().string()?;
-    let mut count = 1;
-    if arguments.len() > 1 {
-        count = arguments[1].clone().integer()?;
+    if arguments.len() == 1 {
+        let res = match data.rpop(key, 1)?.into_iter().next() {
+            Some(value) => ProtocolType::String(value),
+            None => ProtocolType::Nil(),
+        };
+        builder.add(res);
+        return Ok(());
     }
 
+    let count = arguments[1].integer().map_err(|_| RedisError::NotInteger)?;
+    if count < 0 {
+        return Err(RedisError::Generic(
+            "value is out of range, must be positive",
+        ));
+    }
     let vals = data.rpop(key, count as usize)?;
     let res = if vals.is_empty() {
-        ProtocolType::Nil()
-    } else if vals.len() == 1 {
-        ProtocolType::String(vals[0].clone())
+        ProtocolType::NilArray()
     } else {
-        ProtocolType::Array(
-            vals.into_iter()
-                .map(ProtocolType::String)
-                .collect::<Vec<ProtocolType>>(),
-        )
+        ProtocolType::Array(vals.into_iter().map(ProtocolType::String).collect())
     };
     builder.add(res);
     Ok(())
@@ -131,4 +138,24 @@ mod tests {
 
         assert_eq!(ProtocolType::Nil().serialize(), builder.serialize());
     }
+
+    #[test]
+    fn pop_count_no_list() {
+        let data = Arc::new(DataStorage::new());
+        let mut builder = ResponseBuilder::new();
+        data.set("Test", Value::Vec(["1".to_string()].to_vec()))
+            .unwrap();
+
+        let arguments = |key: &str, count: &str| {
+            vec![
+                ProtocolType::String(key.to_string()),
+                ProtocolType::String(count.to_string()),
+            ]
+        };
+        assert!(run(&mut builder, arguments("Test", "-1"), data.clone()).is_err());
+        run(&mut builder, arguments("Test", "1"), data.clone()).unwrap();
+        run(&mut builder, arguments("Missing", "2"), data).unwrap();
+
+        assert_eq!("*1\r\n$1\r\n1\r\n*-1\r\n", builder.serialize());
+    }
 }
@@ -10,7 +10,7 @@ pub struct Logger {
 }
 
 enum Message {
-    Message(String),
+    Log(String),
     File(File),
     Terminate,
 }
@@ -24,7 +24,7 @@ impl Logger {
             let message = receiver.recv();
             if let Ok(msg) = message {
                 match msg {
-                    Message::Message(string) => write(&string, &file),
+                    Message::Log(string) => write(&string, &file),
                     Message::File(new_file) => {
                         file = new_file;
                     }
@@ -42,11 +42,11 @@ impl Logger {
     pub fn log(&self, msg: &str) -> Result<(), &'static str> {
         match self.sender.lock() {
             Ok(sender) => {
-                if sender.send(Message::Message(msg.to_string())).is_err() {
-                    return Err("No se pudo loggear el mensaje.");
+                if sender.send(Message::Log(msg.to_string())).is_err() {
+                    return Err("Could not log the message");
                 };
             }
-            Err(_) => return Err("No se pudo loggear el mensaje."),
+            Err(_) => return Err("Could not log the message"),
         }
         Ok(())
     }
@@ -57,10 +57,10 @@ impl Logger {
         match self.sender.lock() {
             Ok(sender) => {
                 if sender.send(Message::File(file)).is_err() {
-                    return Err("No se pudo loggear el mensaje");
+                    return Err("Could not log the message");
                 }
             }
-            Err(_) => return Err("No se pudo cambiar el nombre de archivo"),
+            Err(_) => return Err("Could not rename the log file"),
         }
         Ok(())
     }
@@ -78,11 +78,11 @@ pub fn create_logfile(file_dir: &str) -> Result<File, &'static str> {
     if let Ok(file) = File::create(file_dir) {
         return Ok(file);
     }
-    Err("No se pudo crear el archivo de logs.")
+    Err("Could not create the log file")
 }
 
 pub fn write(msg: &str, mut file: &File) {
     if file.write(format!("{}{}", msg, '\n').as_bytes()).is_err() {
-        println!("No se pudo escribir en el archivo");
+        println!("Could not write to the log file");
     }
 }
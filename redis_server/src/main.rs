@@ -2,21 +2,34 @@ use crate::config::configuration::Configuration;
 use crate::logging::logger::Logger;
 use std::env;
 use std::sync::Arc;
+mod acl;
 mod client;
+mod client_registry;
+mod cluster;
+mod command_table;
 mod config;
+mod error;
 mod execution;
+mod geo_command;
 mod key_command;
+mod latency;
 mod listener_thread;
 mod lists_command;
 mod logging;
 mod monitor;
+mod panic_guard;
 mod pubsub;
 mod pubsub_command;
+mod replication;
 mod server;
 mod server_command;
 mod set_command;
+mod slowlog;
+mod stats;
 mod storage;
 mod string_command;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -1,7 +1,11 @@
 use crate::client::Client;
+use crate::slowlog::redacted_arguments;
+use redis_protocol::command::Command;
+use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
 use std::sync::RwLock;
 use std::sync::RwLockWriteGuard;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Struct Monitor. It is composed of a
 /// Vector which stores clients to send information.
@@ -29,8 +33,8 @@ impl Monitor {
         }
     }
 
-    /// Send the executed command to the clients
-    /// that are active.
+    /// Send a command executed by a client to the clients that are active,
+    /// in the same format as redis.
     ///
     /// # Example
     ///
@@ -38,19 +42,40 @@ impl Monitor {
     ///
     /// ```
     /// use redis_server::monitor::Monitor;
+    /// use redis_protocol::command::Command;
     /// let monitor = Monitor::new();
-    /// monitor.send(&"Keys *".to_string());
+    /// monitor.feed(&Command::new("KEYS".to_string(), vec![]), "127.0.0.1:54321");
     /// ```
     ///
-    pub fn send(&self, msg: &str) -> Result<(), &'static str> {
-        let lock_clients = self.clients.write().ok().ok_or("Failed to lock database")?;
-        for client in lock_clients.iter() {
-            match client.send(msg) {
-                Ok(_) => continue,
-                Err(s) => return Err(s),
-            }
+    pub fn feed(&self, command: &Command, addr: &str) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.send(&message(command, addr, time));
+    }
+
+    /// Send a message to the clients that are active.
+    /// The list is copied so the lock is not held while writing to a slow client.
+    /// A client that can not receive it, or times out, is disconnected and removed,
+    /// without affecting the other clients.
+    pub fn send(&self, msg: &str) {
+        let clients = self
+            .clients
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let failed: Vec<Arc<Client>> = clients
+            .into_iter()
+            .filter(|client| client.send(msg).is_err())
+            .collect();
+        if failed.is_empty() {
+            return;
         }
-        Ok(())
+        for client in &failed {
+            client.close();
+        }
+        let mut lock = self.clients.write().unwrap_or_else(|e| e.into_inner());
+        lock.retain(|client| !failed.contains(client));
     }
 
     /// Add a client to the clients list
@@ -75,16 +100,99 @@ impl Monitor {
     /// Return true if the monitor is active or false otherwise.
     /// If any client closed the connection, it will be eliminated from the structure
     pub fn is_active(&self) -> bool {
-        let mut lock = self.clients.write().unwrap();
-        let mut clients: Vec<Arc<Client>> = vec![];
-        for client in lock.iter() {
-            if client.is_closed() {
-                clients.push(client.clone());
-            }
+        let mut lock = self.clients.write().unwrap_or_else(|e| e.into_inner());
+        lock.retain(|client| !client.is_closed());
+        !lock.is_empty()
+    }
+}
+
+/// Builds the line sent to the monitors for a command executed at the given unix time,
+/// like `+1623456789.123456 [0 127.0.0.1:54321] "SET" "k" "v"`. Passwords are hidden.
+fn message(command: &Command, addr: &str, time: Duration) -> String {
+    let arguments: Vec<String> = redacted_arguments(command)
+        .iter()
+        .map(|x| quote(x))
+        .collect();
+    ProtocolType::SimpleString(format!(
+        "{}.{:06} [0 {}] {}",
+        time.as_secs(),
+        time.subsec_micros(),
+        addr,
+        arguments.join(" ")
+    ))
+    .serialize()
+}
+
+/// Quotes an argument like redis does, escaping the quotes, backslashes and
+/// the bytes that are not printable.
+fn quote(argument: &str) -> String {
+    let mut quoted = String::from("\"");
+    for byte in argument.bytes() {
+        match byte {
+            b'\\' => quoted.push_str("\\\\"),
+            b'"' => quoted.push_str("\\\""),
+            b'\n' => quoted.push_str("\\n"),
+            b'\r' => quoted.push_str("\\r"),
+            b'\t' => quoted.push_str("\\t"),
+            0x07 => quoted.push_str("\\a"),
+            0x08 => quoted.push_str("\\b"),
+            b' '..=b'~' => quoted.push(byte as char),
+            _ => quoted.push_str(&format!("\\x{:02x}", byte)),
         }
-        for client in clients {
-            self.do_remove(client, &mut lock);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{command, new_client};
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_send_removes_failed_monitors() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut peers = vec![];
+        let monitor = Monitor::new();
+        for _ in 0..2 {
+            let (stream, client) = new_client(&listener);
+            peers.push(stream);
+            monitor.add(client).unwrap();
         }
-        !lock.is_empty()
+        let failed = monitor.clients.read().unwrap()[0].clone();
+        failed.close();
+
+        monitor.send("+message\r\n");
+
+        let mut buffer = [0; 10];
+        peers[1].read_exact(&mut buffer).unwrap();
+        assert_eq!(&buffer, b"+message\r\n");
+        let clients = monitor.clients.read().unwrap();
+        assert_eq!(clients.len(), 1);
+        assert!(!clients.contains(&failed));
+    }
+
+    #[test]
+    fn test_message() {
+        let time = Duration::from_micros(1_623_456_789_000_042);
+
+        assert_eq!(
+            message(&command(&["SET", "k", "v"]), "127.0.0.1:54321", time),
+            "+1623456789.000042 [0 127.0.0.1:54321] \"SET\" \"k\" \"v\"\r\n"
+        );
+        assert_eq!(
+            message(&command(&["AUTH", "secret"]), "127.0.0.1:54321", time),
+            "+1623456789.000042 [0 127.0.0.1:54321] \"AUTH\" \"(redacted)\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a b"), "\"a b\"");
+        assert_eq!(quote("say \"hi\"\\"), "\"say \\\"hi\\\"\\\\\"");
+        assert_eq!(quote("line\r\n\t"), "\"line\\r\\n\\t\"");
+        assert_eq!(quote("\u{1}ñ"), "\"\\x01\\xc3\\xb1\"");
     }
 }
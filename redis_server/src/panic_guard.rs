@@ -0,0 +1,52 @@
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    static LAST_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Runs the function catching any panic inside it, so a failing command does not kill
+/// the thread running the connection.
+/// If the function panics, returns the message of the panic and its backtrace.
+pub fn catch<F, T>(function: F) -> Result<T, String>
+where
+    F: FnOnce() -> T,
+{
+    HOOK.call_once(install_hook);
+    panic::catch_unwind(AssertUnwindSafe(function)).map_err(|_| {
+        LAST_PANIC
+            .with(|x| x.borrow_mut().take())
+            .unwrap_or_else(|| "unknown panic".to_string())
+    })
+}
+
+/// Keeps the message and backtrace of the last panic of each thread, then runs the previous hook.
+fn install_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = format!("{}\n{}", info, Backtrace::force_capture());
+        LAST_PANIC.with(|x| *x.borrow_mut() = Some(report));
+        previous(info);
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_panic() {
+        let report = catch(|| -> i32 { panic!("comando fallido") }).unwrap_err();
+        assert!(report.contains("comando fallido"));
+        assert!(report.contains("panic_guard"));
+    }
+
+    #[test]
+    fn test_catch_without_panic() {
+        assert_eq!(catch(|| 1 + 1), Ok(2));
+    }
+}
@@ -1,4 +1,3 @@
-use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::collections::{HashMap, HashSet};
 
@@ -19,8 +18,8 @@ impl Subscriber {
         }
     }
 
-    fn send(&self, msg: &str) -> Result<(), &'static str> {
-        self.socket.send(msg)
+    fn send(&self, msg: &ProtocolType) -> Result<(), &'static str> {
+        self.socket.send_value(msg)
     }
 }
 
@@ -72,7 +71,7 @@ impl PublisherSubscriber {
 
     /// Publishes a message to a specific channel. Returns the number of subscribers which received the message.
     pub fn publish(&self, channel: String, message: String) -> Result<u32, &'static str> {
-        let response_str = Self::build_response(&channel, &message);
+        let response = Self::build_response(&channel, &message);
         let mut count = 0;
 
         let subscriptions = self.subscriptions.read().ok().ok_or("Failed to lock")?;
@@ -83,7 +82,7 @@ impl PublisherSubscriber {
             let mut dead_users = Vec::new();
             for client in clients {
                 let subscriber = users.get(client).unwrap();
-                let result = match subscriber.send(&response_str) {
+                let result = match subscriber.send(&response) {
                     Ok(_) => {
                         *count_ref += 1;
                         true
@@ -105,25 +104,23 @@ impl PublisherSubscriber {
         Ok(count)
     }
 
-    /// Build RESP response
-    fn build_response(channel: &str, message: &str) -> String {
-        let mut response = ResponseBuilder::new();
-        response.add(ProtocolType::Array(vec![
+    /// Build the message sent to the subscribers, a push in RESP3
+    fn build_response(channel: &str, message: &str) -> ProtocolType {
+        ProtocolType::Push(vec![
             ProtocolType::String("message".to_string()),
             ProtocolType::String(channel.to_string()),
             ProtocolType::String(message.to_string()),
-        ]));
-        response.serialize()
+        ])
     }
 
     /// Returns the subscriptions list for a specific client
     pub fn get_subscriptions(&self, user: Arc<Client>) -> Result<Vec<String>, &'static str> {
         let users = self.users.read().ok().ok_or("Failed to lock")?;
-        return Ok(if let Some(sub) = users.get(&user) {
+        Ok(if let Some(sub) = users.get(&user) {
             sub.channels.iter().cloned().collect::<Vec<String>>()
         } else {
             Vec::new()
-        });
+        })
     }
 
     /// Unsubscribes a user from all the channels it's subscribed.
@@ -165,21 +162,21 @@ impl PublisherSubscriber {
         let subs = self.subscriptions.read().ok().ok_or("Failed to lock")?;
         Ok(subs
             .keys()
-            .cloned()
             .filter(|x| {
                 let count = self.subscriber_count(x);
                 count.is_ok() && count.unwrap() > 0
             })
+            .cloned()
             .collect::<Vec<String>>())
     }
 
     /// Return the subscriber count for a channel
     pub fn subscriber_count(&self, channel: &str) -> Result<usize, &'static str> {
         let subscriptions = self.subscriptions.read().ok().ok_or("Failed to lock")?;
-        return if let Some(s) = subscriptions.get(channel) {
+        if let Some(s) = subscriptions.get(channel) {
             Ok(s.len())
         } else {
             Ok(0)
-        };
+        }
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::pubsub::PublisherSubscriber;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,9 +9,9 @@ pub fn run(
     pubsub: Arc<PublisherSubscriber>,
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("publish"));
     }
 
     let channel = arguments[0].clone().string()?;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::pubsub::PublisherSubscriber;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,7 +10,7 @@ pub fn run(
     pubsub: Arc<PublisherSubscriber>,
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let subcommand = arguments[0].clone().string()?;
 
     match &subcommand.to_lowercase()[..] {
@@ -31,7 +32,9 @@ pub fn run(
             builder,
         )?,
         _ => {
-            return Err("Unknown subcommand");
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try PUBSUB HELP.",
+            ));
         }
     }
 
@@ -42,7 +45,7 @@ fn numsub(
     pubsub: Arc<PublisherSubscriber>,
     channels: Vec<String>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut arr = Vec::new();
     for channel in channels {
         arr.push(ProtocolType::String(channel.clone()));
@@ -58,9 +61,11 @@ fn channels(
     pubsub: Arc<PublisherSubscriber>,
     pattern_str: String,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let pattern = format!("^{}$", pattern_str.replace("?", "."));
-    let re = Regex::new(&pattern).ok().ok_or("Error parsing the regex")?;
+    let re = Regex::new(&pattern)
+        .ok()
+        .ok_or(RedisError::Generic("Error parsing the regex"))?;
     let channels = pubsub.get_channels()?;
 
     builder.add(ProtocolType::Array(
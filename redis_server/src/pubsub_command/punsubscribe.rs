@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::pubsub::PublisherSubscriber;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -10,8 +11,8 @@ pub fn run(
     pubsub: Arc<PublisherSubscriber>,
     client: Arc<Client>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
-    builder.add(ProtocolType::Array(vec![
+) -> Result<(), RedisError> {
+    builder.add(ProtocolType::Push(vec![
         ProtocolType::String("punsubscribe".to_string()),
         ProtocolType::String("none".to_string()),
         ProtocolType::Integer(pubsub.get_subscriptions(client)?.len() as i64),
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::pubsub::PublisherSubscriber;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,9 +12,9 @@ pub fn run(
     client: Arc<Client>,
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.is_empty() {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("subscribe"));
     }
 
     let channels = arguments
@@ -23,7 +24,7 @@ pub fn run(
 
     for channel in channels {
         let current_subs = pubsub.subscribe(client.clone(), &channel)?;
-        builder.add(ProtocolType::Array(vec![
+        builder.add(ProtocolType::Push(vec![
             ProtocolType::String("subscribe".to_string()),
             ProtocolType::String(channel),
             ProtocolType::Integer(current_subs as i64),
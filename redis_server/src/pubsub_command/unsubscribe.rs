@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::pubsub::PublisherSubscriber;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,7 +12,7 @@ pub fn run(
     client: Arc<Client>,
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut channels = arguments
         .iter()
         .map(|x| x.clone().string().unwrap())
@@ -22,7 +23,7 @@ pub fn run(
     }
 
     if channels.is_empty() {
-        builder.add(ProtocolType::Array(vec![
+        builder.add(ProtocolType::Push(vec![
             ProtocolType::String("unsubscribe".to_string()),
             ProtocolType::String("none".to_string()),
             ProtocolType::Integer(channels.len() as i64),
@@ -32,7 +33,7 @@ pub fn run(
 
     for channel in channels {
         let current_subs = pubsub.unsubscribe_from_channel(client.clone(), &channel)?;
-        builder.add(ProtocolType::Array(vec![
+        builder.add(ProtocolType::Push(vec![
             ProtocolType::String("unsubscribe".to_string()),
             ProtocolType::String(channel),
             ProtocolType::Integer(current_subs as i64),
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+
+/// Struct Backlog. Keeps the last bytes of the replication stream, so a replica
+/// that lost its connection can continue from its offset instead of syncing again.
+pub struct Backlog {
+    buffer: VecDeque<u8>,
+    capacity: usize,
+    /// Offset of the first byte held in the buffer
+    start: u64,
+}
+
+impl Backlog {
+    /// Creates an empty backlog holding up to `capacity` bytes, starting at the given offset
+    pub fn new(capacity: usize, start: u64) -> Self {
+        Backlog {
+            buffer: VecDeque::new(),
+            capacity,
+            start,
+        }
+    }
+
+    /// Appends bytes of the stream, discarding the oldest ones beyond the capacity
+    pub fn append(&mut self, bytes: &[u8]) {
+        self.buffer.extend(bytes);
+        if self.buffer.len() > self.capacity {
+            let excess = self.buffer.len() - self.capacity;
+            self.buffer.drain(..excess);
+            self.start += excess as u64;
+        }
+    }
+
+    /// Returns the bytes of the stream from the given offset to the end,
+    /// or None if they are no longer, or not yet, in the backlog.
+    pub fn since(&self, offset: u64) -> Option<Vec<u8>> {
+        if offset < self.start || offset > self.end() {
+            return None;
+        }
+        Some(
+            self.buffer
+                .iter()
+                .skip((offset - self.start) as usize)
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// Returns the offset of the first byte held
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Returns the number of bytes held
+    pub fn histlen(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the offset of the end of the stream
+    fn end(&self) -> u64 {
+        self.start + self.buffer.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since() {
+        let mut backlog = Backlog::new(8, 10);
+        backlog.append(b"abcd");
+
+        assert_eq!(backlog.since(10).unwrap(), b"abcd".to_vec());
+        assert_eq!(backlog.since(12).unwrap(), b"cd".to_vec());
+        assert_eq!(backlog.since(14).unwrap(), Vec::<u8>::new());
+        assert!(backlog.since(9).is_none());
+        assert!(backlog.since(15).is_none());
+    }
+
+    #[test]
+    fn test_discards_oldest_bytes() {
+        let mut backlog = Backlog::new(4, 0);
+        backlog.append(b"abc");
+        backlog.append(b"def");
+
+        assert_eq!(backlog.start(), 2);
+        assert_eq!(backlog.histlen(), 4);
+        assert!(backlog.since(1).is_none());
+        assert_eq!(backlog.since(2).unwrap(), b"cdef".to_vec());
+    }
+}
@@ -0,0 +1,607 @@
+use crate::client::Client;
+use crate::replication::backlog::Backlog;
+use crate::stats::random_run_id;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::command::Command;
+use redis_protocol::types::ProtocolType;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
+use std::time::Instant;
+
+pub mod backlog;
+pub mod replica;
+pub mod snapshot;
+
+/// Size of the replication backlog, the default used by redis
+pub const BACKLOG_SIZE: usize = 1024 * 1024;
+/// Replication id reported when there is no previous one
+const NO_REPLID: &str = "0000000000000000000000000000000000000000";
+
+/// State of the connection of a replica with its master, as reported by ROLE.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LinkState {
+    Connect,
+    Connecting,
+    Sync,
+    Connected,
+}
+
+impl LinkState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinkState::Connect => "connect",
+            LinkState::Connecting => "connecting",
+            LinkState::Sync => "sync",
+            LinkState::Connected => "connected",
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Role {
+    Master,
+    Replica {
+        host: String,
+        port: u16,
+        state: LinkState,
+    },
+}
+
+/// A replica attached to this server, which receives its stream of write commands.
+/// The bytes are queued for a thread of its own that writes them to the connection,
+/// so a slow replica does not delay the commands of the master nor the other replicas.
+struct AttachedReplica {
+    client: Arc<Client>,
+    queue: Sender<String>,
+    port: u16,
+    offset: u64,
+    last_ack: Instant,
+}
+
+impl AttachedReplica {
+    fn new(client: &Arc<Client>, port: u16, offset: u64) -> Self {
+        let (queue, pending) = mpsc::channel::<String>();
+        let sender = client.clone();
+        thread::spawn(move || {
+            for bytes in pending {
+                if sender.send(&bytes).is_err() {
+                    let _ = sender.kill();
+                    break;
+                }
+            }
+        });
+        AttachedReplica {
+            client: client.clone(),
+            queue,
+            port,
+            offset,
+            last_ack: Instant::now(),
+        }
+    }
+
+    /// Queues bytes for the replica. Returns false if it is no longer connected.
+    fn queue(&self, bytes: String) -> bool {
+        !self.client.is_closed() && self.queue.send(bytes).is_ok()
+    }
+}
+
+/// Struct Stream. The stream of write commands of the server, identified by a
+/// replication id and the offset of its last byte, with its last bytes kept in a backlog.
+/// A replica keeps the stream of its master, so it can continue it after reconnecting.
+pub struct Stream {
+    replid: String,
+    replid2: String,
+    second_offset: Option<u64>,
+    offset: u64,
+    backlog: Backlog,
+    replicas: Vec<AttachedReplica>,
+    ports: HashMap<u64, u16>,
+}
+
+impl Stream {
+    fn new() -> Self {
+        Stream {
+            replid: random_run_id(),
+            replid2: NO_REPLID.to_string(),
+            second_offset: None,
+            offset: 0,
+            backlog: Backlog::new(BACKLOG_SIZE, 0),
+            replicas: Vec::new(),
+            ports: HashMap::new(),
+        }
+    }
+
+    /// Appends bytes to the stream and queues them for the attached replicas.
+    /// Replicas that failed to receive the previous bytes are detached.
+    pub fn feed(&mut self, bytes: &str) {
+        self.offset += bytes.len() as u64;
+        self.backlog.append(bytes.as_bytes());
+        self.replicas.retain(|x| x.queue(bytes.to_string()));
+    }
+
+    /// Returns the offset of the last byte of the stream
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the arguments of the PSYNC sent by a replica to continue this stream
+    pub fn psync_arguments(&self) -> (String, String) {
+        (self.replid.clone(), (self.offset + 1).to_string())
+    }
+
+    /// Replaces the stream with the one of a master after a full sync.
+    /// The replicas of this server are disconnected, as their data is no longer valid.
+    pub fn reset(&mut self, replid: &str, offset: u64) {
+        self.replid = replid.to_string();
+        self.replid2 = NO_REPLID.to_string();
+        self.second_offset = None;
+        self.offset = offset;
+        self.backlog = Backlog::new(BACKLOG_SIZE, offset);
+        for replica in self.replicas.drain(..) {
+            let _ = replica.client.kill();
+        }
+    }
+
+    /// Continues the stream of a master after a partial resync, taking its id if it changed
+    pub fn continue_with(&mut self, replid: &str) {
+        if replid != self.replid {
+            self.switch_id(replid.to_string());
+        }
+    }
+
+    /// Starts a new history of the stream, keeping the previous id so the
+    /// replicas that followed it can still continue it.
+    fn switch_id(&mut self, replid: String) {
+        self.replid2 = std::mem::replace(&mut self.replid, replid);
+        self.second_offset = Some(self.offset + 1);
+    }
+
+    /// Returns the bytes a replica needs to continue the stream from the given offset,
+    /// or None if it needs a full sync.
+    fn continuation(&self, replid: &str, offset: u64) -> Option<Vec<u8>> {
+        let same_history = replid == self.replid
+            || (replid == self.replid2 && self.second_offset.is_some_and(|x| offset <= x));
+        if !same_history || offset == 0 {
+            return None;
+        }
+        self.backlog.since(offset - 1)
+    }
+
+    fn live_replicas(&self) -> impl Iterator<Item = &AttachedReplica> {
+        self.replicas.iter().filter(|x| !x.client.is_closed())
+    }
+}
+
+/// Struct Replication. Holds the role of the server, the stream of write commands sent
+/// to its replicas and, when it is a replica, the state of the link with its master.
+pub struct Replication {
+    role: Mutex<Role>,
+    role_changed: Condvar,
+    generation: AtomicU64,
+    stopped: AtomicBool,
+    stream: Mutex<Stream>,
+}
+
+#[allow(clippy::new_without_default)]
+impl Replication {
+    /// Creates the replication state of a master without replicas
+    pub fn new() -> Self {
+        Replication {
+            role: Mutex::new(Role::Master),
+            role_changed: Condvar::new(),
+            generation: AtomicU64::new(0),
+            stopped: AtomicBool::new(false),
+            stream: Mutex::new(Stream::new()),
+        }
+    }
+
+    /// Locks the stream. Write commands hold it while they run, so they are
+    /// sent to the replicas in the same order they modified the database.
+    pub fn lock(&self) -> MutexGuard<'_, Stream> {
+        self.stream.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns if the server is a replica of another one
+    pub fn is_replica(&self) -> bool {
+        matches!(*self.role(), Role::Replica { .. })
+    }
+
+    /// Returns the role of the server, as reported by HELLO
+    pub fn role_name(&self) -> &'static str {
+        if self.is_replica() {
+            "replica"
+        } else {
+            "master"
+        }
+    }
+
+    /// Makes the server a replica of the given master.
+    /// Returns false if it already is a replica of it.
+    pub fn replicate_from(&self, host: &str, port: u16) -> bool {
+        let mut role = self.role();
+        if let Role::Replica {
+            host: current_host,
+            port: current_port,
+            ..
+        } = &*role
+        {
+            if current_host == host && *current_port == port {
+                return false;
+            }
+        }
+        *role = Role::Replica {
+            host: host.to_string(),
+            port,
+            state: LinkState::Connect,
+        };
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.role_changed.notify_all();
+        true
+    }
+
+    /// Turns a replica into a master. Its stream gets a new id, keeping the one
+    /// of its former master so the other replicas can continue from it.
+    pub fn promote(&self) {
+        {
+            let mut role = self.role();
+            if let Role::Master = *role {
+                return;
+            }
+            *role = Role::Master;
+            self.generation.fetch_add(1, Ordering::SeqCst);
+        }
+        self.lock().switch_id(random_run_id());
+    }
+
+    /// Stops the link with the master, when the server shuts down
+    pub fn stop(&self) {
+        let _role = self.role();
+        self.stopped.store(true, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.role_changed.notify_all();
+    }
+
+    /// Waits until the server becomes a replica, and returns the generation of the
+    /// role together with the address of the master. Returns None once stopped.
+    pub fn wait_for_master(&self) -> Option<(u64, String, u16)> {
+        let mut role = self.role();
+        loop {
+            if self.stopped.load(Ordering::SeqCst) {
+                return None;
+            }
+            if let Role::Replica { host, port, .. } = &*role {
+                return Some((self.generation.load(Ordering::SeqCst), host.clone(), *port));
+            }
+            role = self
+                .role_changed
+                .wait(role)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Returns if the role has not changed since the given generation
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// Updates the state of the link with the master, if the role did not change
+    pub fn set_link_state(&self, generation: u64, new_state: LinkState) {
+        let mut role = self.role();
+        if let Role::Replica { state, .. } = &mut *role {
+            if self.is_current(generation) {
+                *state = new_state;
+            }
+        }
+    }
+
+    /// Registers the port a replica listens on, sent with REPLCONF listening-port
+    pub fn set_listening_port(&self, client: &Client, port: u16) {
+        self.lock().ports.insert(client.id(), port);
+    }
+
+    /// Registers the offset processed by a replica, sent with REPLCONF ACK
+    pub fn ack(&self, client: &Client, offset: u64) {
+        let mut stream = self.lock();
+        if let Some(replica) = stream.replicas.iter_mut().find(|x| *x.client == *client) {
+            replica.offset = offset;
+            replica.last_ack = Instant::now();
+        }
+    }
+
+    /// Answers the PSYNC of a replica and attaches it to the stream.
+    /// If the replica can continue the stream from its offset it receives the missing bytes,
+    /// otherwise it receives a snapshot of the database and the stream continues after it.
+    /// The snapshot is taken while holding the stream, but it is sent by the thread of the
+    /// replica once the stream is released, followed by the commands executed meanwhile.
+    pub fn sync(
+        &self,
+        client: &Arc<Client>,
+        replid: &str,
+        offset: Option<u64>,
+        data: &DataStorage,
+    ) -> Result<(), &'static str> {
+        let mut stream = self.lock();
+        let port = stream.ports.remove(&client.id()).unwrap_or(0);
+        let replica = AttachedReplica::new(client, port, stream.offset);
+        let queued = match offset.and_then(|x| stream.continuation(replid, x)) {
+            Some(missing) => {
+                replica.queue(format!("+CONTINUE {}\r\n", stream.replid))
+                    && replica.queue(String::from_utf8_lossy(&missing).to_string())
+            }
+            None => {
+                replica.queue(format!(
+                    "+FULLRESYNC {} {}\r\n",
+                    stream.replid, stream.offset
+                )) && replica.queue(ProtocolType::String(snapshot::encode(data)).serialize())
+            }
+        };
+        if !queued {
+            return Err("Error while writing to client");
+        }
+
+        client.set_replica_mode(true);
+        stream.replicas.push(replica);
+        Ok(())
+    }
+
+    /// Returns the reply of ROLE
+    pub fn role_reply(&self) -> ProtocolType {
+        let role = self.role().clone();
+        let stream = self.lock();
+        match role {
+            Role::Master => ProtocolType::Array(vec![
+                ProtocolType::String("master".to_string()),
+                ProtocolType::Integer(stream.offset as i64),
+                ProtocolType::Array(
+                    stream
+                        .live_replicas()
+                        .map(|x| {
+                            ProtocolType::Array(vec![
+                                ProtocolType::String(ip(&x.client)),
+                                ProtocolType::String(x.port.to_string()),
+                                ProtocolType::String(x.offset.to_string()),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ]),
+            Role::Replica { host, port, state } => ProtocolType::Array(vec![
+                ProtocolType::String("slave".to_string()),
+                ProtocolType::String(host),
+                ProtocolType::Integer(port as i64),
+                ProtocolType::String(state.name().to_string()),
+                ProtocolType::Integer(stream.offset as i64),
+            ]),
+        }
+    }
+
+    /// Returns the lines of the replication section of INFO
+    pub fn info(&self) -> Vec<String> {
+        let role = self.role().clone();
+        let stream = self.lock();
+        let mut lines = vec!["# Replication".to_string()];
+        match role {
+            Role::Master => lines.push("role:master".to_string()),
+            Role::Replica { host, port, state } => lines.extend(vec![
+                "role:slave".to_string(),
+                format!("master_host:{}", host),
+                format!("master_port:{}", port),
+                format!(
+                    "master_link_status:{}",
+                    if state == LinkState::Connected {
+                        "up"
+                    } else {
+                        "down"
+                    }
+                ),
+                format!(
+                    "master_sync_in_progress:{}",
+                    (state == LinkState::Sync) as u8
+                ),
+                format!("slave_repl_offset:{}", stream.offset),
+                "slave_read_only:1".to_string(),
+            ]),
+        }
+
+        lines.push(format!(
+            "connected_slaves:{}",
+            stream.live_replicas().count()
+        ));
+        for (i, replica) in stream.live_replicas().enumerate() {
+            lines.push(format!(
+                "slave{}:ip={},port={},state=online,offset={},lag={}",
+                i,
+                ip(&replica.client),
+                replica.port,
+                replica.offset,
+                replica.last_ack.elapsed().as_secs()
+            ));
+        }
+        lines.extend(vec![
+            format!("master_replid:{}", stream.replid),
+            format!("master_replid2:{}", stream.replid2),
+            format!("master_repl_offset:{}", stream.offset),
+            format!(
+                "second_repl_offset:{}",
+                stream.second_offset.map_or(-1, |x| x as i64)
+            ),
+            "repl_backlog_active:1".to_string(),
+            format!("repl_backlog_size:{}", BACKLOG_SIZE),
+            format!(
+                "repl_backlog_first_byte_offset:{}",
+                stream.backlog.start() + 1
+            ),
+            format!("repl_backlog_histlen:{}", stream.backlog.histlen()),
+        ]);
+        lines
+    }
+
+    fn role(&self) -> MutexGuard<'_, Role> {
+        self.role.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Encodes a command the way it is sent to the replicas, as an array of bulk strings
+pub fn encode(cmd: &Command) -> String {
+    let mut words = vec![ProtocolType::String(cmd.name())];
+    words.extend(
+        cmd.arguments()
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string())),
+    );
+    ProtocolType::Array(words).serialize()
+}
+
+/// Returns the ip of the remote end of a connection
+fn ip(client: &Client) -> String {
+    client
+        .addr()
+        .rsplit_once(':')
+        .map(|(ip, _)| ip.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+    use crate::test_util::new_client;
+    use redis_protocol::parser::decoder::Decoder;
+    use std::io::Read;
+    use std::net::{TcpListener, TcpStream};
+    use std::time::Duration;
+
+    /// Reads from the stream until the decoder holds the given number of frames
+    fn read_frames(stream: &mut TcpStream, count: usize) -> Vec<ProtocolType> {
+        let mut decoder = Decoder::new();
+        let mut frames = Vec::new();
+        let mut buffer = [0; 1024];
+        while frames.len() < count {
+            match decoder.next_frame().unwrap() {
+                Some(frame) => frames.push(frame),
+                None => {
+                    let read = stream.read(&mut buffer).unwrap();
+                    assert!(read > 0);
+                    decoder.feed(&buffer[..read]);
+                }
+            }
+        }
+        frames
+    }
+
+    fn set(key: &str, value: &str) -> Command {
+        Command::new(
+            "SET".to_string(),
+            vec![
+                ProtocolType::String(key.to_string()),
+                ProtocolType::String(value.to_string()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_full_sync_and_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (mut stream, client) = new_client(&listener);
+        let replication = Replication::new();
+        let data = DataStorage::new();
+        data.set("key", Value::String("value".to_string())).unwrap();
+        replication.lock().feed(&encode(&set("key", "value")));
+
+        replication.sync(&client, "?", None, &data).unwrap();
+        replication.lock().feed(&encode(&set("other", "1")));
+
+        let frames = read_frames(&mut stream, 3);
+        let offset = encode(&set("key", "value")).len();
+        assert_eq!(
+            frames[0].to_string(),
+            format!("FULLRESYNC {} {}", replication.lock().replid, offset)
+        );
+        let copy = DataStorage::new();
+        snapshot::load(&copy, &frames[1].to_string()).unwrap();
+        assert_eq!(copy.get("key").unwrap().string().unwrap(), "value");
+        assert_eq!(frames[2].to_string(), "[SET, other, 1]");
+        assert!(client.in_replica_mode());
+    }
+
+    #[test]
+    fn test_partial_resync() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (mut stream, client) = new_client(&listener);
+        let replication = Replication::new();
+        let first = encode(&set("a", "1"));
+        replication.lock().feed(&first);
+        replication.lock().feed(&encode(&set("b", "2")));
+        let replid = replication.lock().replid.clone();
+
+        replication
+            .sync(
+                &client,
+                &replid,
+                Some(first.len() as u64 + 1),
+                &DataStorage::new(),
+            )
+            .unwrap();
+
+        let frames = read_frames(&mut stream, 2);
+        assert_eq!(frames[0].to_string(), format!("CONTINUE {}", replid));
+        assert_eq!(frames[1].to_string(), "[SET, b, 2]");
+    }
+
+    #[test]
+    fn test_stalled_replica_does_not_block_the_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stalled, client) = new_client(&listener);
+        let replication = Replication::new();
+        replication
+            .sync(&client, "?", None, &DataStorage::new())
+            .unwrap();
+
+        let start = Instant::now();
+        let value = "x".repeat(1024 * 1024);
+        for _ in 0..16 {
+            replication.lock().feed(&encode(&set("key", &value)));
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(replication.lock().live_replicas().count(), 1);
+    }
+
+    #[test]
+    fn test_continuation_needs_same_history() {
+        let mut stream = Stream::new();
+        stream.feed(&encode(&set("a", "1")));
+        let replid = stream.replid.clone();
+
+        assert!(stream.continuation(&replid, 1).is_some());
+        assert!(stream.continuation("other", 1).is_none());
+        assert!(stream.continuation(&replid, stream.offset + 2).is_none());
+
+        stream.switch_id(random_run_id());
+        assert!(stream.continuation(&replid, stream.offset + 1).is_some());
+        stream.feed(&encode(&set("b", "2")));
+        assert!(stream.continuation(&replid, stream.offset + 1).is_none());
+    }
+
+    #[test]
+    fn test_roles() {
+        let replication = Replication::new();
+        assert_eq!(replication.role_name(), "master");
+
+        assert!(replication.replicate_from("127.0.0.1", 6380));
+        assert!(!replication.replicate_from("127.0.0.1", 6380));
+        assert!(replication.is_replica());
+        assert_eq!(
+            replication.role_reply().to_string(),
+            "[slave, 127.0.0.1, 6380, connect, 0]"
+        );
+
+        let replid = replication.lock().replid.clone();
+        replication.promote();
+        assert_eq!(replication.role_name(), "master");
+        assert_eq!(replication.lock().replid2, replid);
+        assert_eq!(replication.role_reply().to_string(), "[master, 0, []]");
+    }
+}
@@ -0,0 +1,208 @@
+use crate::config::configuration::Configuration;
+use crate::execution::Execution;
+use crate::logging::logger::Logger;
+use crate::replication::{snapshot, LinkState, Replication};
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::parser::decoder::decode;
+use redis_protocol::types::ProtocolType;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Time to wait before connecting again to the master
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Time between the acknowledgements of the processed offset sent to the master
+const ACK_PERIOD: Duration = Duration::from_secs(1);
+/// Longest wait for a reply of the master during the handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Size of the chunks read from the master
+const READ_CHUNK: usize = 16 * 1024;
+
+/// Runs the link of the server with its master, until the server shuts down.
+/// While the server is a replica it connects to the master, syncs its data and applies
+/// the stream of write commands it receives, connecting again if the link breaks.
+pub fn run(
+    execution: Arc<Execution>,
+    replication: Arc<Replication>,
+    data: Arc<DataStorage>,
+    config: Arc<Mutex<Configuration>>,
+    logger: Arc<Logger>,
+) {
+    while let Some((generation, host, port)) = replication.wait_for_master() {
+        let link = ReplicaLink {
+            execution: &execution,
+            replication: &replication,
+            data: &data,
+            config: &config,
+            logger: &logger,
+            generation,
+        };
+        link.log(format!("Connecting to MASTER {}:{}", host, port));
+        if let Err(e) = link.follow(&format!("{}:{}", host, port)) {
+            link.log(format!("Replication link with MASTER lost: {}", e));
+        }
+        replication.set_link_state(generation, LinkState::Connect);
+        if replication.is_current(generation) {
+            thread::sleep(RECONNECT_DELAY);
+        }
+    }
+}
+
+/// The link with the master for a generation of the role of the server.
+struct ReplicaLink<'a> {
+    execution: &'a Execution,
+    replication: &'a Replication,
+    data: &'a DataStorage,
+    config: &'a Mutex<Configuration>,
+    logger: &'a Logger,
+    generation: u64,
+}
+
+impl ReplicaLink<'_> {
+    /// Syncs with the master and applies its stream until the role changes or the link breaks
+    fn follow(&self, addr: &str) -> Result<(), String> {
+        self.replication
+            .set_link_state(self.generation, LinkState::Connecting);
+        let mut master = MasterConnection::connect(addr)?;
+        let (masterauth, port) = {
+            let config = self.config.lock().unwrap();
+            (config.get_masterauth().cloned(), config.get_port())
+        };
+        if let Some(password) = masterauth {
+            master.query(&["AUTH", &password])?;
+        }
+        master.query(&["PING"])?;
+        master.query(&["REPLCONF", "listening-port", &port.to_string()])?;
+
+        self.replication
+            .set_link_state(self.generation, LinkState::Sync);
+        let (replid, offset) = self.replication.lock().psync_arguments();
+        let reply = master.query(&["PSYNC", &replid, &offset])?.to_string();
+        match reply.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["FULLRESYNC", replid, offset] => {
+                let offset = offset
+                    .parse()
+                    .map_err(|_| format!("Invalid reply to PSYNC: {}", reply))?;
+                let snapshot = master.reply()?.to_string();
+                let mut stream = self.replication.lock();
+                if !self.replication.is_current(self.generation) {
+                    return Ok(());
+                }
+                snapshot::load(self.data, &snapshot).map_err(|e| e.to_string())?;
+                stream.reset(replid, offset);
+                self.log("MASTER <-> REPLICA sync: Finished with success".to_string());
+            }
+            ["CONTINUE", replid] => {
+                self.replication.lock().continue_with(replid);
+                self.log(
+                    "MASTER <-> REPLICA sync: Master accepted a Partial Resynchronization"
+                        .to_string(),
+                );
+            }
+            _ => return Err(format!("Invalid reply to PSYNC: {}", reply)),
+        }
+        self.replication
+            .set_link_state(self.generation, LinkState::Connected);
+
+        master.set_timeout(ACK_PERIOD)?;
+        let mut last_ack = Instant::now();
+        while self.replication.is_current(self.generation) {
+            if let Some((frame, bytes)) = master.next_frame()? {
+                if let Err(e) = self
+                    .execution
+                    .replicate(frame, &String::from_utf8_lossy(&bytes))
+                {
+                    self.log(format!("Error applying a command of the MASTER: {}", e));
+                }
+            }
+            if last_ack.elapsed() >= ACK_PERIOD {
+                let offset = self.replication.lock().offset().to_string();
+                master.send(&["REPLCONF", "ACK", &offset])?;
+                last_ack = Instant::now();
+            }
+        }
+        Ok(())
+    }
+
+    fn log(&self, msg: String) {
+        println!("{}", msg);
+        let _ = self.logger.log(&msg);
+    }
+}
+
+/// A blocking connection with the master, which keeps the bytes of every frame
+/// it reads so the replica can count them in its offset.
+struct MasterConnection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+}
+
+impl MasterConnection {
+    fn connect(addr: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(addr).map_err(|e| e.to_string())?;
+        let master = MasterConnection {
+            stream,
+            buffer: Vec::new(),
+        };
+        master.set_timeout(HANDSHAKE_TIMEOUT)?;
+        Ok(master)
+    }
+
+    fn set_timeout(&self, timeout: Duration) -> Result<(), String> {
+        self.stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|e| e.to_string())
+    }
+
+    fn send(&mut self, arguments: &[&str]) -> Result<(), String> {
+        let command = ProtocolType::Array(
+            arguments
+                .iter()
+                .map(|x| ProtocolType::String(x.to_string()))
+                .collect(),
+        );
+        self.stream
+            .write_all(command.serialize().as_bytes())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Sends a command and waits for its reply. An error reply is returned as an Err.
+    fn query(&mut self, arguments: &[&str]) -> Result<ProtocolType, String> {
+        self.send(arguments)?;
+        match self.reply()? {
+            ProtocolType::Error(e) => Err(format!("{} replied {}", arguments[0], e)),
+            reply => Ok(reply),
+        }
+    }
+
+    /// Waits for the next frame, failing if it does not arrive before the timeout
+    fn reply(&mut self) -> Result<ProtocolType, String> {
+        match self.next_frame()? {
+            Some((frame, _)) => Ok(frame),
+            None => Err("Timeout waiting for the MASTER".to_string()),
+        }
+    }
+
+    /// Returns the next frame together with its bytes, or None if it did not
+    /// arrive before the timeout.
+    fn next_frame(&mut self) -> Result<Option<(ProtocolType, Vec<u8>)>, String> {
+        loop {
+            if let Some((frame, used)) = decode(&self.buffer)? {
+                let bytes = self.buffer.drain(..used).collect();
+                return Ok(Some((frame, bytes)));
+            }
+            let mut chunk = [0; READ_CHUNK];
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err("Connection closed by the MASTER".to_string()),
+                Ok(read) => self.buffer.extend_from_slice(&chunk[..read]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::dump;
+use redis_protocol::parser::decoder::decode;
+use redis_protocol::types::ProtocolType;
+use std::time::Duration;
+
+const SNAPSHOT_ERROR: RedisError = RedisError::Generic("Invalid replication snapshot");
+
+/// Encodes every key of the database, to be sent to a replica in a full sync.
+/// Each key is an array with its name, its expiration in milliseconds since unix epoch
+/// (or -1 if it has none) and its value serialized like DUMP does.
+pub fn encode(data: &DataStorage) -> String {
+    let lock = data.read();
+    let mut snapshot = String::new();
+    for (key, entry) in lock.iter() {
+        let (value, expiration) = match (entry.value(), entry.key_expiration()) {
+            (Ok(value), Ok(expiration)) => (value, expiration),
+            _ => continue,
+        };
+        let expiration = expiration
+            .map(|x| x.as_millis().to_string())
+            .unwrap_or_else(|| "-1".to_string());
+        snapshot.push_str(
+            &ProtocolType::Array(vec![
+                ProtocolType::String(key.to_string()),
+                ProtocolType::String(expiration),
+                ProtocolType::String(dump::serialize(&value)),
+            ])
+            .serialize(),
+        );
+    }
+    snapshot
+}
+
+/// Replaces the content of the database with the keys of a snapshot created by `encode`.
+pub fn load(data: &DataStorage, snapshot: &str) -> Result<(), RedisError> {
+    data.delete_all()?;
+    let mut pending = snapshot.as_bytes();
+    while !pending.is_empty() {
+        let (frame, used) = match decode(pending) {
+            Ok(Some(frame)) => frame,
+            _ => return Err(SNAPSHOT_ERROR),
+        };
+        pending = &pending[used..];
+
+        let fields: Vec<String> = frame
+            .array()
+            .map_err(|_| SNAPSHOT_ERROR)?
+            .iter()
+            .map(|x| x.to_string())
+            .collect();
+        if fields.len() != 3 {
+            return Err(SNAPSHOT_ERROR);
+        }
+        let expiration = match fields[1].parse::<i64>() {
+            Ok(-1) => None,
+            Ok(millis) if millis >= 0 => Some(Duration::from_millis(millis as u64)),
+            _ => return Err(SNAPSHOT_ERROR),
+        };
+        data.restore(&fields[0], dump::deserialize(&fields[2])?, expiration, true)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_encode_and_load() {
+        let data = DataStorage::new();
+        data.set("key", Value::String("value".to_string())).unwrap();
+        data.set("list", Value::Vec(vec!["a".to_string(), "b c".to_string()]))
+            .unwrap();
+        let expiration =
+            SystemTime::now().duration_since(UNIX_EPOCH).unwrap() + Duration::from_secs(100);
+        data.restore(
+            "temporal",
+            Value::String("x".to_string()),
+            Some(expiration),
+            false,
+        )
+        .unwrap();
+
+        let replica = DataStorage::new();
+        replica
+            .set("old", Value::String("gone".to_string()))
+            .unwrap();
+        load(&replica, &encode(&data)).unwrap();
+
+        assert_eq!(replica.len().unwrap(), 3);
+        assert!(replica.get("old").is_none());
+        assert_eq!(replica.get("key").unwrap().string().unwrap(), "value");
+        assert_eq!(
+            replica.get("list").unwrap().array().unwrap(),
+            vec!["a".to_string(), "b c".to_string()]
+        );
+        let (ttl, _) = replica.get_with_expiration("temporal").unwrap();
+        assert_eq!(
+            ttl.unwrap().as_millis(),
+            Duration::from_millis(expiration.as_millis() as u64).as_millis()
+        );
+    }
+
+    #[test]
+    fn test_load_invalid_snapshot() {
+        let data = DataStorage::new();
+
+        assert_eq!(load(&data, "*1\r\n$3\r\nkey\r\n"), Err(SNAPSHOT_ERROR));
+        assert_eq!(load(&data, "garbage"), Err(SNAPSHOT_ERROR));
+        assert!(load(&data, "").is_ok());
+    }
+}
@@ -1,17 +1,24 @@
+use crate::acl::users::Acl;
+use crate::client_registry::ClientRegistry;
+use crate::cluster::{bus, Cluster};
 use crate::config::configuration::Configuration;
 use crate::execution::Execution;
+use crate::latency::{LatencyMonitor, SAVE_EVENT};
 use crate::listener_thread::ListenerThread;
 use crate::logging::logger::Logger;
 use crate::monitor::Monitor;
 use crate::pubsub::PublisherSubscriber;
+use crate::replication::{replica, Replication};
+use crate::slowlog::SlowLog;
+use crate::stats::ServerStats;
 use crate::storage::data_storage::DataStorage;
 use std::net::TcpStream;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::Duration;
 use std::time::SystemTime;
+use std::time::{Duration, Instant};
 
 // Globals
 const DURATION: u64 = 600;
@@ -27,6 +34,10 @@ pub struct Server {
     config: Arc<Mutex<Configuration>>,
     sys_time: Arc<SystemTime>,
     logger: Arc<Logger>,
+    stats: Arc<ServerStats>,
+    replication: Arc<Replication>,
+    cluster: Arc<Cluster>,
+    latency: Arc<LatencyMonitor>,
     sender: Option<Sender<()>>,
     receiver: Option<Receiver<()>>,
     is_running: bool,
@@ -34,6 +45,11 @@ pub struct Server {
 
 impl Server {
     pub fn new(config: Configuration, logger: Arc<Logger>) -> Self {
+        let cluster = Cluster::new(
+            config.get_cluster_enabled(),
+            config.get_ip(),
+            config.get_port(),
+        );
         Server {
             addr: config.get_ip().to_string(),
             handle: None,
@@ -42,6 +58,10 @@ impl Server {
             config: Arc::new(Mutex::new(config)),
             sys_time: Arc::new(SystemTime::now()),
             logger,
+            stats: Arc::new(ServerStats::new()),
+            replication: Arc::new(Replication::new()),
+            cluster: Arc::new(cluster),
+            latency: Arc::new(LatencyMonitor::new()),
             sender: None,
             receiver: None,
             is_running: false,
@@ -56,6 +76,9 @@ impl Server {
             println!("Error loading data from dbfile");
         };
         let addr_and_port = self.get_addr_and_port();
+        let clients = Arc::new(ClientRegistry::new());
+        let acl = self.load_acl();
+        let slowlog = Arc::new(SlowLog::new());
         let execution = Arc::new(Execution::new(
             self.data.clone(),
             self.config.clone(),
@@ -63,28 +86,63 @@ impl Server {
             self.logger.clone(),
             Arc::new(PublisherSubscriber::new()),
             Monitor::new(),
+            self.stats.clone(),
+            clients.clone(),
+            acl,
+            self.replication.clone(),
+            self.cluster.clone(),
+            slowlog.clone(),
+            self.latency.clone(),
         ));
+        let replication = self.replication.clone();
+        let data = self.data.clone();
+        let config = self.config.clone();
+        let logger = self.logger.clone();
+        let execution_cpy = execution.clone();
+        thread::spawn(move || replica::run(execution_cpy, replication, data, config, logger));
+        if self.cluster.is_enabled() {
+            let cluster = self.cluster.clone();
+            let config = self.config.clone();
+            let logger = self.logger.clone();
+            thread::spawn(move || bus::run(cluster, config, logger));
+        }
         let ttl = self.config.lock().unwrap().get_timeout();
         let logger_cpy = self.logger.clone();
         let config_cpy = self.config.clone();
+        let stats_cpy = self.stats.clone();
         let (server_sender, listener_receiver) = channel();
         let (listener_sender, server_receiver) = channel();
         let handle = thread::spawn(move || {
-            let listener = ListenerThread::new(addr_and_port, execution, logger_cpy, config_cpy);
+            let listener = ListenerThread::new(
+                addr_and_port,
+                execution,
+                logger_cpy,
+                config_cpy,
+                stats_cpy,
+                clients,
+                slowlog,
+            );
             listener.run(ttl, listener_sender, listener_receiver);
         });
         let data_storage = self.data.clone();
         let configuration = self.config.clone();
+        let stats = self.stats.clone();
+        let latency = self.latency.clone();
         let handle_store_data = thread::spawn(move || loop {
-            loop {
-                let dbfilename = configuration.lock().unwrap().get_dbfilename().clone();
-                let result = data_storage.save_data(&dbfilename);
-                if result.is_err() {
-                    println!("Error saving data from dbfile");
-                };
-                let ten_mins = Duration::from_secs(DURATION);
-                thread::sleep(ten_mins);
-            }
+            let dbfilename = configuration.lock().unwrap().get_dbfilename().clone();
+            let start = Instant::now();
+            let result = data_storage.save_data(&dbfilename);
+            let threshold = configuration
+                .lock()
+                .unwrap()
+                .get_latency_monitor_threshold();
+            latency.record(SAVE_EVENT, start.elapsed(), threshold);
+            stats.saved(result.is_ok());
+            if result.is_err() {
+                println!("Error saving data from dbfile");
+            };
+            let ten_mins = Duration::from_secs(DURATION);
+            thread::sleep(ten_mins);
         });
         self.sender = Some(server_sender);
         self.receiver = Some(server_receiver);
@@ -92,6 +150,22 @@ impl Server {
         self.handle_store_data = Some(handle_store_data);
     }
 
+    /// Creates the ACL users, setting requirepass as the password of the default user
+    /// and loading the users from the ACL file if there is one.
+    fn load_acl(&self) -> Arc<Acl> {
+        let acl = Arc::new(Acl::new());
+        let config = self.config.lock().unwrap();
+        if acl.set_requirepass(config.get_requirepass()).is_err() {
+            println!("Error setting the password of the default user");
+        }
+        if let Some(aclfile) = config.get_aclfile() {
+            if acl.load(aclfile).is_err() {
+                println!("Error loading users from aclfile");
+            }
+        }
+        acl
+    }
+
     /// Returns the joined address and port
     fn get_addr_and_port(&self) -> String {
         self.addr.clone() + ":" + &self.config.lock().unwrap().get_port().to_string()
@@ -117,8 +191,11 @@ impl Server {
         self.handle.take().unwrap().join().unwrap();
     }
 
-    /// Stops listening for new connections
+    /// Stops listening for new connections, the link with the master and the gossip
+    /// with the other nodes of the cluster, if any
     pub fn shutdown(&mut self) {
+        self.replication.stop();
+        self.cluster.stop();
         if let Some(sender) = &self.sender {
             match sender.send(()) {
                 Ok(_) => {}
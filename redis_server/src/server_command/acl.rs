@@ -0,0 +1,187 @@
+use crate::acl::categories;
+use crate::acl::users::{Acl, DEFAULT_USER};
+use crate::client::Client;
+use crate::config::configuration::Configuration;
+use crate::error::RedisError;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::{Arc, Mutex};
+
+const NO_ACLFILE: RedisError =
+    RedisError::Generic("This Redis instance is not configured to use an ACL file.");
+
+/// Includes ACL SETUSER, GETUSER, DELUSER, LIST, USERS, WHOAMI, CAT, LOAD, SAVE and HELP
+/// ACL SETUSER: creates or modifies a user with the given rules.
+/// ACL GETUSER: returns the flags, passwords, commands, keys and channels of a user.
+/// ACL LOAD / ACL SAVE: reads or writes the users from the file set with aclfile.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    acl: &Arc<Acl>,
+    config: &Arc<Mutex<Configuration>>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("acl"));
+    }
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    match &arguments[0].to_ascii_lowercase()[..] {
+        "setuser" if arguments.len() > 1 => {
+            acl.set_user(&arguments[1], &arguments[2..])?;
+            builder.add(ok());
+        }
+        "getuser" if arguments.len() == 2 => match acl.get_user(&arguments[1])? {
+            Some(user) => builder.add(ProtocolType::Array(vec![
+                ProtocolType::String("flags".to_string()),
+                strings(user.flags()),
+                ProtocolType::String("passwords".to_string()),
+                strings(user.passwords()),
+                ProtocolType::String("commands".to_string()),
+                ProtocolType::String(user.command_rules()),
+                ProtocolType::String("keys".to_string()),
+                ProtocolType::String(user.key_rules()),
+                ProtocolType::String("channels".to_string()),
+                ProtocolType::String(user.channel_rules()),
+            ])),
+            None => builder.add(ProtocolType::Nil()),
+        },
+        "deluser" if arguments.len() > 1 => {
+            let deleted = acl.delete_users(&arguments[1..])?;
+            builder.add(ProtocolType::Integer(deleted as i64));
+        }
+        "list" if arguments.len() == 1 => {
+            builder.add(strings(acl.users()?.iter().map(|x| x.describe()).collect()))
+        }
+        "users" if arguments.len() == 1 => builder.add(strings(
+            acl.users()?.iter().map(|x| x.name().to_string()).collect(),
+        )),
+        "whoami" if arguments.len() == 1 => builder.add(ProtocolType::String(
+            client.user().unwrap_or_else(|| DEFAULT_USER.to_string()),
+        )),
+        "cat" if arguments.len() == 1 => builder.add(strings(
+            categories::CATEGORIES
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+        )),
+        "cat" if arguments.len() == 2 => {
+            let commands = categories::commands_in(&arguments[1].to_ascii_lowercase())
+                .ok_or(RedisError::Generic("Unknown category"))?;
+            builder.add(strings(commands.iter().map(|x| x.to_string()).collect()))
+        }
+        "load" if arguments.len() == 1 => {
+            let aclfile = config.lock().unwrap().get_aclfile().cloned();
+            acl.load(&aclfile.ok_or(NO_ACLFILE)?)?;
+            builder.add(ok());
+        }
+        "save" if arguments.len() == 1 => {
+            let aclfile = config.lock().unwrap().get_aclfile().cloned();
+            acl.save(&aclfile.ok_or(NO_ACLFILE)?)?;
+            builder.add(ok());
+        }
+        "help" if arguments.len() == 1 => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try ACL HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn ok() -> ProtocolType {
+    ProtocolType::SimpleString("OK".to_string())
+}
+
+fn strings(values: Vec<String>) -> ProtocolType {
+    ProtocolType::Array(values.into_iter().map(ProtocolType::String).collect())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "ACL <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "CAT [<category>]",
+        "    List all commands that belong to <category>, or all command categories",
+        "    when no category is specified.",
+        "DELUSER <username> [<username> ...]",
+        "    Delete a list of users.",
+        "GETUSER <username>",
+        "    Get the user's details.",
+        "LIST",
+        "    Show users details in config file format.",
+        "LOAD",
+        "    Reload users from the ACL file.",
+        "SAVE",
+        "    Save the current config to the ACL file.",
+        "SETUSER <username> <attribute> [<attribute> ...]",
+        "    Create or modify a user with the specified attributes.",
+        "USERS",
+        "    List all the registered usernames.",
+        "WHOAMI",
+        "    Return the current connection username.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_client;
+    use std::net::TcpListener;
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    fn acl_command(values: &[&str], acl: &Arc<Acl>) -> Result<String, RedisError> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let config = Arc::new(Mutex::new(Configuration::new()));
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, arguments(values), client, acl, &config)?;
+        Ok(builder.serialize())
+    }
+
+    #[test]
+    fn test_setuser_and_getuser() {
+        let acl = Arc::new(Acl::new());
+        acl_command(&["SETUSER", "alice", "on", "~cache:*", "+get"], &acl).unwrap();
+
+        let user = acl_command(&["GETUSER", "alice"], &acl).unwrap();
+        assert!(user.contains("$5\r\nflags\r\n*1\r\n$2\r\non\r\n"));
+        assert!(user.contains("$10\r\n-@all +get\r\n"));
+        assert!(user.contains("$8\r\n~cache:*\r\n"));
+        assert_eq!(acl_command(&["GETUSER", "bob"], &acl).unwrap(), "$-1\r\n");
+    }
+
+    #[test]
+    fn test_users_and_deluser() {
+        let acl = Arc::new(Acl::new());
+        acl_command(&["SETUSER", "alice"], &acl).unwrap();
+        assert_eq!(
+            acl_command(&["USERS"], &acl).unwrap(),
+            "*2\r\n$5\r\nalice\r\n$7\r\ndefault\r\n"
+        );
+        assert_eq!(
+            acl_command(&["DELUSER", "alice", "bob"], &acl).unwrap(),
+            ":1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_whoami_and_load_without_file() {
+        let acl = Arc::new(Acl::new());
+        assert_eq!(acl_command(&["WHOAMI"], &acl).unwrap(), "$7\r\ndefault\r\n");
+        assert_eq!(acl_command(&["LOAD"], &acl), Err(NO_ACLFILE));
+    }
+}
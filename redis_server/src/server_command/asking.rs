@@ -0,0 +1,20 @@
+use crate::client::Client;
+use crate::cluster::Cluster;
+use crate::error::RedisError;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// ASKING
+/// Lets the next command of the client use the keys of a slot this node is importing,
+/// after being redirected with an ASK error by the node the slot is migrated from.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    client: Arc<Client>,
+    cluster: &Arc<Cluster>,
+) -> Result<(), RedisError> {
+    cluster.check_enabled()?;
+    client.set_asking();
+    builder.add(ProtocolType::SimpleString("OK".to_string()));
+    Ok(())
+}
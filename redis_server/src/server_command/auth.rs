@@ -0,0 +1,107 @@
+use crate::acl::users::{Acl, DEFAULT_USER};
+use crate::client::Client;
+use crate::error::RedisError;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Authenticates the connection as an ACL user.
+/// AUTH password authenticates as the default user, whose password is set with requirepass.
+/// AUTH username password authenticates as any other user.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    acl: &Arc<Acl>,
+) -> Result<(), RedisError> {
+    let (username, password) = match arguments.len() {
+        1 => (DEFAULT_USER.to_string(), arguments[0].to_string()),
+        2 => (arguments[0].to_string(), arguments[1].to_string()),
+        _ => return Err(RedisError::WrongArity("auth")),
+    };
+
+    if arguments.len() == 1 && acl.default_user_nopass() {
+        return Err(RedisError::Generic("AUTH <password> called without any password configured for the default user. Are you sure your configuration is correct?"));
+    }
+    login(&client, acl, &username, &password)?;
+    builder.add(ProtocolType::SimpleString("OK".to_string()));
+    Ok(())
+}
+
+/// Authenticates the client as the given ACL user, as done by AUTH and HELLO.
+pub fn login(client: &Client, acl: &Acl, username: &str, password: &str) -> Result<(), RedisError> {
+    if !acl.authenticate(username, password) {
+        return Err(RedisError::WrongPass);
+    }
+    client.set_user(username);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_client;
+    use std::net::TcpListener;
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_auth_with_password() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let acl = Arc::new(Acl::new());
+        acl.set_requirepass(Some(&"secreto".to_string())).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        let result = run(&mut builder, arguments(&["otra"]), client.clone(), &acl);
+        assert_eq!(result.unwrap_err(), RedisError::WrongPass);
+        assert_eq!(client.user(), None);
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["default", "secreto"]),
+            client.clone(),
+            &acl,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), "+OK\r\n");
+        assert_eq!(client.user(), Some(DEFAULT_USER.to_string()));
+    }
+
+    #[test]
+    fn test_auth_with_username() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let acl = Arc::new(Acl::new());
+        acl.set_user("alice", &["on".to_string(), ">pass".to_string()])
+            .unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["alice", "pass"]),
+            client.clone(),
+            &acl,
+        )
+        .unwrap();
+        assert_eq!(client.user(), Some("alice".to_string()));
+    }
+
+    #[test]
+    fn test_auth_without_password_configured() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let acl = Arc::new(Acl::new());
+
+        let mut builder = ResponseBuilder::new();
+        let result = run(&mut builder, arguments(&["secreto"]), client.clone(), &acl);
+        assert!(result.unwrap_err().to_string().starts_with("ERR AUTH"));
+        assert_eq!(client.user(), None);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::client::Client;
+use crate::client_registry::ClientRegistry;
+use crate::error::RedisError;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Includes CLIENT ID, GETNAME, SETNAME, INFO, LIST, KILL, PAUSE, UNPAUSE and HELP
+/// CLIENT LIST: returns a line for every connected client (or the ones given with ID).
+/// CLIENT KILL: closes connections by address (old form) or by ID / ADDR / TYPE / SKIPME filters.
+/// CLIENT PAUSE: stops executing commands (write commands with WRITE) for the given milliseconds.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    clients: &Arc<ClientRegistry>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("client"));
+    }
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    match &arguments[0].to_ascii_lowercase()[..] {
+        "id" if arguments.len() == 1 => builder.add(ProtocolType::Integer(client.id() as i64)),
+        "getname" if arguments.len() == 1 => match client.name() {
+            Some(name) => builder.add(ProtocolType::String(name)),
+            None => builder.add(ProtocolType::Nil()),
+        },
+        "setname" if arguments.len() == 2 => {
+            check_name(&arguments[1])?;
+            client.set_name(&arguments[1]);
+            builder.add(ProtocolType::SimpleString("OK".to_string()));
+        }
+        "info" if arguments.len() == 1 => {
+            builder.add(ProtocolType::String(format!("{}\n", client.info())))
+        }
+        "list" => run_list(builder, &arguments[1..], clients)?,
+        "kill" if arguments.len() > 1 => run_kill(builder, &arguments[1..], client, clients)?,
+        "pause" if arguments.len() == 2 || arguments.len() == 3 => {
+            run_pause(builder, &arguments[1..], clients)?
+        }
+        "unpause" if arguments.len() == 1 => {
+            clients.unpause()?;
+            builder.add(ProtocolType::SimpleString("OK".to_string()));
+        }
+        "help" if arguments.len() == 1 => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try CLIENT HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn run_list(
+    builder: &mut ResponseBuilder,
+    arguments: &[String],
+    clients: &Arc<ClientRegistry>,
+) -> Result<(), RedisError> {
+    let ids = match arguments.split_first() {
+        None => None,
+        Some((filter, ids)) if filter.eq_ignore_ascii_case("id") && !ids.is_empty() => Some(
+            ids.iter()
+                .map(|x| x.parse::<u64>().ok())
+                .collect::<Option<Vec<u64>>>()
+                .ok_or(RedisError::Generic("Invalid client ID"))?,
+        ),
+        Some(_) => return Err(RedisError::Syntax),
+    };
+
+    let list: String = clients
+        .list()?
+        .iter()
+        .filter(|x| ids.as_ref().is_none_or(|ids| ids.contains(&x.id())))
+        .map(|x| format!("{}\n", x.info()))
+        .collect();
+    builder.add(ProtocolType::String(list));
+    Ok(())
+}
+
+/// Returns the type of a connection, as used by the TYPE filter of CLIENT KILL
+fn client_type(client: &Client) -> &'static str {
+    if client.in_replica_mode() {
+        "replica"
+    } else if client.in_pubsub_mode() {
+        "pubsub"
+    } else {
+        "normal"
+    }
+}
+
+fn run_kill(
+    builder: &mut ResponseBuilder,
+    arguments: &[String],
+    client: Arc<Client>,
+    clients: &Arc<ClientRegistry>,
+) -> Result<(), RedisError> {
+    // Old form: CLIENT KILL addr:port
+    if arguments.len() == 1 {
+        let killed = clients.kill(|x| x.addr() == arguments[0])?;
+        if killed == 0 {
+            return Err(RedisError::Generic("No such client"));
+        }
+        builder.add(ProtocolType::SimpleString("OK".to_string()));
+        return Ok(());
+    }
+
+    if !arguments.len().is_multiple_of(2) {
+        return Err(RedisError::Syntax);
+    }
+    let mut id = None;
+    let mut addr = None;
+    let mut kind = None;
+    let mut skipme = true;
+    for pair in arguments.chunks(2) {
+        match &pair[0].to_ascii_lowercase()[..] {
+            "id" => {
+                id = Some(
+                    pair[1]
+                        .parse::<u64>()
+                        .ok()
+                        .ok_or(RedisError::Generic("client-id should be greater than 0"))?,
+                )
+            }
+            "addr" => addr = Some(pair[1].clone()),
+            "type" => match &pair[1].to_ascii_lowercase()[..] {
+                x @ ("normal" | "master" | "replica" | "pubsub") => kind = Some(x.to_string()),
+                "slave" => kind = Some("replica".to_string()),
+                _ => return Err(RedisError::Generic("Unknown client type")),
+            },
+            "skipme" => match &pair[1].to_ascii_lowercase()[..] {
+                "yes" => skipme = true,
+                "no" => skipme = false,
+                _ => return Err(RedisError::Syntax),
+            },
+            _ => return Err(RedisError::Syntax),
+        }
+    }
+
+    let killed = clients.kill(|x| {
+        id.is_none_or(|id| x.id() == id)
+            && addr.as_ref().is_none_or(|addr| x.addr() == addr)
+            && kind.as_ref().is_none_or(|kind| client_type(x) == kind)
+            && !(skipme && *x == *client)
+    })?;
+    builder.add(ProtocolType::Integer(killed as i64));
+    Ok(())
+}
+
+fn run_pause(
+    builder: &mut ResponseBuilder,
+    arguments: &[String],
+    clients: &Arc<ClientRegistry>,
+) -> Result<(), RedisError> {
+    let timeout = arguments[0].parse::<u64>().ok().ok_or(RedisError::Generic(
+        "timeout is not an integer or out of range",
+    ))?;
+    let all = match arguments.get(1).map(|x| x.to_ascii_lowercase()) {
+        None => true,
+        Some(mode) if mode == "all" => true,
+        Some(mode) if mode == "write" => false,
+        Some(_) => return Err(RedisError::Syntax),
+    };
+    clients.pause(Duration::from_millis(timeout), all)?;
+    builder.add(ProtocolType::SimpleString("OK".to_string()));
+    Ok(())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "CLIENT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "ID",
+        "    Return the ID of the current connection.",
+        "GETNAME",
+        "    Return the name of the current connection.",
+        "SETNAME <name>",
+        "    Assign the name <name> to the current connection.",
+        "INFO",
+        "    Return information about the current client connection.",
+        "LIST [ID <id> [<id> ...]]",
+        "    Return information about client connections.",
+        "KILL <ip:port>",
+        "    Kill connection made from <ip:port>.",
+        "KILL <option> <value> [<option> <value> [...]]",
+        "    Kill connections. Options are: ID <id>, ADDR <ip:port>,",
+        "    TYPE (NORMAL|MASTER|REPLICA|PUBSUB), SKIPME (YES|NO).",
+        "PAUSE <timeout> [WRITE|ALL]",
+        "    Suspend all, or just write, clients for <timeout> milliseconds.",
+        "UNPAUSE",
+        "    Stop the current client pause, resuming traffic.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+/// Checks that a connection name, set with CLIENT SETNAME or HELLO, has no spaces or special characters
+pub fn check_name(name: &str) -> Result<(), RedisError> {
+    if name.chars().any(|x| !x.is_ascii_graphic()) {
+        return Err(RedisError::Generic(
+            "Client names cannot contain spaces, newlines or special characters.",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_client;
+    use std::net::TcpListener;
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_setname_and_getname() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let clients = Arc::new(ClientRegistry::new());
+        let (_stream, client) = new_client(&listener);
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["GETNAME"]),
+            client.clone(),
+            &clients,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), "$-1\r\n");
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["SETNAME", "worker"]),
+            client.clone(),
+            &clients,
+        )
+        .unwrap();
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["GETNAME"]),
+            client.clone(),
+            &clients,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), "$6\r\nworker\r\n");
+
+        let mut builder = ResponseBuilder::new();
+        let result = run(
+            &mut builder,
+            arguments(&["SETNAME", "a b"]),
+            client,
+            &clients,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_list_filters_by_id() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let clients = Arc::new(ClientRegistry::new());
+        let (_first_stream, first) = new_client(&listener);
+        let (_second_stream, second) = new_client(&listener);
+        clients.add(first.clone()).unwrap();
+        clients.add(second.clone()).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        let id = second.id().to_string();
+        run(
+            &mut builder,
+            arguments(&["LIST", "ID", &id]),
+            first,
+            &clients,
+        )
+        .unwrap();
+        let list = builder.serialize();
+        assert!(list.contains(&format!("id={} ", id)));
+        assert_eq!(list.matches("id=").count(), 1);
+    }
+
+    #[test]
+    fn test_kill_skips_caller() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let clients = Arc::new(ClientRegistry::new());
+        let (_first_stream, first) = new_client(&listener);
+        let (_second_stream, second) = new_client(&listener);
+        clients.add(first.clone()).unwrap();
+        clients.add(second.clone()).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        let addr = first.addr().to_string();
+        run(
+            &mut builder,
+            arguments(&["KILL", "ADDR", &addr]),
+            first.clone(),
+            &clients,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), ":0\r\n");
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["KILL", "ADDR", &addr, "SKIPME", "no"]),
+            first.clone(),
+            &clients,
+        )
+        .unwrap();
+        assert_eq!(builder.serialize(), ":1\r\n");
+        assert!(first.is_closed());
+        assert!(!second.is_closed());
+    }
+}
@@ -0,0 +1,267 @@
+use crate::client::Client;
+use crate::cluster::slots::{self, parse_slot};
+use crate::cluster::Cluster;
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+const INVALID_SLOT: RedisError = RedisError::Generic("Invalid or out of range slot");
+
+/// Includes CLUSTER INFO, MYID, NODES, SLOTS, KEYSLOT, ADDSLOTS, ADDSLOTSRANGE, DELSLOTS, MEET,
+/// SETSLOT, COUNTKEYSINSLOT, GETKEYSINSLOT and HELP
+/// CLUSTER ADDSLOTS: assigns slots to this node, which the other nodes learn through the gossip.
+/// CLUSTER MEET: joins this node and the one at the given address in the same cluster.
+/// CLUSTER SETSLOT: moves a slot between nodes, marking it as MIGRATING in its owner and as
+/// IMPORTING in its new node, and then assigning it to its new NODE.
+/// CLUSTER GOSSIP is used by the nodes to share their state, and is not meant for clients.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    cluster: &Arc<Cluster>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    cluster.check_enabled()?;
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    match &arguments[0].to_ascii_lowercase()[..] {
+        "info" if arguments.len() == 1 => builder.add(ProtocolType::Verbatim(
+            "txt".to_string(),
+            format!("{}\r\n", cluster.info().join("\r\n")),
+        )),
+        "myid" if arguments.len() == 1 => builder.add(ProtocolType::String(cluster.myself())),
+        "nodes" if arguments.len() == 1 => {
+            builder.add(ProtocolType::Verbatim("txt".to_string(), cluster.nodes()))
+        }
+        "slots" if arguments.len() == 1 => builder.add(cluster.slots()),
+        "keyslot" if arguments.len() == 2 => {
+            builder.add(ProtocolType::Integer(slots::key_slot(&arguments[1]) as i64))
+        }
+        "addslots" if arguments.len() > 1 => {
+            cluster.add_slots(&parse_slots(&arguments[1..])?)?;
+            builder.add(ok());
+        }
+        "addslotsrange" if arguments.len() > 1 && !arguments.len().is_multiple_of(2) => {
+            let mut ranges = Vec::new();
+            for range in parse_slots(&arguments[1..])?.chunks(2) {
+                if range[0] > range[1] {
+                    return Err(RedisError::Generic(
+                        "start slot number is greater than end slot number",
+                    ));
+                }
+                ranges.extend(range[0]..=range[1]);
+            }
+            cluster.add_slots(&ranges)?;
+            builder.add(ok());
+        }
+        "delslots" if arguments.len() > 1 => {
+            cluster.del_slots(&parse_slots(&arguments[1..])?)?;
+            builder.add(ok());
+        }
+        "meet" if arguments.len() == 3 => {
+            let port = arguments[2]
+                .parse()
+                .map_err(|_| RedisError::Generic("Invalid node address specified"))?;
+            cluster.meet(&arguments[1], port);
+            builder.add(ok());
+        }
+        "setslot" if arguments.len() == 3 || arguments.len() == 4 => {
+            let slot = parse_slot(&arguments[1]).ok_or(INVALID_SLOT)?;
+            match (&arguments[2].to_ascii_lowercase()[..], arguments.get(3)) {
+                ("importing", Some(id)) => cluster.set_importing(slot, id)?,
+                ("migrating", Some(id)) => cluster.set_migrating(slot, id)?,
+                ("node", Some(id)) => {
+                    if *id != cluster.myself() && !Cluster::keys_in_slot(data, slot).is_empty() {
+                        return Err(RedisError::Generic("Can't assign hashslot to a different node while I still hold keys for this hash slot."));
+                    }
+                    cluster.set_node(slot, id)?
+                }
+                ("stable", None) => cluster.set_stable(slot),
+                _ => return Err(RedisError::Syntax),
+            }
+            builder.add(ok());
+        }
+        "countkeysinslot" if arguments.len() == 2 => {
+            let slot = parse_slot(&arguments[1]).ok_or(INVALID_SLOT)?;
+            builder.add(ProtocolType::Integer(
+                Cluster::keys_in_slot(data, slot).len() as i64,
+            ));
+        }
+        "getkeysinslot" if arguments.len() == 3 => {
+            let slot = parse_slot(&arguments[1]).ok_or(INVALID_SLOT)?;
+            let count: usize = arguments[2]
+                .parse()
+                .map_err(|_| RedisError::Generic("Invalid number of keys"))?;
+            builder.add(ProtocolType::Array(
+                Cluster::keys_in_slot(data, slot)
+                    .into_iter()
+                    .take(count)
+                    .map(ProtocolType::String)
+                    .collect(),
+            ));
+        }
+        "gossip" => {
+            let ip = client
+                .addr()
+                .rsplit_once(':')
+                .map(|(ip, _)| ip.trim_start_matches('[').trim_end_matches(']'))
+                .unwrap_or_default();
+            builder.add(ProtocolType::String(
+                cluster.receive_gossip(ip, &arguments[1..])?,
+            ));
+        }
+        "help" if arguments.len() == 1 => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try CLUSTER HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn parse_slots(arguments: &[String]) -> Result<Vec<u16>, RedisError> {
+    arguments
+        .iter()
+        .map(|x| parse_slot(x).ok_or(INVALID_SLOT))
+        .collect()
+}
+
+fn ok() -> ProtocolType {
+    ProtocolType::SimpleString("OK".to_string())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "CLUSTER <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "ADDSLOTS <slot> [<slot> ...]",
+        "    Assign slots to current node.",
+        "ADDSLOTSRANGE <start slot> <end slot> [<start slot> <end slot> ...]",
+        "    Assign slots which are between <start-slot> and <end-slot> to current node.",
+        "COUNTKEYSINSLOT <slot>",
+        "    Return the number of keys in <slot>.",
+        "DELSLOTS <slot> [<slot> ...]",
+        "    Delete slots information from current node.",
+        "GETKEYSINSLOT <slot> <count>",
+        "    Return key names stored by current node in a slot.",
+        "INFO",
+        "    Return information about the cluster.",
+        "KEYSLOT <key>",
+        "    Return the hash slot for <key>.",
+        "MEET <ip> <port>",
+        "    Connect nodes into a working cluster.",
+        "MYID",
+        "    Return the node id.",
+        "NODES",
+        "    Return cluster configuration seen by node.",
+        "SETSLOT <slot> (IMPORTING <node-id>|MIGRATING <node-id>|STABLE|NODE <node-id>)",
+        "    Set slot state.",
+        "SLOTS",
+        "    Return information about slots range mappings.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+    use crate::test_util::new_client;
+    use std::net::TcpListener;
+
+    fn cluster_command(
+        values: &[&str],
+        cluster: &Arc<Cluster>,
+        data: &Arc<DataStorage>,
+    ) -> Result<String, RedisError> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let arguments = values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect();
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, arguments, client, cluster, data)?;
+        Ok(builder.serialize())
+    }
+
+    #[test]
+    fn test_keyslot_and_slots() {
+        let cluster = Arc::new(Cluster::new(true, "127.0.0.1", 7000));
+        let data = Arc::new(DataStorage::new());
+        let id = cluster.myself();
+
+        assert_eq!(
+            cluster_command(&["KEYSLOT", "foo"], &cluster, &data).unwrap(),
+            ":12182\r\n"
+        );
+        assert_eq!(
+            cluster_command(&["ADDSLOTSRANGE", "0", "99"], &cluster, &data).unwrap(),
+            "+OK\r\n"
+        );
+        cluster_command(&["ADDSLOTS", "200"], &cluster, &data).unwrap();
+        assert_eq!(
+            cluster_command(&["ADDSLOTS", "50"], &cluster, &data),
+            Err(RedisError::Generic("Slot is already busy"))
+        );
+        assert_eq!(
+            cluster_command(&["ADDSLOTS", "16384"], &cluster, &data),
+            Err(INVALID_SLOT)
+        );
+        assert_eq!(
+            cluster_command(&["SLOTS"], &cluster, &data).unwrap(),
+            format!(
+                "*2\r\n*3\r\n:0\r\n:99\r\n*3\r\n$9\r\n127.0.0.1\r\n:7000\r\n$40\r\n{}\r\n\
+                 *3\r\n:200\r\n:200\r\n*3\r\n$9\r\n127.0.0.1\r\n:7000\r\n$40\r\n{}\r\n",
+                id, id
+            )
+        );
+        let nodes = cluster_command(&["NODES"], &cluster, &data).unwrap();
+        assert!(nodes.contains(&format!(
+            "{} 127.0.0.1:7000@7000 myself,master - 0 0 0 connected 0-99 200\n",
+            id
+        )));
+        let info = cluster_command(&["INFO"], &cluster, &data).unwrap();
+        assert!(info.contains("cluster_state:fail\r\ncluster_slots_assigned:101\r\n"));
+    }
+
+    #[test]
+    fn test_keys_in_slot() {
+        let cluster = Arc::new(Cluster::new(true, "127.0.0.1", 7000));
+        let data = Arc::new(DataStorage::new());
+        for key in ["{user}.a", "{user}.b", "other"].iter() {
+            data.set(key, Value::String("x".to_string())).unwrap();
+        }
+        let slot = slots::key_slot("user").to_string();
+
+        assert_eq!(
+            cluster_command(&["COUNTKEYSINSLOT", &slot], &cluster, &data).unwrap(),
+            ":2\r\n"
+        );
+        assert_eq!(
+            cluster_command(&["GETKEYSINSLOT", &slot, "1"], &cluster, &data).unwrap(),
+            "*1\r\n$8\r\n{user}.a\r\n"
+        );
+        cluster_command(&["ADDSLOTS", &slot], &cluster, &data).unwrap();
+        assert!(cluster_command(&["SETSLOT", &slot, "NODE", "unknown"], &cluster, &data).is_err());
+    }
+
+    #[test]
+    fn test_cluster_disabled() {
+        let cluster = Arc::new(Cluster::new(false, "127.0.0.1", 7000));
+        let data = Arc::new(DataStorage::new());
+
+        assert_eq!(
+            cluster_command(&["INFO"], &cluster, &data),
+            Err(crate::cluster::CLUSTER_DISABLED)
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::command_table::{self, CommandSpec, COMMANDS};
+use crate::error::RedisError;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+
+/// Includes COMMAND, COMMAND COUNT, COMMAND INFO, COMMAND DOCS and COMMAND HELP
+/// COMMAND: returns the details of every command known by the server.
+/// COMMAND INFO: returns the name, arity, flags, key positions and ACL categories of the commands.
+/// COMMAND DOCS: returns the summary and group of the commands.
+pub fn run(builder: &mut ResponseBuilder, arguments: Vec<ProtocolType>) -> Result<(), RedisError> {
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    let names = arguments.get(1..).unwrap_or_default();
+    match arguments.first().map(|x| x.to_ascii_lowercase()).as_deref() {
+        None => builder.add(ProtocolType::Array(COMMANDS.iter().map(info).collect())),
+        Some("count") if names.is_empty() => {
+            builder.add(ProtocolType::Integer(COMMANDS.len() as i64))
+        }
+        Some("info") if names.is_empty() => {
+            builder.add(ProtocolType::Array(COMMANDS.iter().map(info).collect()))
+        }
+        Some("info") => builder.add(ProtocolType::Array(
+            names
+                .iter()
+                .map(|x| match command_table::lookup(&x.to_ascii_lowercase()) {
+                    Some(command) => info(command),
+                    None => ProtocolType::Nil(),
+                })
+                .collect(),
+        )),
+        Some("docs") if names.is_empty() => {
+            builder.add(ProtocolType::Map(COMMANDS.iter().map(docs).collect()))
+        }
+        Some("docs") => builder.add(ProtocolType::Map(
+            names
+                .iter()
+                .filter_map(|x| command_table::lookup(&x.to_ascii_lowercase()))
+                .map(docs)
+                .collect(),
+        )),
+        Some("help") if names.is_empty() => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try COMMAND HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn info(command: &CommandSpec) -> ProtocolType {
+    ProtocolType::Array(vec![
+        ProtocolType::String(command.name.to_string()),
+        ProtocolType::Integer(command.arity),
+        ProtocolType::Array(
+            command
+                .flags
+                .iter()
+                .map(|x| ProtocolType::SimpleString(x.to_string()))
+                .collect(),
+        ),
+        ProtocolType::Integer(command.first_key),
+        ProtocolType::Integer(command.last_key),
+        ProtocolType::Integer(command.step),
+        ProtocolType::Array(
+            command
+                .categories
+                .iter()
+                .map(|x| ProtocolType::SimpleString(format!("@{}", x)))
+                .collect(),
+        ),
+    ])
+}
+
+fn docs(command: &CommandSpec) -> (ProtocolType, ProtocolType) {
+    (
+        ProtocolType::String(command.name.to_string()),
+        ProtocolType::Map(vec![
+            (
+                ProtocolType::String("summary".to_string()),
+                ProtocolType::String(command.summary.to_string()),
+            ),
+            (
+                ProtocolType::String("group".to_string()),
+                ProtocolType::String(command.group.to_string()),
+            ),
+        ]),
+    )
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "COMMAND <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "(no subcommand)",
+        "    Return details about all commands.",
+        "COUNT",
+        "    Return the total number of commands in this server.",
+        "INFO [<command-name> ...]",
+        "    Return details about multiple commands.",
+        "    If no command names are given, documentation details for all",
+        "    commands are returned.",
+        "DOCS [<command-name> ...]",
+        "    Return documentation details about multiple commands.",
+        "    If no command names are given, documentation details for all",
+        "    commands are returned.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(values: &[&str]) -> Result<String, RedisError> {
+        let arguments = values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect();
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, arguments)?;
+        Ok(builder.serialize())
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(
+            command(&["COUNT"]).unwrap(),
+            format!(":{}\r\n", COMMANDS.len())
+        );
+    }
+
+    #[test]
+    fn test_info() {
+        assert_eq!(
+            command(&["INFO", "get", "foo"]).unwrap(),
+            "*2\r\n*7\r\n$3\r\nget\r\n:2\r\n*2\r\n+readonly\r\n+fast\r\n:1\r\n:1\r\n:1\r\n*1\r\n+@read\r\n$-1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_docs() {
+        let docs = command(&["DOCS", "ping"]).unwrap();
+        assert!(docs.starts_with("*2\r\n$4\r\nping\r\n*4\r\n$7\r\nsummary\r\n"));
+        assert!(docs.ends_with("$5\r\ngroup\r\n$10\r\nconnection\r\n"));
+        assert!(command(&["FOO"]).is_err());
+    }
+}
@@ -1,28 +1,50 @@
+use crate::acl::users::Acl;
 use crate::config::configuration::Configuration;
+use crate::error::RedisError;
 use crate::logging::logger::Logger;
+use crate::stats::ServerStats;
+use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::{Arc, Mutex};
 
 /// Includes CONFIG SET and CONFIG GET
 /// CONFIG GET: returns the specified configuration from the server, or returns all configuration if '*' given.
+/// With RESP3 the configuration is returned as a map from its name to its value.
 /// CONFIG SET: sets the configuration given.
+/// CONFIG RESETSTAT: resets the statistics reported by INFO, including the command statistics.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     config: Arc<Mutex<Configuration>>,
     logger: Arc<Logger>,
-) -> Result<(), &'static str> {
+    acl: &Arc<Acl>,
+    protocol: u8,
+    stats: &Arc<ServerStats>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments[0].to_string().to_ascii_lowercase() == *"resetstat" {
+        if arguments.len() != 1 {
+            return Err(RedisError::WrongArity("config"));
+        }
+        stats.reset();
+        data.reset_stats();
+        builder.add(ProtocolType::SimpleString("OK".to_string()));
+        return Ok(());
+    }
     if arguments[0].to_string().to_ascii_lowercase() == *"set" {
-        return run_set(arguments, builder, config, logger);
+        return run_set(arguments, builder, config, logger, acl);
     } else if arguments[0].to_string().to_ascii_lowercase() == *"get" {
         if arguments.len() < 2 {
-            return Err("Wrong number of parameters");
+            return Err(RedisError::WrongArity("config"));
         }
-        run_get(arguments, builder, config);
+        run_get(arguments, builder, config, protocol);
         return Ok(());
     }
-    Err("There's no configuration argument named like that")
+    Err(RedisError::Generic(
+        "There's no configuration argument named like that",
+    ))
 }
 
 fn run_set(
@@ -30,7 +52,11 @@ fn run_set(
     builder: &mut ResponseBuilder,
     config: Arc<Mutex<Configuration>>,
     logger: Arc<Logger>,
-) -> Result<(), &'static str> {
+    acl: &Arc<Acl>,
+) -> Result<(), RedisError> {
+    if arguments.len() != 3 {
+        return Err(RedisError::WrongArity("config"));
+    }
     let argument: &str = &arguments[1].to_string().to_ascii_lowercase()[..];
 
     match argument {
@@ -38,23 +64,37 @@ fn run_set(
             let new_verb: String = arguments[2].to_string();
             let new_verb_u8 = new_verb.parse();
             if new_verb_u8.is_err() {
-                return Err("Could not set verbosity (must be 1 or 0)");
-            }
-            let config_res = config.lock().unwrap().set_verbose(new_verb_u8.unwrap());
-            if let Err(er) = config_res {
-                return Err(er);
+                return Err(RedisError::Generic(
+                    "Could not set verbosity (must be 1 or 0)",
+                ));
             }
+            config.lock().unwrap().set_verbose(new_verb_u8.unwrap())?;
             builder.add(ProtocolType::String("Ok".to_string()));
         }
         "timeout" => {
             let new_timeout = arguments[2].to_string();
             let new_timeout_u32 = new_timeout.parse();
             if new_timeout_u32.is_err() {
-                return Err("Could not set verbosity (must be 1 or 0)");
+                return Err(RedisError::Generic(
+                    "Could not set verbosity (must be 1 or 0)",
+                ));
             }
             config.lock().unwrap().set_timeout(new_timeout_u32.unwrap());
             builder.add(ProtocolType::String("Ok".to_string()));
         }
+        "maxclients" => {
+            let new_maxclients = arguments[2].to_string().parse();
+            if new_maxclients.is_err() {
+                return Err(RedisError::Generic(
+                    "Could not set maxclients (must be a positive integer)",
+                ));
+            }
+            config
+                .lock()
+                .unwrap()
+                .set_maxclients(new_maxclients.unwrap())?;
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
         "dbfilename" => {
             let new_dbfilename = arguments[2].to_string();
             config.lock().unwrap().set_dbfilename(new_dbfilename);
@@ -66,9 +106,50 @@ fn run_set(
             logger.change_logfile_name(new_logfile)?;
             builder.add(ProtocolType::String("Ok".to_string()));
         }
+        "requirepass" => {
+            let new_requirepass = arguments.get(2).map(|x| x.to_string()).unwrap_or_default();
+            let mut config_lock = config.lock().unwrap();
+            config_lock.set_requirepass(new_requirepass);
+            acl.set_requirepass(config_lock.get_requirepass())?;
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
+        "masterauth" => {
+            let new_masterauth = arguments[2].to_string();
+            config.lock().unwrap().set_masterauth(new_masterauth);
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
+        "slowlog-log-slower-than" => {
+            let new_slower_than = arguments[2].to_string().parse().map_err(|_| {
+                RedisError::Generic("Could not set slowlog-log-slower-than (must be an integer)")
+            })?;
+            config
+                .lock()
+                .unwrap()
+                .set_slowlog_log_slower_than(new_slower_than);
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
+        "slowlog-max-len" => {
+            let new_max_len = arguments[2].to_string().parse().map_err(|_| {
+                RedisError::Generic("Could not set slowlog-max-len (must be a positive integer)")
+            })?;
+            config.lock().unwrap().set_slowlog_max_len(new_max_len);
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
+        "latency-monitor-threshold" => {
+            let new_threshold = arguments[2].to_string().parse().map_err(|_| {
+                RedisError::Generic(
+                    "Could not set latency-monitor-threshold (must be a positive integer)",
+                )
+            })?;
+            config
+                .lock()
+                .unwrap()
+                .set_latency_monitor_threshold(new_threshold);
+            builder.add(ProtocolType::String("Ok".to_string()));
+        }
         _ => builder.add(ProtocolType::String(format!(
-            "There's no configuration named: {}",
-            arguments[1].to_string()
+            "ERR There's no configuration named: {}",
+            arguments[1]
         ))),
     }
     Ok(())
@@ -78,6 +159,7 @@ fn run_get(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     config: Arc<Mutex<Configuration>>,
+    protocol: u8,
 ) {
     let argument: &str = &arguments[1].to_string().to_ascii_lowercase()[..];
 
@@ -101,33 +183,107 @@ fn run_get(
         "timeout" => response.push(ProtocolType::Integer(
             config.lock().unwrap().get_timeout() as i64
         )),
+        "maxclients" => response.push(ProtocolType::Integer(
+            config.lock().unwrap().get_maxclients() as i64,
+        )),
+        "requirepass" => response.push(ProtocolType::String(
+            config
+                .lock()
+                .unwrap()
+                .get_requirepass()
+                .cloned()
+                .unwrap_or_default(),
+        )),
+        "masterauth" => response.push(ProtocolType::String(
+            config
+                .lock()
+                .unwrap()
+                .get_masterauth()
+                .cloned()
+                .unwrap_or_default(),
+        )),
+        "slowlog-log-slower-than" => response.push(ProtocolType::Integer(
+            config.lock().unwrap().get_slowlog_log_slower_than(),
+        )),
+        "slowlog-max-len" => response.push(ProtocolType::Integer(
+            config.lock().unwrap().get_slowlog_max_len() as i64,
+        )),
+        "latency-monitor-threshold" => response.push(ProtocolType::Integer(
+            config.lock().unwrap().get_latency_monitor_threshold() as i64,
+        )),
+        "cluster-enabled" => response.push(ProtocolType::String(
+            if config.lock().unwrap().get_cluster_enabled() {
+                "yes"
+            } else {
+                "no"
+            }
+            .to_string(),
+        )),
         "*" => {
-            send_all_config_params(config, builder);
+            send_all_config_params(config, builder, protocol);
             return;
         }
         _ => {
             builder.add(ProtocolType::Error(format!(
-                "There's no configuration named: {}",
-                arguments[1].to_string()
+                "ERR There's no configuration named: {}",
+                arguments[1]
             )));
             return;
         }
     }
-    builder.add(ProtocolType::Array(response));
+    if protocol == 3 {
+        builder.add(ProtocolType::Map(vec![(
+            ProtocolType::String(argument.to_string()),
+            response.remove(0),
+        )]));
+    } else {
+        builder.add(ProtocolType::Array(response));
+    }
 }
-#[allow(unused_variables)]
-fn send_all_config_params(config: Arc<Mutex<Configuration>>, builder: &mut ResponseBuilder) {
-    let response = vec![
-        ProtocolType::String(format!("Verbose: {}", config.lock().unwrap().get_verbose())),
-        ProtocolType::String(format!("Port: {}", config.lock().unwrap().get_port())),
-        ProtocolType::String(format!("Ip: {}", config.lock().unwrap().get_ip())),
-        ProtocolType::String(format!(
-            "Dbfilename: {}",
-            config.lock().unwrap().get_dbfilename()
-        )),
-        ProtocolType::String(format!("Logfile: {}", config.lock().unwrap().get_logfile())),
-        ProtocolType::String(format!("Timeout: {}", config.lock().unwrap().get_timeout())),
+
+fn send_all_config_params(
+    config: Arc<Mutex<Configuration>>,
+    builder: &mut ResponseBuilder,
+    protocol: u8,
+) {
+    let config = config.lock().unwrap();
+    let params = vec![
+        ("Verbose", config.get_verbose().to_string()),
+        ("Port", config.get_port().to_string()),
+        ("Ip", config.get_ip().to_string()),
+        ("Dbfilename", config.get_dbfilename().to_string()),
+        ("Logfile", config.get_logfile().to_string()),
+        ("Timeout", config.get_timeout().to_string()),
+        ("Maxclients", config.get_maxclients().to_string()),
+        (
+            "Slowlog-log-slower-than",
+            config.get_slowlog_log_slower_than().to_string(),
+        ),
+        ("Slowlog-max-len", config.get_slowlog_max_len().to_string()),
+        (
+            "Latency-monitor-threshold",
+            config.get_latency_monitor_threshold().to_string(),
+        ),
     ];
 
-    builder.add(ProtocolType::Array(response));
+    if protocol == 3 {
+        builder.add(ProtocolType::Map(
+            params
+                .into_iter()
+                .map(|(name, value)| {
+                    (
+                        ProtocolType::String(name.to_ascii_lowercase()),
+                        ProtocolType::String(value),
+                    )
+                })
+                .collect(),
+        ));
+    } else {
+        builder.add(ProtocolType::Array(
+            params
+                .into_iter()
+                .map(|(name, value)| ProtocolType::String(format!("{}: {}", name, value)))
+                .collect(),
+        ));
+    }
 }
@@ -1,10 +1,11 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
 
 ///Returns the number of elements in the data base.
-pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(), &'static str> {
+pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(), RedisError> {
     let result = data.len();
 
     match result {
@@ -12,7 +13,7 @@ pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(),
             builder.add(ProtocolType::Integer(s as i64));
             Ok(())
         }
-        Err(_) => Err("can't count elements in data base"),
+        Err(_) => Err(RedisError::Generic("can't count elements in data base")),
     }
 }
 
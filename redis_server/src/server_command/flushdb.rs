@@ -1,10 +1,11 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
 
 ///Delete all the keys of the currently selected DB.
-pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(), &'static str> {
+pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(), RedisError> {
     let response = data.delete_all();
 
     match response {
@@ -12,7 +13,7 @@ pub fn run(builder: &mut ResponseBuilder, data: Arc<DataStorage>) -> Result<(),
             builder.add(ProtocolType::String("OK".to_string()));
             Ok(())
         }
-        Err(_) => Err("Flushdb not executed"),
+        Err(_) => Err(RedisError::Generic("Flushdb not executed")),
     }
 }
 
@@ -0,0 +1,185 @@
+use crate::acl::users::Acl;
+use crate::client::Client;
+use crate::error::RedisError;
+use crate::replication::Replication;
+use crate::server_command::{auth, client as client_command};
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// HELLO [protover [AUTH username password] [SETNAME clientname]]
+/// Switches the connection to the given RESP version, optionally authenticating it
+/// and setting its name, and replies with a map describing the server.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    acl: &Arc<Acl>,
+    replication: &Arc<Replication>,
+) -> Result<(), RedisError> {
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    let protocol = match arguments.first().map(|x| x.parse::<i64>()) {
+        None => client.protocol(),
+        Some(Ok(version)) if version == 2 || version == 3 => version as u8,
+        Some(Ok(_)) => return Err(RedisError::NoProto),
+        Some(Err(_)) => {
+            return Err(RedisError::Generic(
+                "Protocol version is not an integer or out of range",
+            ))
+        }
+    };
+
+    let mut name = None;
+    let mut i = 1;
+    while i < arguments.len() {
+        match arguments[i].to_ascii_lowercase().as_str() {
+            "auth" if i + 2 < arguments.len() => {
+                auth::login(&client, acl, &arguments[i + 1], &arguments[i + 2])?;
+                i += 3;
+            }
+            "setname" if i + 1 < arguments.len() => {
+                client_command::check_name(&arguments[i + 1])?;
+                name = Some(&arguments[i + 1]);
+                i += 2;
+            }
+            _ => return Err(RedisError::Syntax),
+        }
+    }
+    if client.user().is_none() && !acl.default_user_nopass() {
+        return Err(RedisError::NoAuth);
+    }
+
+    if let Some(name) = name {
+        client.set_name(name);
+    }
+    client.set_protocol(protocol);
+    builder.add(ProtocolType::Map(vec![
+        field("server", ProtocolType::String("redis".to_string())),
+        field("version", ProtocolType::String("1.0".to_string())),
+        field("proto", ProtocolType::Integer(protocol as i64)),
+        field("id", ProtocolType::Integer(client.id() as i64)),
+        field("mode", ProtocolType::String("standalone".to_string())),
+        field(
+            "role",
+            ProtocolType::String(replication.role_name().to_string()),
+        ),
+        field("modules", ProtocolType::Array(Vec::new())),
+    ]));
+    Ok(())
+}
+
+fn field(name: &str, value: ProtocolType) -> (ProtocolType, ProtocolType) {
+    (ProtocolType::String(name.to_string()), value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::new_client;
+    use std::net::TcpListener;
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_hello_switches_protocol() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let acl = Arc::new(Acl::new());
+        let replication = Arc::new(Replication::new());
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["3", "SETNAME", "conn"]),
+            client.clone(),
+            &acl,
+            &replication,
+        )
+        .unwrap();
+        assert_eq!(client.protocol(), 3);
+        assert_eq!(client.name(), Some("conn".to_string()));
+        assert!(builder
+            .serialize_resp3()
+            .starts_with("%7\r\n$6\r\nserver\r\n$5\r\nredis\r\n"));
+        assert!(builder.to_string().contains("proto: 3"));
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&[]),
+            client.clone(),
+            &acl,
+            &replication,
+        )
+        .unwrap();
+        assert_eq!(client.protocol(), 3);
+        assert_eq!(
+            run(
+                &mut builder,
+                arguments(&["4"]),
+                client.clone(),
+                &acl,
+                &replication
+            ),
+            Err(RedisError::NoProto)
+        );
+        assert_eq!(
+            run(
+                &mut builder,
+                arguments(&["3", "FOO"]),
+                client,
+                &acl,
+                &replication
+            ),
+            Err(RedisError::Syntax)
+        );
+    }
+
+    #[test]
+    fn test_hello_with_auth() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (_stream, client) = new_client(&listener);
+        let acl = Arc::new(Acl::new());
+        let replication = Arc::new(Replication::new());
+        acl.set_requirepass(Some(&"secreto".to_string())).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        assert_eq!(
+            run(
+                &mut builder,
+                arguments(&["3"]),
+                client.clone(),
+                &acl,
+                &replication
+            ),
+            Err(RedisError::NoAuth)
+        );
+        assert_eq!(
+            run(
+                &mut builder,
+                arguments(&["3", "AUTH", "default", "wrong"]),
+                client.clone(),
+                &acl,
+                &replication
+            ),
+            Err(RedisError::WrongPass)
+        );
+        assert_eq!(client.protocol(), 2);
+
+        run(
+            &mut builder,
+            arguments(&["3", "AUTH", "default", "secreto"]),
+            client.clone(),
+            &acl,
+            &replication,
+        )
+        .unwrap();
+        assert_eq!(client.user(), Some("default".to_string()));
+        assert_eq!(client.protocol(), 3);
+    }
+}
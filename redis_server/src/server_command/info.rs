@@ -1,58 +1,324 @@
 use crate::config::configuration::Configuration;
-use crate::server::THREADS;
+use crate::error::RedisError;
+use crate::replication::Replication;
+use crate::stats::ServerStats;
+use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::env;
+use std::process;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::time::SystemTime;
 
+const SECTIONS: [&str; 9] = [
+    "server",
+    "clients",
+    "memory",
+    "persistence",
+    "stats",
+    "replication",
+    "commandstats",
+    "cluster",
+    "keyspace",
+];
+
+/// Sections only printed when requested by name, or with `all` or `everything`
+const EXTRA_SECTIONS: [&str; 1] = ["commandstats"];
+
 ///Funcion para la ejecucion del comando INFO.
-///Imprime informacion del servidor y los clientes conectados.
+///Imprime informacion del servidor, los clientes conectados, la memoria, la persistencia,
+///las estadisticas, la replicacion, el cluster y el keyspace. Si se indican secciones solo se imprimen esas.
+///Las estadisticas de cada comando (commandstats) solo se imprimen si se piden, o con `all` o `everything`.
 pub fn run(
     builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    config: &Arc<Mutex<Configuration>>,
+    sys_time: &Arc<SystemTime>,
+    data: &Arc<DataStorage>,
+    stats: &Arc<ServerStats>,
+    replication: &Arc<Replication>,
+) -> Result<(), RedisError> {
+    let requested: Vec<String> = arguments
+        .iter()
+        .map(|x| x.to_string().to_ascii_lowercase())
+        .collect();
+    let all = requested.iter().any(|x| x == "all" || x == "everything");
+    let default = requested.is_empty() || requested.iter().any(|x| x == "default");
+
+    let mut sections = Vec::new();
+    for section in SECTIONS.iter() {
+        let included = all
+            || (default && !EXTRA_SECTIONS.contains(section))
+            || requested.iter().any(|x| x == section);
+        if !included {
+            continue;
+        }
+        let lines = match *section {
+            "server" => server_section(config, sys_time, stats),
+            "clients" => clients_section(config, stats),
+            "memory" => memory_section(data)?,
+            "persistence" => persistence_section(stats),
+            "stats" => stats_section(data, stats),
+            "replication" => replication.info(),
+            "commandstats" => commandstats_section(stats),
+            "cluster" => cluster_section(config),
+            _ => keyspace_section(data)?,
+        };
+        sections.push(format!("{}\r\n", lines.join("\r\n")));
+    }
+
+    builder.add(ProtocolType::Verbatim(
+        "txt".to_string(),
+        sections.join("\r\n"),
+    ));
+    Ok(())
+}
+
+fn server_section(
     config: &Arc<Mutex<Configuration>>,
     sys_time: &Arc<SystemTime>,
-) -> Result<(), &'static str> {
+    stats: &Arc<ServerStats>,
+) -> Vec<String> {
     let active_time: Duration = get_system_active_time(sys_time);
     let cfg_lock = config.lock().unwrap();
-    let info: String = format!(
-        "# Server 
-redis_version:1.0
-redis_mode:N/A
-os:{}
-arch_bits:64
-process_id:N/A
-process_supervised:N/A
-run_id:N/A
-tcp_port:{}
-server_time_usec:{}
-uptime_in_seconds:{}
-executable:{}
-config_file:{}
-# Clients
-maxclients:{}
-blocked_clients:0
-tracking_clients:0
-clients_in_timeout_table:0
-\n\r",
-        env::consts::OS,
-        cfg_lock.get_port(),
-        active_time.as_micros(),
-        active_time.as_secs(),
-        env::current_exe().unwrap().to_str().unwrap(),
-        match cfg_lock.get_configfile() {
-            Some(v) => v,
-            None => "None".to_string(),
-        },
-        THREADS,
-    );
-
-    builder.add(ProtocolType::String(info));
-    Ok(())
+    vec![
+        "# Server".to_string(),
+        "redis_version:1.0".to_string(),
+        format!(
+            "redis_mode:{}",
+            if cfg_lock.get_cluster_enabled() {
+                "cluster"
+            } else {
+                "standalone"
+            }
+        ),
+        format!("os:{}", env::consts::OS),
+        "arch_bits:64".to_string(),
+        format!("process_id:{}", process::id()),
+        format!("run_id:{}", stats.run_id()),
+        format!("tcp_port:{}", cfg_lock.get_port()),
+        format!("server_time_usec:{}", now().as_micros()),
+        format!("uptime_in_seconds:{}", active_time.as_secs()),
+        format!("uptime_in_days:{}", active_time.as_secs() / 86400),
+        format!(
+            "executable:{}",
+            env::current_exe()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default()
+        ),
+        format!(
+            "config_file:{}",
+            cfg_lock.get_configfile().unwrap_or_default()
+        ),
+    ]
+}
+
+fn clients_section(config: &Arc<Mutex<Configuration>>, stats: &Arc<ServerStats>) -> Vec<String> {
+    vec![
+        "# Clients".to_string(),
+        format!("connected_clients:{}", stats.connected_clients()),
+        format!("maxclients:{}", config.lock().unwrap().get_maxclients()),
+        "blocked_clients:0".to_string(),
+    ]
+}
+
+fn memory_section(data: &Arc<DataStorage>) -> Result<Vec<String>, RedisError> {
+    let used_memory = data.memory_stats()?.used_memory();
+    Ok(vec![
+        "# Memory".to_string(),
+        format!("used_memory:{}", used_memory),
+        format!("used_memory_human:{}", bytes_to_human(used_memory)),
+    ])
+}
+
+fn persistence_section(stats: &Arc<ServerStats>) -> Vec<String> {
+    vec![
+        "# Persistence".to_string(),
+        "loading:0".to_string(),
+        "rdb_bgsave_in_progress:0".to_string(),
+        format!("rdb_last_save_time:{}", stats.last_save_time()),
+        format!(
+            "rdb_last_bgsave_status:{}",
+            if stats.last_save_ok() { "ok" } else { "err" }
+        ),
+    ]
+}
+
+fn stats_section(data: &Arc<DataStorage>, stats: &Arc<ServerStats>) -> Vec<String> {
+    vec![
+        "# Stats".to_string(),
+        format!(
+            "total_connections_received:{}",
+            stats.total_connections_received()
+        ),
+        format!(
+            "total_commands_processed:{}",
+            stats.total_commands_processed()
+        ),
+        format!("expired_keys:{}", data.expired_keys()),
+        format!("keyspace_hits:{}", data.keyspace_hits()),
+        format!("keyspace_misses:{}", data.keyspace_misses()),
+    ]
+}
+
+fn commandstats_section(stats: &Arc<ServerStats>) -> Vec<String> {
+    let mut lines = vec!["# Commandstats".to_string()];
+    lines.extend(stats.command_stats());
+    lines
+}
+
+fn cluster_section(config: &Arc<Mutex<Configuration>>) -> Vec<String> {
+    vec![
+        "# Cluster".to_string(),
+        format!(
+            "cluster_enabled:{}",
+            config.lock().unwrap().get_cluster_enabled() as u8
+        ),
+    ]
+}
+
+fn keyspace_section(data: &Arc<DataStorage>) -> Result<Vec<String>, RedisError> {
+    let mut lines = vec!["# Keyspace".to_string()];
+    let keys = data.len()?;
+    if keys > 0 {
+        lines.push(format!(
+            "db0:keys={},expires={},avg_ttl=0",
+            keys,
+            data.expires_count()
+        ));
+    }
+    Ok(lines)
+}
+
+///Formatea una cantidad de bytes de la misma forma que redis (por ejemplo 1.50K).
+fn bytes_to_human(bytes: usize) -> String {
+    let units = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else {
+        format!("{:.2}{}", value, units[unit])
+    }
+}
+
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
 }
 
 ///Obtiene tiempo total en el cual el servidor se encontro activo.
 pub fn get_system_active_time(sys_time: &Arc<SystemTime>) -> Duration {
     sys_time.elapsed().unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn info(arguments: &[&str], data: &Arc<DataStorage>, stats: &Arc<ServerStats>) -> String {
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments
+                .iter()
+                .map(|x| ProtocolType::String(x.to_string()))
+                .collect(),
+            &Arc::new(Mutex::new(Configuration::new())),
+            &Arc::new(SystemTime::now()),
+            data,
+            stats,
+            &Arc::new(Replication::new()),
+        )
+        .unwrap();
+        builder.serialize()
+    }
+
+    #[test]
+    fn test_info_all_sections() {
+        let data = Arc::new(DataStorage::new());
+        let stats = Arc::new(ServerStats::new());
+
+        let response = info(&[], &data, &stats);
+
+        for section in [
+            "# Server",
+            "# Clients",
+            "# Memory",
+            "# Persistence",
+            "# Stats",
+            "# Replication",
+            "# Cluster",
+            "# Keyspace",
+        ]
+        .iter()
+        {
+            assert!(response.contains(section));
+        }
+        assert!(!response.contains("# Commandstats"));
+        assert!(response.contains(&format!("process_id:{}", process::id())));
+    }
+
+    #[test]
+    fn test_info_single_section() {
+        let data = Arc::new(DataStorage::new());
+        let stats = Arc::new(ServerStats::new());
+        stats.client_connected();
+
+        let response = info(&["CLIENTS"], &data, &stats);
+
+        assert!(response.contains("connected_clients:1\r\n"));
+        assert!(!response.contains("# Server"));
+    }
+
+    #[test]
+    fn test_info_replication() {
+        let data = Arc::new(DataStorage::new());
+        let stats = Arc::new(ServerStats::new());
+
+        let response = info(&["replication"], &data, &stats);
+
+        assert!(response.contains("role:master\r\n"));
+        assert!(response.contains("connected_slaves:0\r\n"));
+        assert!(response.contains("master_repl_offset:0\r\n"));
+    }
+
+    #[test]
+    fn test_info_stats_and_keyspace() {
+        let data = Arc::new(DataStorage::new());
+        let stats = Arc::new(ServerStats::new());
+        data.set("key", Value::String("value".to_string())).unwrap();
+        data.get("key");
+        data.get("missing");
+
+        let response = info(&["stats", "keyspace"], &data, &stats);
+
+        assert!(response.contains("keyspace_hits:1\r\n"));
+        assert!(response.contains("keyspace_misses:1\r\n"));
+        assert!(response.contains("db0:keys=1,expires=0,avg_ttl=0\r\n"));
+    }
+
+    #[test]
+    fn test_info_commandstats() {
+        let data = Arc::new(DataStorage::new());
+        let stats = Arc::new(ServerStats::new());
+        stats.command_called(
+            "get",
+            Duration::from_micros(4),
+            crate::stats::CallOutcome::Succeeded,
+        );
+
+        let response = info(&["commandstats"], &data, &stats);
+        assert!(response.contains(
+            "# Commandstats\r\ncmdstat_get:calls=1,usec=4,usec_per_call=4.00,rejected_calls=0,failed_calls=0\r\n"
+        ));
+        assert!(info(&["all"], &data, &stats).contains("# Commandstats"));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::RedisError;
+use crate::latency::LatencyMonitor;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Includes LATENCY LATEST, HISTORY, RESET and HELP
+/// LATENCY LATEST: returns, for each event, its name, the unix time and latency in milliseconds
+/// of its latest spike, and its highest latency.
+/// LATENCY HISTORY: returns the unix time and latency of the spikes of an event.
+/// LATENCY RESET: removes the given events, or all of them, and returns how many were removed.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    latency: &Arc<LatencyMonitor>,
+) -> Result<(), RedisError> {
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    match &arguments[0].to_ascii_lowercase()[..] {
+        "latest" if arguments.len() == 1 => builder.add(latency.latest()),
+        "history" if arguments.len() == 2 => builder.add(latency.history(&arguments[1])),
+        "reset" => builder.add(ProtocolType::Integer(latency.reset(&arguments[1..]) as i64)),
+        "help" if arguments.len() == 1 => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try LATENCY HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "LATENCY <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "HISTORY <event>",
+        "    Return time-latency samples for the <event> class.",
+        "LATEST",
+        "    Return the latest latency samples for all events.",
+        "RESET [<event> ...]",
+        "    Reset latency data of one or more <event> classes.",
+        "    (default: reset all data for all event classes)",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
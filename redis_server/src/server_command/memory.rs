@@ -0,0 +1,169 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::DataStorage;
+use crate::storage::memory::DEFAULT_SAMPLES;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Includes MEMORY USAGE, MEMORY STATS and MEMORY HELP
+/// MEMORY USAGE: returns the estimated bytes used by a key and its value. Collections are
+/// estimated from SAMPLES elements (5 by default, 0 to measure all of them).
+/// MEMORY STATS: returns a summary of the memory used by the database.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    if arguments.is_empty() {
+        return Err(RedisError::WrongArity("memory"));
+    }
+    match &arguments[0].to_string().to_ascii_lowercase()[..] {
+        "usage" => run_usage(builder, &arguments[1..], data),
+        "stats" if arguments.len() == 1 => run_stats(builder, data),
+        "help" if arguments.len() == 1 => {
+            builder.add(help());
+            Ok(())
+        }
+        _ => Err(RedisError::Generic(
+            "Unknown subcommand or wrong number of arguments. Try MEMORY HELP.",
+        )),
+    }
+}
+
+fn run_usage(
+    builder: &mut ResponseBuilder,
+    arguments: &[ProtocolType],
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    let samples = match arguments.len() {
+        1 => DEFAULT_SAMPLES,
+        3 if arguments[1].to_string().eq_ignore_ascii_case("samples") => arguments[2]
+            .to_string()
+            .parse::<usize>()
+            .ok()
+            .ok_or(RedisError::NotInteger)?,
+        2 | 3 => return Err(RedisError::Syntax),
+        _ => return Err(RedisError::WrongArity("memory|usage")),
+    };
+    let key = arguments[0].clone().string()?;
+
+    match data.memory_usage(&key, samples) {
+        Some(bytes) => builder.add(ProtocolType::Integer(bytes as i64)),
+        None => builder.add(ProtocolType::Nil()),
+    }
+    Ok(())
+}
+
+fn run_stats(builder: &mut ResponseBuilder, data: &Arc<DataStorage>) -> Result<(), RedisError> {
+    let stats = data.memory_stats()?;
+    let used_memory = stats.used_memory();
+    let bytes_per_key = stats.dataset_bytes.checked_div(stats.keys).unwrap_or(0);
+    let percentage = if used_memory == 0 {
+        0.0
+    } else {
+        stats.dataset_bytes as f64 * 100.0 / used_memory as f64
+    };
+
+    builder.add(ProtocolType::Array(vec![
+        ProtocolType::String("total.allocated".to_string()),
+        ProtocolType::Integer(used_memory as i64),
+        ProtocolType::String("overhead.total".to_string()),
+        ProtocolType::Integer(stats.overhead_bytes as i64),
+        ProtocolType::String("keys.count".to_string()),
+        ProtocolType::Integer(stats.keys as i64),
+        ProtocolType::String("keys.bytes-per-key".to_string()),
+        ProtocolType::Integer(bytes_per_key as i64),
+        ProtocolType::String("dataset.bytes".to_string()),
+        ProtocolType::Integer(stats.dataset_bytes as i64),
+        ProtocolType::String("dataset.percentage".to_string()),
+        ProtocolType::String(format!("{:.2}", percentage)),
+    ]));
+    Ok(())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "MEMORY <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "STATS",
+        "    Return information about the memory usage of the server.",
+        "USAGE <key> [SAMPLES <count>]",
+        "    Return memory in bytes used by <key> and its value. Nested values are",
+        "    sampled up to <count> times (default: 5, 0 means sample all).",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::data_storage::Value;
+
+    fn arguments(values: &[&str]) -> Vec<ProtocolType> {
+        values
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let data = Arc::new(DataStorage::new());
+        data.set("key", Value::String("value".to_string())).unwrap();
+        let expected = data.memory_usage("key", 0).unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, arguments(&["USAGE", "key"]), &data).unwrap();
+        assert_eq!(builder.serialize(), format!(":{}\r\n", expected));
+
+        let mut builder = ResponseBuilder::new();
+        run(&mut builder, arguments(&["USAGE", "missing"]), &data).unwrap();
+        assert_eq!(builder.serialize(), "$-1\r\n");
+    }
+
+    #[test]
+    fn test_memory_usage_samples() {
+        let data = Arc::new(DataStorage::new());
+        data.set("key", Value::Vec(vec!["value".to_string(); 10]))
+            .unwrap();
+
+        let mut builder = ResponseBuilder::new();
+        run(
+            &mut builder,
+            arguments(&["USAGE", "key", "SAMPLES", "0"]),
+            &data,
+        )
+        .unwrap();
+        assert_eq!(
+            builder.serialize(),
+            format!(":{}\r\n", data.memory_usage("key", 0).unwrap())
+        );
+
+        let mut builder = ResponseBuilder::new();
+        let result = run(
+            &mut builder,
+            arguments(&["USAGE", "key", "OTHER", "0"]),
+            &data,
+        );
+        assert_eq!(result, Err(RedisError::Syntax));
+    }
+
+    #[test]
+    fn test_memory_stats() {
+        let data = Arc::new(DataStorage::new());
+        data.set("key", Value::String("value".to_string())).unwrap();
+        let mut builder = ResponseBuilder::new();
+
+        run(&mut builder, arguments(&["STATS"]), &data).unwrap();
+
+        let response = builder.serialize();
+        assert!(response.starts_with("*12\r\n$15\r\ntotal.allocated\r\n"));
+        assert!(response.contains("$10\r\nkeys.count\r\n:1\r\n"));
+    }
+}
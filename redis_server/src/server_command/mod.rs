@@ -1,7 +1,21 @@
+pub mod acl;
+pub mod asking;
+pub mod auth;
+pub mod client;
+pub mod cluster;
+pub mod command;
 pub mod config;
 pub mod dbsize;
 pub mod flushdb;
+pub mod hello;
 pub mod info;
+pub mod latency;
+pub mod memory;
 pub mod monitor;
 pub mod ping;
+pub mod psync;
 pub mod quit;
+pub mod replconf;
+pub mod replicaof;
+pub mod role;
+pub mod slowlog;
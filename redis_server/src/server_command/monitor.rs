@@ -1,4 +1,5 @@
 use crate::client::Client;
+use crate::error::RedisError;
 use crate::monitor::Monitor;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,7 +10,8 @@ pub fn run(
     monitor: &Monitor,
     client: Arc<Client>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
+    client.set_monitor_mode(true);
     monitor.add(client)?;
     builder.add(ProtocolType::SimpleString("OK".to_string()));
     Ok(())
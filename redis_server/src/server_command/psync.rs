@@ -0,0 +1,23 @@
+use crate::client::Client;
+use crate::error::RedisError;
+use crate::replication::Replication;
+use crate::storage::data_storage::DataStorage;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// PSYNC replicationid offset
+/// Sent by a replica to receive the replication stream. If the server still has the stream
+/// from the given offset it replies +CONTINUE followed by the missing commands, otherwise
+/// +FULLRESYNC followed by a snapshot of the database. Then the connection receives every
+/// write command executed by the server. The reply is written directly to the client.
+pub fn run(
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    replication: &Arc<Replication>,
+    data: &Arc<DataStorage>,
+) -> Result<(), RedisError> {
+    let replid = arguments[0].to_string();
+    let offset = arguments[1].to_string().parse().ok();
+    replication.sync(&client, &replid, offset, data)?;
+    Ok(())
+}
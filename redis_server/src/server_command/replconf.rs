@@ -0,0 +1,38 @@
+use crate::client::Client;
+use crate::error::RedisError;
+use crate::replication::Replication;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// REPLCONF option value [option value ...]
+/// Configures the replication stream of a replica. `listening-port` sets the port
+/// reported for it, and `ACK` registers the offset it processed, without a reply.
+/// Other options, like `capa`, are accepted and ignored.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    client: Arc<Client>,
+    replication: &Arc<Replication>,
+) -> Result<(), RedisError> {
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    if !arguments.len().is_multiple_of(2) {
+        return Err(RedisError::Syntax);
+    }
+    for pair in arguments.chunks(2) {
+        match pair[0].to_ascii_lowercase().as_str() {
+            "listening-port" => {
+                let port = pair[1].parse().map_err(|_| RedisError::NotInteger)?;
+                replication.set_listening_port(&client, port);
+            }
+            "ack" => {
+                let offset = pair[1].parse().map_err(|_| RedisError::NotInteger)?;
+                replication.ack(&client, offset);
+                return Ok(());
+            }
+            _ => {}
+        }
+    }
+    builder.add(ProtocolType::SimpleString("OK".to_string()));
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::error::RedisError;
+use crate::replication::Replication;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// REPLICAOF host port | REPLICAOF NO ONE
+/// Makes the server a replica of the given master, which it syncs with in the background,
+/// or turns a replica into a master that keeps its data. SLAVEOF is an alias.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    replication: &Arc<Replication>,
+) -> Result<(), RedisError> {
+    let host = arguments[0].to_string();
+    let port = arguments[1].to_string();
+    if host.eq_ignore_ascii_case("no") && port.eq_ignore_ascii_case("one") {
+        replication.promote();
+        builder.add(ProtocolType::SimpleString("OK".to_string()));
+        return Ok(());
+    }
+
+    let port = port
+        .parse()
+        .map_err(|_| RedisError::Generic("Invalid master port"))?;
+    let reply = if replication.replicate_from(&host, port) {
+        "OK"
+    } else {
+        "OK Already connected to specified master"
+    };
+    builder.add(ProtocolType::SimpleString(reply.to_string()));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replicaof(host: &str, port: &str, replication: &Arc<Replication>) -> String {
+        let mut builder = ResponseBuilder::new();
+        let arguments = vec![
+            ProtocolType::String(host.to_string()),
+            ProtocolType::String(port.to_string()),
+        ];
+        match run(&mut builder, arguments, replication) {
+            Ok(()) => builder.serialize(),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_replicaof() {
+        let replication = Arc::new(Replication::new());
+
+        assert_eq!(replicaof("127.0.0.1", "6380", &replication), "+OK\r\n");
+        assert!(replication.is_replica());
+        assert_eq!(
+            replicaof("127.0.0.1", "6380", &replication),
+            "+OK Already connected to specified master\r\n"
+        );
+        assert_eq!(
+            replicaof("127.0.0.1", "port", &replication),
+            "ERR Invalid master port"
+        );
+
+        assert_eq!(replicaof("NO", "ONE", &replication), "+OK\r\n");
+        assert!(!replication.is_replica());
+    }
+}
@@ -0,0 +1,16 @@
+use crate::error::RedisError;
+use crate::replication::Replication;
+use redis_protocol::response::ResponseBuilder;
+use std::sync::Arc;
+
+/// ROLE
+/// Returns the replication role of the server. A master replies with its offset and
+/// the address and offset of every replica, and a replica with the address of its master,
+/// the state of the link with it and its offset.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    replication: &Arc<Replication>,
+) -> Result<(), RedisError> {
+    builder.add(replication.role_reply());
+    Ok(())
+}
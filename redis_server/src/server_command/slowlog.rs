@@ -0,0 +1,72 @@
+use crate::error::RedisError;
+use crate::slowlog::SlowLog;
+use redis_protocol::response::ResponseBuilder;
+use redis_protocol::types::ProtocolType;
+use std::sync::Arc;
+
+/// Number of entries returned by SLOWLOG GET when no count is given
+const DEFAULT_COUNT: usize = 10;
+
+/// Includes SLOWLOG GET, LEN, RESET and HELP
+/// SLOWLOG GET [count]: returns the newest entries of the slow log, 10 unless a count is given,
+/// or all of them with a count of -1. Each entry has its id, the unix time it was recorded at,
+/// the execution time in microseconds, the arguments of the command and the client address and name.
+/// SLOWLOG LEN: returns the number of entries.
+/// SLOWLOG RESET: removes every entry.
+pub fn run(
+    builder: &mut ResponseBuilder,
+    arguments: Vec<ProtocolType>,
+    slowlog: &Arc<SlowLog>,
+) -> Result<(), RedisError> {
+    let arguments: Vec<String> = arguments.iter().map(|x| x.to_string()).collect();
+    match &arguments[0].to_ascii_lowercase()[..] {
+        "get" if arguments.len() <= 2 => {
+            let count = match arguments.get(1).map(|x| x.parse::<i64>()) {
+                None => Some(DEFAULT_COUNT),
+                Some(Ok(-1)) => None,
+                Some(Ok(count)) if count >= 0 => Some(count as usize),
+                _ => {
+                    return Err(RedisError::Generic(
+                        "count should be greater than or equal to -1",
+                    ))
+                }
+            };
+            builder.add(slowlog.get(count));
+        }
+        "len" if arguments.len() == 1 => builder.add(ProtocolType::Integer(slowlog.len() as i64)),
+        "reset" if arguments.len() == 1 => {
+            slowlog.reset();
+            builder.add(ProtocolType::SimpleString("OK".to_string()));
+        }
+        "help" if arguments.len() == 1 => builder.add(help()),
+        _ => {
+            return Err(RedisError::Generic(
+                "Unknown subcommand or wrong number of arguments. Try SLOWLOG HELP.",
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn help() -> ProtocolType {
+    let lines = [
+        "SLOWLOG <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+        "GET [<count>]",
+        "    Return top <count> entries from the slowlog (default: 10, -1 mean all).",
+        "    Entries are made of:",
+        "    id, timestamp, time in microseconds, arguments array, client IP and port,",
+        "    client name",
+        "LEN",
+        "    Return the length of the slowlog.",
+        "RESET",
+        "    Reset the slowlog.",
+        "HELP",
+        "    Print this help.",
+    ];
+    ProtocolType::Array(
+        lines
+            .iter()
+            .map(|x| ProtocolType::SimpleString(x.to_string()))
+            .collect(),
+    )
+}
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
@@ -9,20 +10,17 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("ERR wrong number of arguments");
+        return Err(RedisError::WrongArity("scard"));
     }
     let string_key = arguments[0].clone().string()?;
     let result = data.get(&string_key);
     match result {
         Some(value) => match value {
-            Value::String(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
-            Value::Vec(_) => {
-                return Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-            }
+            Value::String(_) => return Err(RedisError::WrongType),
+            Value::Vec(_) => return Err(RedisError::WrongType),
+            Value::Geo(_) => return Err(RedisError::WrongType),
             Value::HashSet(set) => {
                 builder.add(ProtocolType::Integer(set.len() as i64));
             }
@@ -89,10 +87,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 
@@ -110,10 +105,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 }
@@ -1,16 +1,17 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
 use std::sync::Arc;
 
-/// Returns all the members of the set value stored at key.
+/// Returns all the members of the set value stored at key, as a set in RESP3.
 pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("Wrong quantity of arguments.");
+        return Err(RedisError::WrongArity("smembers"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -18,7 +19,7 @@ pub fn run(
 
     match result {
         Ok(s) => {
-            builder.add(ProtocolType::Array(
+            builder.add(ProtocolType::Set(
                 s.into_iter().map(ProtocolType::String).collect(),
             ));
             Ok(())
@@ -0,0 +1,249 @@
+use crate::client::Client;
+use redis_protocol::command::Command;
+use redis_protocol::types::ProtocolType;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Most arguments of a command kept in an entry, like redis does
+const MAX_ARGUMENTS: usize = 32;
+/// Most characters of an argument kept in an entry
+const MAX_ARGUMENT_LEN: usize = 128;
+
+/// A command that took longer than the `slowlog-log-slower-than` threshold.
+struct SlowLogEntry {
+    id: u64,
+    timestamp: u64,
+    duration: u64,
+    arguments: Vec<String>,
+    addr: String,
+    name: String,
+}
+
+impl SlowLogEntry {
+    fn reply(&self) -> ProtocolType {
+        ProtocolType::Array(vec![
+            ProtocolType::Integer(self.id as i64),
+            ProtocolType::Integer(self.timestamp as i64),
+            ProtocolType::Integer(self.duration as i64),
+            ProtocolType::Array(
+                self.arguments
+                    .iter()
+                    .map(|x| ProtocolType::String(x.clone()))
+                    .collect(),
+            ),
+            ProtocolType::String(self.addr.clone()),
+            ProtocolType::String(self.name.clone()),
+        ])
+    }
+}
+
+struct Entries {
+    next_id: u64,
+    entries: VecDeque<SlowLogEntry>,
+}
+
+/// Struct SlowLog. Keeps the last commands slower than the configured threshold,
+/// newest first, in a buffer of at most `slowlog-max-len` entries.
+pub struct SlowLog {
+    entries: Mutex<Entries>,
+}
+
+#[allow(clippy::new_without_default)]
+impl SlowLog {
+    pub fn new() -> Self {
+        SlowLog {
+            entries: Mutex::new(Entries {
+                next_id: 0,
+                entries: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records a command that took the given time, if it is over the threshold in microseconds.
+    /// A negative threshold disables the slow log, and a threshold of zero records every command.
+    pub fn record(
+        &self,
+        command: &Command,
+        client: &Client,
+        duration: Duration,
+        threshold: i64,
+        max_len: usize,
+    ) {
+        if threshold < 0 || duration.as_micros() < threshold as u128 {
+            return;
+        }
+        let entry = SlowLogEntry {
+            id: 0,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|x| x.as_secs())
+                .unwrap_or(0),
+            duration: duration.as_micros() as u64,
+            arguments: entry_arguments(command),
+            addr: client.addr().to_string(),
+            name: client.name().unwrap_or_default(),
+        };
+
+        let mut lock = self.lock();
+        let id = lock.next_id;
+        lock.next_id += 1;
+        lock.entries.push_front(SlowLogEntry { id, ..entry });
+        lock.entries.truncate(max_len);
+    }
+
+    /// Returns the newest entries, or all of them if no count is given
+    pub fn get(&self, count: Option<usize>) -> ProtocolType {
+        let lock = self.lock();
+        ProtocolType::Array(
+            lock.entries
+                .iter()
+                .take(count.unwrap_or(usize::MAX))
+                .map(|x| x.reply())
+                .collect(),
+        )
+    }
+
+    /// Returns the number of entries
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Removes every entry
+    pub fn reset(&self) {
+        self.lock().entries.clear();
+    }
+}
+
+/// Returns the name and arguments of a command, hiding the passwords given to AUTH and HELLO.
+pub fn redacted_arguments(command: &Command) -> Vec<String> {
+    let name = command.name();
+    let mut arguments: Vec<String> = command.arguments().iter().map(|x| x.to_string()).collect();
+    if name.eq_ignore_ascii_case("auth") {
+        arguments = vec!["(redacted)".to_string(); arguments.len()];
+    } else if name.eq_ignore_ascii_case("hello") {
+        if let Some(auth) = arguments
+            .iter()
+            .position(|x| x.eq_ignore_ascii_case("auth"))
+        {
+            for argument in arguments.iter_mut().skip(auth + 1).take(2) {
+                *argument = "(redacted)".to_string();
+            }
+        }
+    }
+    arguments.insert(0, name);
+    arguments
+}
+
+/// Returns the name and arguments of a command as kept in the slow log. Passwords are
+/// hidden, and long commands are shortened like redis does.
+fn entry_arguments(command: &Command) -> Vec<String> {
+    let arguments = redacted_arguments(command);
+    let total = arguments.len();
+    let mut kept: Vec<String> = arguments
+        .into_iter()
+        .take(MAX_ARGUMENTS)
+        .map(|x| {
+            let length = x.chars().count();
+            if length > MAX_ARGUMENT_LEN {
+                let prefix: String = x.chars().take(MAX_ARGUMENT_LEN).collect();
+                format!("{}... ({} more bytes)", prefix, length - MAX_ARGUMENT_LEN)
+            } else {
+                x
+            }
+        })
+        .collect();
+    if total > MAX_ARGUMENTS {
+        kept[MAX_ARGUMENTS - 1] = format!("... ({} more arguments)", total - MAX_ARGUMENTS + 1);
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{client, command};
+
+    #[test]
+    fn test_record_over_threshold() {
+        let slowlog = SlowLog::new();
+        let client = client();
+        client.set_name("worker");
+
+        slowlog.record(
+            &command(&["GET", "a"]),
+            &client,
+            Duration::from_micros(50),
+            100,
+            10,
+        );
+        slowlog.record(
+            &command(&["SET", "a", "1"]),
+            &client,
+            Duration::from_micros(150),
+            100,
+            10,
+        );
+        slowlog.record(
+            &command(&["GET", "a"]),
+            &client,
+            Duration::from_micros(10),
+            -1,
+            10,
+        );
+        assert_eq!(slowlog.len(), 1);
+
+        let reply = slowlog.get(None).serialize();
+        assert!(reply.starts_with("*1\r\n*6\r\n:0\r\n:"));
+        assert!(reply.contains(":150\r\n*3\r\n$3\r\nSET\r\n$1\r\na\r\n$1\r\n1\r\n"));
+        assert!(reply.ends_with("$6\r\nworker\r\n"));
+
+        slowlog.reset();
+        assert_eq!(slowlog.len(), 0);
+    }
+
+    #[test]
+    fn test_buffer_is_bounded() {
+        let slowlog = SlowLog::new();
+        let client = client();
+        for i in 0..5 {
+            let key = i.to_string();
+            slowlog.record(&command(&["GET", &key]), &client, Duration::ZERO, 0, 3);
+        }
+
+        assert_eq!(slowlog.len(), 3);
+        let newest = slowlog.get(Some(1)).serialize();
+        assert!(newest.starts_with("*1\r\n*6\r\n:4\r\n"));
+        assert!(newest.contains("$3\r\nGET\r\n$1\r\n4\r\n"));
+    }
+
+    #[test]
+    fn test_entry_arguments() {
+        assert_eq!(
+            entry_arguments(&command(&["AUTH", "user", "secret"])),
+            vec!["AUTH", "(redacted)", "(redacted)"]
+        );
+        assert_eq!(
+            entry_arguments(&command(&["HELLO", "3", "AUTH", "user", "secret"])),
+            vec!["HELLO", "3", "AUTH", "(redacted)", "(redacted)"]
+        );
+
+        let long = "x".repeat(130);
+        let arguments = entry_arguments(&command(&["SET", "key", &long]));
+        assert_eq!(
+            arguments[2],
+            format!("{}... (2 more bytes)", "x".repeat(128))
+        );
+
+        let keys: Vec<String> = (0..40).map(|x| x.to_string()).collect();
+        let mut values = vec!["DEL"];
+        values.extend(keys.iter().map(|x| x.as_str()));
+        let arguments = entry_arguments(&command(&values));
+        assert_eq!(arguments.len(), 32);
+        assert_eq!(arguments[31], "... (10 more arguments)");
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::hash_map::RandomState;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How the execution of a command ended, as counted by INFO commandstats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CallOutcome {
+    /// The command ran and succeeded.
+    Succeeded,
+    /// The command was refused before running, like a wrong arity or a missing permission.
+    Rejected,
+    /// The command ran and returned an error.
+    Failed,
+}
+
+/// The calls of a single command.
+#[derive(Default)]
+struct CommandStats {
+    calls: u64,
+    usec: u64,
+    rejected_calls: u64,
+    failed_calls: u64,
+}
+
+/// Struct ServerStats. Holds the live counters of the server reported by INFO.
+/// Every counter is atomic so it can be shared between the connection threads.
+pub struct ServerStats {
+    run_id: String,
+    connected_clients: AtomicU64,
+    total_connections_received: AtomicU64,
+    total_commands_processed: AtomicU64,
+    last_save_time: AtomicU64,
+    last_save_ok: AtomicBool,
+    commands: Mutex<BTreeMap<String, CommandStats>>,
+}
+
+/// Implementation of the ServerStats structure.
+#[allow(clippy::new_without_default)]
+impl ServerStats {
+    /// Create the ServerStats structure with every counter at zero and a random run id.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::stats::ServerStats;
+    /// let stats = ServerStats::new();
+    /// ```
+    ///
+    pub fn new() -> Self {
+        ServerStats {
+            run_id: random_run_id(),
+            connected_clients: AtomicU64::new(0),
+            total_connections_received: AtomicU64::new(0),
+            total_commands_processed: AtomicU64::new(0),
+            last_save_time: AtomicU64::new(now_secs()),
+            last_save_ok: AtomicBool::new(true),
+            commands: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the random identifier of this server instance.
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    /// Registers a new accepted connection.
+    pub fn client_connected(&self) {
+        self.connected_clients.fetch_add(1, Ordering::SeqCst);
+        self.total_connections_received
+            .fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Registers that a connection was closed.
+    pub fn client_disconnected(&self) {
+        let _ = self
+            .connected_clients
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |x| x.checked_sub(1));
+    }
+
+    /// Returns the number of clients currently connected.
+    pub fn connected_clients(&self) -> u64 {
+        self.connected_clients.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of connections accepted since the server started.
+    pub fn total_connections_received(&self) -> u64 {
+        self.total_connections_received.load(Ordering::SeqCst)
+    }
+
+    /// Registers an executed command.
+    pub fn command_processed(&self) {
+        self.total_commands_processed.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns the number of commands executed since the server started.
+    pub fn total_commands_processed(&self) -> u64 {
+        self.total_commands_processed.load(Ordering::SeqCst)
+    }
+
+    /// Registers the result of saving the database to disk.
+    pub fn saved(&self, ok: bool) {
+        self.last_save_ok.store(ok, Ordering::SeqCst);
+        if ok {
+            self.last_save_time.store(now_secs(), Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the unix time of the last successful save.
+    pub fn last_save_time(&self) -> u64 {
+        self.last_save_time.load(Ordering::SeqCst)
+    }
+
+    /// Returns if the last save was successful.
+    pub fn last_save_ok(&self) -> bool {
+        self.last_save_ok.load(Ordering::SeqCst)
+    }
+
+    /// Registers a call to a command, with its execution time and how it ended.
+    /// Rejected calls are only counted as such, because the command never ran.
+    pub fn command_called(&self, name: &str, duration: Duration, outcome: CallOutcome) {
+        let mut commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = commands.entry(name.to_ascii_lowercase()).or_default();
+        match outcome {
+            CallOutcome::Rejected => stats.rejected_calls += 1,
+            _ => {
+                stats.calls += 1;
+                stats.usec += duration.as_micros() as u64;
+                if outcome == CallOutcome::Failed {
+                    stats.failed_calls += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns a line for each called command, sorted by name, in the format of INFO commandstats.
+    pub fn command_stats(&self) -> Vec<String> {
+        let commands = self.commands.lock().unwrap_or_else(|e| e.into_inner());
+        commands
+            .iter()
+            .map(|(name, stats)| {
+                format!(
+                    "cmdstat_{}:calls={},usec={},usec_per_call={:.2},rejected_calls={},failed_calls={}",
+                    name,
+                    stats.calls,
+                    stats.usec,
+                    if stats.calls == 0 {
+                        0.0
+                    } else {
+                        stats.usec as f64 / stats.calls as f64
+                    },
+                    stats.rejected_calls,
+                    stats.failed_calls
+                )
+            })
+            .collect()
+    }
+
+    /// Sets the counters of connections and commands back to zero, like CONFIG RESETSTAT.
+    pub fn reset(&self) {
+        self.total_connections_received.store(0, Ordering::SeqCst);
+        self.total_commands_processed.store(0, Ordering::SeqCst);
+        self.commands
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or(0)
+}
+
+/// Builds a 40 characters hexadecimal identifier like the one used by redis.
+pub(crate) fn random_run_id() -> String {
+    (0..3)
+        .map(|i| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u64(i);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect::<String>()[..40]
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connections() {
+        let stats = ServerStats::new();
+        stats.client_connected();
+        stats.client_connected();
+        stats.client_disconnected();
+
+        assert_eq!(stats.connected_clients(), 1);
+        assert_eq!(stats.total_connections_received(), 2);
+    }
+
+    #[test]
+    fn test_disconnect_never_underflows() {
+        let stats = ServerStats::new();
+        stats.client_disconnected();
+
+        assert_eq!(stats.connected_clients(), 0);
+    }
+
+    #[test]
+    fn test_run_id() {
+        let stats = ServerStats::new();
+
+        assert_eq!(stats.run_id().len(), 40);
+        assert!(stats.run_id().chars().all(|x| x.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn test_command_stats() {
+        let stats = ServerStats::new();
+        stats.command_called("SET", Duration::from_micros(10), CallOutcome::Succeeded);
+        stats.command_called("set", Duration::from_micros(20), CallOutcome::Failed);
+        stats.command_called("get", Duration::ZERO, CallOutcome::Rejected);
+
+        assert_eq!(
+            stats.command_stats(),
+            vec![
+                "cmdstat_get:calls=0,usec=0,usec_per_call=0.00,rejected_calls=1,failed_calls=0",
+                "cmdstat_set:calls=2,usec=30,usec_per_call=15.00,rejected_calls=0,failed_calls=1",
+            ]
+        );
+
+        stats.command_processed();
+        stats.reset();
+        assert!(stats.command_stats().is_empty());
+        assert_eq!(stats.total_commands_processed(), 0);
+    }
+
+    #[test]
+    fn test_failed_save_keeps_last_time() {
+        let stats = ServerStats::new();
+        let last_save = stats.last_save_time();
+        stats.saved(false);
+
+        assert!(!stats.last_save_ok());
+        assert_eq!(stats.last_save_time(), last_save);
+    }
+}
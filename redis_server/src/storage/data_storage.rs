@@ -1,14 +1,18 @@
+use crate::error::RedisError;
 use crate::storage::entry::Entry;
+use crate::storage::memory::{self, MemoryStats};
 use crate::storage::parser;
 use crate::storage::SafeDataStorage;
+use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::sync::RwLock;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::RwLockReadGuard;
 use std::sync::{Arc, RwLockWriteGuard};
+use std::sync::{PoisonError, RwLock};
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
-use std::usize;
 
 /// Enumeration value. Contains all supported data types
 /// for the DataStorage.
@@ -17,6 +21,7 @@ pub enum Value {
     String(String),
     Vec(Vec<String>),
     HashSet(HashSet<String>),
+    Geo(HashMap<String, u64>),
 }
 
 #[allow(dead_code)]
@@ -37,10 +42,10 @@ impl Value {
     /// value.string();
     /// ```
     ///
-    pub fn string(&self) -> Result<String, &'static str> {
+    pub fn string(&self) -> Result<String, RedisError> {
         match self {
             Value::String(s) => Ok(s.clone()),
-            _ => Err("Failed to cast Value to string"),
+            _ => Err(RedisError::WrongType),
         }
     }
 
@@ -59,10 +64,10 @@ impl Value {
     /// value.array();
     /// ```
     ///
-    pub fn array(&self) -> Result<Vec<String>, &'static str> {
+    pub fn array(&self) -> Result<Vec<String>, RedisError> {
         match self {
             Value::Vec(v) => Ok(v.clone()),
-            _ => Err("Failed to cast Value to string"),
+            _ => Err(RedisError::WrongType),
         }
     }
 
@@ -83,10 +88,34 @@ impl Value {
     /// value.set();
     /// ```
     ///
-    pub fn set(&self) -> Result<HashSet<String>, &'static str> {
+    pub fn set(&self) -> Result<HashSet<String>, RedisError> {
         match self {
             Value::HashSet(s) => Ok(s.clone()),
-            _ => Err("Failed to cast Value to string"),
+            _ => Err(RedisError::WrongType),
+        }
+    }
+
+    /// Given a possible Value Geo, it analyzes if the value
+    /// can be obtained as a geo index and returns it, if it is another type of data,
+    /// it returns an error.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::Value;
+    /// use std::collections::HashMap;
+    /// let mut index = HashMap::new();
+    /// index.insert("Palermo".to_string(), 3479099956230698);
+    /// let value = Value::Geo(index);
+    /// value.geo();
+    /// ```
+    ///
+    pub fn geo(&self) -> Result<HashMap<String, u64>, RedisError> {
+        match self {
+            Value::Geo(g) => Ok(g.clone()),
+            _ => Err(RedisError::WrongType),
         }
     }
 }
@@ -96,6 +125,9 @@ impl Value {
 /// Structure protected by a RwLock.
 pub struct DataStorage {
     data: SafeDataStorage,
+    keyspace_hits: AtomicU64,
+    keyspace_misses: AtomicU64,
+    expired_keys: AtomicU64,
 }
 
 /// Implementation of the DataStorage structure.
@@ -116,6 +148,9 @@ impl DataStorage {
     pub fn new() -> Self {
         DataStorage {
             data: Arc::new(RwLock::new(HashMap::new())),
+            keyspace_hits: AtomicU64::new(0),
+            keyspace_misses: AtomicU64::new(0),
+            expired_keys: AtomicU64::new(0),
         }
     }
 
@@ -140,11 +175,11 @@ impl DataStorage {
     /// data.load_data(&"new_file.txt");
     /// ```
     ///
-    pub fn load_data(&self, file: &str) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn load_data(&self, file: &str) -> Result<(), RedisError> {
+        let mut lock = self.write();
         match parser::parse_data(file, &mut lock) {
             Ok(_s) => Ok(()),
-            Err(_i) => Err("Could not parse the file"),
+            Err(_i) => Err(RedisError::Generic("Could not parse the file")),
         }
     }
 
@@ -167,8 +202,8 @@ impl DataStorage {
     /// data.save_data(&"file.txt");
     /// ```
     ///
-    pub fn save_data(&self, file: &str) -> Result<(), &'static str> {
-        let lock = self.data.read().ok().ok_or("Failed to lock database")?;
+    pub fn save_data(&self, file: &str) -> Result<(), RedisError> {
+        let lock = self.read();
         parser::store_data(file, &lock);
         Ok(())
     }
@@ -195,8 +230,8 @@ impl DataStorage {
     /// data.set(&"key", Value::String("hola".to_string()));
     /// ```
     ///
-    pub fn set(&self, key: &str, value: Value) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn set(&self, key: &str, value: Value) -> Result<(), RedisError> {
+        let mut lock = self.write();
         self.do_set(&mut lock, key, value)?;
         Ok(())
     }
@@ -212,13 +247,8 @@ impl DataStorage {
         lock: &mut RwLockWriteGuard<HashMap<String, Entry>>,
         key: &str,
         value: Value,
-    ) -> Result<(), &'static str> {
-        let copy_key = key.to_string();
-        match value {
-            Value::String(s) => lock.insert(copy_key, Entry::new(now()?, None, Value::String(s))),
-            Value::Vec(i) => lock.insert(copy_key, Entry::new(now()?, None, Value::Vec(i))),
-            Value::HashSet(j) => lock.insert(copy_key, Entry::new(now()?, None, Value::HashSet(j))),
-        };
+    ) -> Result<(), RedisError> {
+        lock.insert(key.to_string(), Entry::new(now()?, None, value));
         Ok(())
     }
 
@@ -242,8 +272,8 @@ impl DataStorage {
     /// data.set_multiple(keys, values);
     /// ```
     ///
-    pub fn set_multiple(&self, keys: Vec<String>, values: Vec<Value>) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn set_multiple(&self, keys: Vec<String>, values: Vec<Value>) -> Result<(), RedisError> {
+        let mut lock = self.write();
         for (key, value) in keys.iter().zip(values) {
             self.do_set(&mut lock, key, value)?;
         }
@@ -268,8 +298,8 @@ impl DataStorage {
     /// data.delete_key(&"key".to_string());
     /// ```
     ///
-    pub fn delete_key(&self, key: &str) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn delete_key(&self, key: &str) -> Result<(), RedisError> {
+        let mut lock = self.write();
         self.do_delete_key(&mut lock, key)
     }
 
@@ -277,14 +307,14 @@ impl DataStorage {
     /// PRE: The DataStorage structure must be created.
     /// POST: The key is removed and its corresponding value. In case
     /// if the key is not in the structure, an error is thrown.
-    fn do_delete_key<'i>(
+    fn do_delete_key(
         &self,
-        lock: &'i mut RwLockWriteGuard<HashMap<String, Entry>>,
+        lock: &mut RwLockWriteGuard<HashMap<String, Entry>>,
         key: &str,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), RedisError> {
         match lock.remove(key) {
             Some(_a) => Ok(()),
-            None => Err("Not key in HashMap"),
+            None => Err(RedisError::NoSuchKey),
         }
     }
 
@@ -300,8 +330,8 @@ impl DataStorage {
     /// data.delete_all();
     /// ```
     ///
-    pub fn delete_all(&self) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn delete_all(&self) -> Result<(), RedisError> {
+        let mut lock = self.write();
         lock.clear();
         Ok(())
     }
@@ -318,7 +348,7 @@ impl DataStorage {
     /// ```
     ///
     pub fn is_empty(&self) -> bool {
-        let lock = self.data.read().unwrap();
+        let lock = self.read();
         lock.is_empty()
     }
 
@@ -337,11 +367,11 @@ impl DataStorage {
     /// let exists = data.exists_key(&"key".to_string());
     /// ```
     ///
-    pub fn exists_key(&self, key: &str) -> Result<(), &'static str> {
+    pub fn exists_key(&self, key: &str) -> Result<(), RedisError> {
         let value = self.get(key);
         match value {
             Some(_) => Ok(()),
-            None => Err("Not key in HashMap"),
+            None => Err(RedisError::NoSuchKey),
         }
     }
 
@@ -356,8 +386,8 @@ impl DataStorage {
     /// let len = data.len();
     /// ```
     ///
-    pub fn len(&self) -> Result<usize, &'static str> {
-        let lock = self.data.read().ok().ok_or("Failed to lock database")?;
+    pub fn len(&self) -> Result<usize, RedisError> {
+        let lock = self.read();
         let mut count = 0;
         for entry in lock.values() {
             match entry.value() {
@@ -379,8 +409,15 @@ impl DataStorage {
     /// let read_data = data.read();
     /// ```
     ///
+    /// A lock poisoned by a panic is recovered, so a failed command does not break the database.
     pub fn read(&self) -> RwLockReadGuard<'_, HashMap<String, Entry>> {
-        self.data.read().unwrap()
+        self.data.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns a write reference for the DataStorage structure,
+    /// recovering the lock if it was poisoned by a panic.
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, Entry>> {
+        self.data.write().unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Returns a copy of the value at key or none if it doesnt exist.
@@ -423,31 +460,34 @@ impl DataStorage {
     /// ```
     ///
     pub fn get_with_expiration(&self, key: &str) -> Option<(Option<Duration>, Value)> {
-        let lock = self.data.read().ok()?;
+        let result = self.do_get_with_expiration(key);
+        match result {
+            Some(_) => self.keyspace_hits.fetch_add(1, Ordering::SeqCst),
+            None => self.keyspace_misses.fetch_add(1, Ordering::SeqCst),
+        };
+        result
+    }
 
-        if lock.contains_key(key) {
-            let result = lock.get(key).unwrap();
-            let key_exp = result.key_expiration();
+    /// Returns the expiration and value of a key, deleting it if it has expired.
+    /// The last access of the key is updated under the same lock it is read with.
+    fn do_get_with_expiration(&self, key: &str) -> Option<(Option<Duration>, Value)> {
+        let mut lock = self.write();
+        let entry = lock.get_mut(key)?;
 
-            if key_exp != Ok(None) {
-                match result.value() {
-                    Ok(value) => {
-                        drop(lock);
-                        self.modify_last_key_access(key, now().ok()?).unwrap();
-                        return Some((key_exp.unwrap(), value));
-                    }
-                    Err(_s) => {
-                        // Key has expired, we should delete it
-                        drop(lock);
-                        self.delete_key(key).unwrap();
-                        return None;
-                    }
+        match (entry.key_expiration(), entry.value()) {
+            (Ok(expiration), Ok(value)) => {
+                if let Ok(now) = now() {
+                    let _ = entry.set_last_access(now);
                 }
+                Some((expiration, value))
+            }
+            _ => {
+                // Key has expired, we should delete it
+                lock.remove(key);
+                self.expired_keys.fetch_add(1, Ordering::SeqCst);
+                None
             }
-            return Some((None, result.value().unwrap()));
         }
-
-        None
     }
 
     /// Give a key it returns Ok(Some(entryF)) for a specified key,
@@ -462,7 +502,7 @@ impl DataStorage {
         &self,
         key: &str,
         lock: &'i mut RwLockWriteGuard<HashMap<String, Entry>>,
-    ) -> Result<Option<&'i mut Entry>, &'static str> {
+    ) -> Result<Option<&'i mut Entry>, RedisError> {
         if lock.contains_key(key) {
             let entry: &Entry = lock.get(key).unwrap();
             let key_exp = entry.key_expiration();
@@ -476,12 +516,16 @@ impl DataStorage {
                             Ok(Some(()))
                         } else {
                             self.do_delete_key(lock, key)?;
+                            self.expired_keys.fetch_add(1, Ordering::SeqCst);
                             Ok(None)
                         }
                     }
-                    None => Ok(Some(())),
+                    None => {
+                        self.do_modify_last_key_access(lock, key, now()?)?;
+                        Ok(Some(()))
+                    }
                 },
-                Err(_) => Err("No value for that key"),
+                Err(_) => Err(RedisError::NoSuchKey),
             };
             match res {
                 Ok(v) => match v {
@@ -491,7 +535,7 @@ impl DataStorage {
                 Err(e) => Err(e),
             }
         } else {
-            Err("No value for that key")
+            Err(RedisError::NoSuchKey)
         }
     }
 
@@ -511,8 +555,8 @@ impl DataStorage {
     /// let value = data.lpop("key".to_string(), 0);
     /// ```
     ///
-    pub fn lpop(&self, key: String, count: usize) -> Result<Vec<String>, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn lpop(&self, key: String, count: usize) -> Result<Vec<String>, RedisError> {
+        let mut lock = self.write();
         let mut result = Vec::new();
         let _ = self.do_apply_vec(key, &mut lock, |list| {
             for _ in 0..count {
@@ -536,7 +580,7 @@ impl DataStorage {
         key: String,
         lock: &mut RwLockWriteGuard<HashMap<String, Entry>>,
         mut apply: F,
-    ) -> Result<usize, &'static str> {
+    ) -> Result<usize, RedisError> {
         let res_entry = self.get_entry(&key, lock);
         if res_entry.is_err() {
             return Ok(0);
@@ -544,18 +588,15 @@ impl DataStorage {
         match res_entry.unwrap() {
             Some(entry) => match entry.value() {
                 Ok(val) => match val {
-                    Value::String(_) => {
-                        Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                    }
+                    Value::String(_) => Err(RedisError::WrongType),
                     Value::Vec(mut v) => {
                         apply(&mut v);
                         let len = v.len();
                         entry.update_value(Value::Vec(v))?;
                         Ok(len)
                     }
-                    Value::HashSet(_) => {
-                        Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                    }
+                    Value::HashSet(_) => Err(RedisError::WrongType),
+                    Value::Geo(_) => Err(RedisError::WrongType),
                 },
                 Err(_) => Ok(0),
             },
@@ -580,8 +621,8 @@ impl DataStorage {
     /// let value = data.append("key".to_string(), "new_value".to_string());
     /// ```
     ///
-    pub fn append(&self, key: String, value: String) -> Result<usize, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn append(&self, key: String, value: String) -> Result<usize, RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(&key, &mut lock);
 
         match res_entry {
@@ -595,15 +636,16 @@ impl DataStorage {
                             entry.update_value(Value::String(new_string))?;
                             Ok(length)
                         }
-                        Value::Vec(_i) => Err("Value must be a string not a vector"),
-                        Value::HashSet(_j) => Err("Value must be a string not a set"),
+                        Value::Vec(_i) => Err(RedisError::WrongType),
+                        Value::HashSet(_j) => Err(RedisError::WrongType),
+                        Value::Geo(_) => Err(RedisError::WrongType),
                     }
                 }
                 None => {
                     let value_copy = value.clone();
                     match self.do_set(&mut lock, &key, Value::String(value_copy)) {
                         Ok(_s) => Ok(value.len()),
-                        Err(_i) => Err("String value not created"),
+                        Err(_i) => Err(RedisError::Generic("String value not created")),
                     }
                 }
             },
@@ -611,7 +653,7 @@ impl DataStorage {
                 let value_copy = value.clone();
                 match self.do_set(&mut lock, &key, Value::String(value_copy)) {
                     Ok(_s) => Ok(value.len()),
-                    Err(_i) => Err("String value not created"),
+                    Err(_i) => Err(RedisError::Generic("String value not created")),
                 }
             }
         }
@@ -633,14 +675,15 @@ impl DataStorage {
     /// let value = data.get_string_value("key".to_string());
     /// ```
     ///
-    pub fn get_string_value(&self, key: String) -> Result<Option<String>, &'static str> {
+    pub fn get_string_value(&self, key: String) -> Result<Option<String>, RedisError> {
         let value = self.get(&key);
 
         match value {
             Some(val) => match val {
                 Value::String(string_value) => Ok(Some(string_value)),
-                Value::Vec(_i) => Err("value not a string"),
-                Value::HashSet(_j) => Err("value not a string"),
+                Value::Vec(_i) => Err(RedisError::WrongType),
+                Value::HashSet(_j) => Err(RedisError::WrongType),
+                Value::Geo(_) => Err(RedisError::WrongType),
             },
             None => Ok(None),
         }
@@ -666,8 +709,8 @@ impl DataStorage {
     /// data.getset(&"key", Value::String("hola".to_string()));
     /// ```
     ///
-    pub fn getset(&self, key: &str, new_value: Value) -> Result<String, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn getset(&self, key: &str, new_value: Value) -> Result<String, RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(key, &mut lock);
         match res_entry {
             Ok(opt_entry) => match opt_entry {
@@ -678,12 +721,9 @@ impl DataStorage {
                             drop(lock);
                             Ok(old_value)
                         }
-                        Value::Vec(_) => {
-                            Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                        }
-                        Value::HashSet(_) => {
-                            Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                        }
+                        Value::Vec(_) => Err(RedisError::WrongType),
+                        Value::HashSet(_) => Err(RedisError::WrongType),
+                        Value::Geo(_) => Err(RedisError::WrongType),
                     },
                     Err(_) => Ok("nil".to_string()),
                 },
@@ -712,8 +752,8 @@ impl DataStorage {
     /// data.rename(&"key", &"new_key");
     /// ```
     ///
-    pub fn rename(&self, src: &str, dst: &str) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn rename(&self, src: &str, dst: &str) -> Result<(), RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(src, &mut lock);
         match res_entry {
             Ok(opt_entry) => match opt_entry {
@@ -727,11 +767,11 @@ impl DataStorage {
                         self.delete_key(src)?;
                         Ok(())
                     }
-                    Err(_) => Err("No such key"),
+                    Err(_) => Err(RedisError::NoSuchKey),
                 },
-                None => Err("No such key"),
+                None => Err(RedisError::NoSuchKey),
             },
-            Err(_) => Err("No such key"),
+            Err(_) => Err(RedisError::NoSuchKey),
         }
     }
 
@@ -760,7 +800,7 @@ impl DataStorage {
         key: &str,
         value: Value,
         expiration_time_since_unix_epoch: Duration,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), RedisError> {
         self.set(key, value)?;
         self.set_expiration_to_key(Some(expiration_time_since_unix_epoch), key)?;
         Ok(())
@@ -792,8 +832,8 @@ impl DataStorage {
         &self,
         expiration_time_since_unix_epoch: Option<Duration>,
         key: &str,
-    ) -> Result<u64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    ) -> Result<u64, RedisError> {
+        let mut lock = self.write();
         let copy_key = key.to_string();
 
         if lock.contains_key(&copy_key) {
@@ -802,7 +842,7 @@ impl DataStorage {
                 .set_key_expiration(expiration_time_since_unix_epoch);
             Ok(1)
         } else {
-            Err("Key not found in DataStorage")
+            Err(RedisError::NoSuchKey)
         }
     }
 
@@ -870,18 +910,18 @@ impl DataStorage {
         &self,
         key: &str,
         last_access_since_unix_epoch: Duration,
-    ) -> Result<Duration, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    ) -> Result<Duration, RedisError> {
+        let mut lock = self.write();
         self.do_modify_last_key_access(&mut lock, key, last_access_since_unix_epoch)
     }
 
     ///Do modify last key access if the key exist or is not expired.
-    fn do_modify_last_key_access<'i>(
+    fn do_modify_last_key_access(
         &self,
-        lock: &'i mut RwLockWriteGuard<HashMap<String, Entry>>,
+        lock: &mut RwLockWriteGuard<HashMap<String, Entry>>,
         key: &str,
         last_access_since_unix_epoch: Duration,
-    ) -> Result<Duration, &'static str> {
+    ) -> Result<Duration, RedisError> {
         let copy_key = key.to_string();
 
         if lock.contains_key(&copy_key) {
@@ -895,16 +935,20 @@ impl DataStorage {
                     Ok(_s) => return Ok(l_a),
                     Err(_s) => {
                         self.delete_key(key)?;
-                        return Err("last access not modify not existing key");
+                        return Err(RedisError::Generic(
+                            "last access not modify not existing key",
+                        ));
                     }
                 },
                 Err(_) => {
                     self.delete_key(key)?;
-                    return Err("last access not modify not existing key");
+                    return Err(RedisError::Generic(
+                        "last access not modify not existing key",
+                    ));
                 }
             }
         }
-        Err("last access not modify")
+        Err(RedisError::Generic("last access not modify"))
     }
 
     /// Increments the number stored at key by increment.
@@ -926,8 +970,8 @@ impl DataStorage {
     /// data.increment_value("key".to_string(), 5);
     /// ```
     ///
-    pub fn increment_value(&self, key: String, numeric_value: i64) -> Result<i64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn increment_value(&self, key: String, numeric_value: i64) -> Result<i64, RedisError> {
+        let mut lock = self.write();
         let value = lock.get(&key);
 
         match value {
@@ -939,14 +983,11 @@ impl DataStorage {
                         entry.update_value(Value::String(new_value.to_string()))?;
                         Ok(number + numeric_value)
                     }
-                    Err(_) => Err("ERR value is not an integer or out of range"),
+                    Err(_) => Err(RedisError::NotInteger),
                 },
-                Value::Vec(_) => {
-                    Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                }
-                Value::HashSet(_) => {
-                    Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                }
+                Value::Vec(_) => Err(RedisError::WrongType),
+                Value::HashSet(_) => Err(RedisError::WrongType),
+                Value::Geo(_) => Err(RedisError::WrongType),
             },
             None => {
                 self.do_set(&mut lock, &key, Value::String(numeric_value.to_string()))?;
@@ -974,8 +1015,8 @@ impl DataStorage {
     /// data.decrement_value("key".to_string(), 5);
     /// ```
     ///
-    pub fn decrement_value(&self, key: String, numeric_value: i64) -> Result<i64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn decrement_value(&self, key: String, numeric_value: i64) -> Result<i64, RedisError> {
+        let mut lock = self.write();
         let value = lock.get(&key);
         match value {
             Some(val) => match val.value()? {
@@ -986,10 +1027,9 @@ impl DataStorage {
                         entry.update_value(Value::String(new_value.to_string()))?;
                         Ok(number - numeric_value)
                     }
-                    Err(_j) => Err("Cant decrement a value to a not integer value"),
+                    Err(_j) => Err(RedisError::NotInteger),
                 },
-                Value::Vec(_i) => Err("Cant decrement a value to a vector"),
-                Value::HashSet(_j) => Err("Cant decrement a value to a set"),
+                _ => Err(RedisError::WrongType),
             },
             None => {
                 let negative_value = 0 - numeric_value;
@@ -1013,7 +1053,7 @@ impl DataStorage {
     /// data.lpushx("key".to_string(), ["hola".to_string()].to_vec());
     /// ```
     ///
-    pub fn lpushx(&self, key: String, vec_values: Vec<String>) -> Result<usize, &'static str> {
+    pub fn lpushx(&self, key: String, vec_values: Vec<String>) -> Result<usize, RedisError> {
         self.pushx(key, vec_values, |list, element| list.insert(0, element))
     }
 
@@ -1031,7 +1071,7 @@ impl DataStorage {
     /// data.lpush("key".to_string(), ["hola".to_string()].to_vec());
     /// ```
     ///
-    pub fn lpush(&self, key: String, vec_values: Vec<String>) -> Result<usize, &'static str> {
+    pub fn lpush(&self, key: String, vec_values: Vec<String>) -> Result<usize, RedisError> {
         self.push(key, vec_values, |list, element| list.insert(0, element))
     }
 
@@ -1049,7 +1089,7 @@ impl DataStorage {
     /// data.rpushx("key".to_string(), ["hola".to_string()].to_vec());
     /// ```
     ///
-    pub fn rpushx(&self, key: String, vec_values: Vec<String>) -> Result<usize, &'static str> {
+    pub fn rpushx(&self, key: String, vec_values: Vec<String>) -> Result<usize, RedisError> {
         self.pushx(key, vec_values, |list, element| list.push(element))
     }
 
@@ -1067,7 +1107,7 @@ impl DataStorage {
     /// data.rpush("key".to_string(), ["hola".to_string()].to_vec());
     /// ```
     ///
-    pub fn rpush(&self, key: String, vec_values: Vec<String>) -> Result<usize, &'static str> {
+    pub fn rpush(&self, key: String, vec_values: Vec<String>) -> Result<usize, RedisError> {
         self.push(key, vec_values, |list, element| list.push(element))
     }
 
@@ -1085,8 +1125,8 @@ impl DataStorage {
     /// data.rpop("key".to_string(), 2);
     /// ```
     ///
-    pub fn rpop(&self, key: String, count: usize) -> Result<Vec<String>, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn rpop(&self, key: String, count: usize) -> Result<Vec<String>, RedisError> {
+        let mut lock = self.write();
         let mut result = Vec::new();
         let _ = self.do_apply_vec(key, &mut lock, |list| {
             for _ in 0..count {
@@ -1105,11 +1145,11 @@ impl DataStorage {
         key: String,
         vec_values: Vec<String>,
         apply: fn(&mut Vec<String>, String) -> (),
-    ) -> Result<usize, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    ) -> Result<usize, RedisError> {
+        let mut lock = self.write();
         match self.do_pushx(key.clone(), vec_values.clone(), &mut lock, apply) {
             Ok(l) => {
-                return if l == 0 {
+                if l == 0 {
                     self.do_set(&mut lock, &key, Value::Vec(Vec::new()))?;
                     self.do_pushx(key, vec_values, &mut lock, apply)
                 } else {
@@ -1126,8 +1166,8 @@ impl DataStorage {
         key: String,
         vec_values: Vec<String>,
         apply: fn(&mut Vec<String>, String) -> (),
-    ) -> Result<usize, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    ) -> Result<usize, RedisError> {
+        let mut lock = self.write();
         self.do_pushx(key, vec_values, &mut lock, apply)
     }
 
@@ -1138,7 +1178,7 @@ impl DataStorage {
         vec_values: Vec<String>,
         lock: &mut RwLockWriteGuard<HashMap<String, Entry>>,
         apply: fn(&mut Vec<String>, String) -> (),
-    ) -> Result<usize, &'static str> {
+    ) -> Result<usize, RedisError> {
         self.do_apply_vec(key, lock, |vec| {
             for val in &vec_values {
                 apply(vec, val.clone());
@@ -1163,14 +1203,14 @@ impl DataStorage {
     /// data.set(&"key", Value::Vec(["hola".to_string()].to_vec()));
     /// data.lset("key".to_string(), 0, "mundo".to_string());
     ///
-    pub fn lset(&self, key: String, index: i64, value: String) -> Result<(), &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn lset(&self, key: String, index: i64, value: String) -> Result<(), RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(&key, &mut lock);
 
         match res_entry {
             Ok(opt_entry) => match opt_entry {
                 Some(entry) => match entry.value().unwrap() {
-                    Value::String(_) => Err("Not list value for that key"),
+                    Value::String(_) => Err(RedisError::WrongType),
                     Value::Vec(mut i) => {
                         let index = if index < 0 {
                             (i.len() as i64) + index
@@ -1180,7 +1220,7 @@ impl DataStorage {
                         let res = if (index as usize) < i.len() {
                             Ok(index as usize)
                         } else {
-                            Err("Index not correct in lset")
+                            Err(RedisError::Generic("index out of range"))
                         };
                         match res {
                             Ok(number) => {
@@ -1191,11 +1231,12 @@ impl DataStorage {
                             Err(s) => Err(s),
                         }
                     }
-                    Value::HashSet(_) => Err("Not list value for that key"),
+                    Value::HashSet(_) => Err(RedisError::WrongType),
+                    Value::Geo(_) => Err(RedisError::WrongType),
                 },
-                None => Err("No such key"),
+                None => Err(RedisError::NoSuchKey),
             },
-            Err(_) => Err("No such key"),
+            Err(_) => Err(RedisError::NoSuchKey),
         }
     }
 
@@ -1220,14 +1261,14 @@ impl DataStorage {
     /// data.set(&"key", Value::Vec(["hola".to_string(), "hola".to_string()].to_vec()));
     /// data.lrem("key".to_string(), 0, "hola".to_string());
     ///
-    pub fn lrem(&self, key: String, index: i64, value: String) -> Result<i64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn lrem(&self, key: String, index: i64, value: String) -> Result<i64, RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(&key, &mut lock);
 
         match res_entry {
             Ok(opt_entry) => match opt_entry {
                 Some(entry) => match entry.value().unwrap() {
-                    Value::String(_) => Err("Not list value for that key"),
+                    Value::String(_) => Err(RedisError::WrongType),
                     Value::Vec(mut vector) => {
                         let result: i64;
                         match index {
@@ -1266,7 +1307,8 @@ impl DataStorage {
                             }
                         }
                     }
-                    Value::HashSet(_) => Err("Not list value for that key"),
+                    Value::HashSet(_) => Err(RedisError::WrongType),
+                    Value::Geo(_) => Err(RedisError::WrongType),
                 },
                 None => Ok(0),
             },
@@ -1293,12 +1335,13 @@ impl DataStorage {
     /// data.set(&"key", Value::HashSet(set));
     /// data.sismember("key".to_string(), "hola".to_string());
     ///
-    pub fn sismember(&self, key: String, input_val: String) -> Result<i64, &'static str> {
+    pub fn sismember(&self, key: String, input_val: String) -> Result<i64, RedisError> {
         let value = self.get(&key);
         match value {
             Some(val) => match val {
-                Value::String(_) => Err("Not set value to that key"),
-                Value::Vec(_) => Err("Not set value to that key"),
+                Value::String(_) => Err(RedisError::WrongType),
+                Value::Vec(_) => Err(RedisError::WrongType),
+                Value::Geo(_) => Err(RedisError::WrongType),
                 Value::HashSet(set) => {
                     if set.contains(&input_val) {
                         Ok(1)
@@ -1333,15 +1376,16 @@ impl DataStorage {
     /// data.set(&"key", Value::HashSet(set));
     /// data.srem("key".to_string(), vec!["hola".to_string()]);
     ///
-    pub fn srem(&self, key: String, values: Vec<String>) -> Result<i64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn srem(&self, key: String, values: Vec<String>) -> Result<i64, RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(&key, &mut lock);
 
         match res_entry {
             Ok(opt_entry) => match opt_entry {
                 Some(entry) => match entry.value().unwrap() {
-                    Value::String(_) => Err("Not list value for that key"),
-                    Value::Vec(_) => Err("Not list value for that key"),
+                    Value::String(_) => Err(RedisError::WrongType),
+                    Value::Vec(_) => Err(RedisError::WrongType),
+                    Value::Geo(_) => Err(RedisError::WrongType),
                     Value::HashSet(mut set) => {
                         let mut count = 0;
                         for value in values {
@@ -1380,19 +1424,16 @@ impl DataStorage {
     /// data.set(&"key", Value::HashSet(set));
     /// data.sadd("key".to_string(), vec!["mundo".to_string()]);
     ///
-    pub fn sadd(&self, key: String, values: Vec<String>) -> Result<i64, &'static str> {
-        let mut lock = self.data.write().ok().ok_or("Failed to lock database")?;
+    pub fn sadd(&self, key: String, values: Vec<String>) -> Result<i64, RedisError> {
+        let mut lock = self.write();
         let res_entry = self.get_entry(&key, &mut lock);
 
         match res_entry {
             Ok(opt_entry) => match opt_entry {
                 Some(entry) => match entry.value().unwrap() {
-                    Value::String(_) => {
-                        Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                    }
-                    Value::Vec(_) => {
-                        Err("WRONGTYPE Operation against a key holding the wrong kind of value")
-                    }
+                    Value::String(_) => Err(RedisError::WrongType),
+                    Value::Vec(_) => Err(RedisError::WrongType),
+                    Value::Geo(_) => Err(RedisError::WrongType),
                     Value::HashSet(mut set) => {
                         let mut count = 0;
                         for value in values {
@@ -1439,12 +1480,13 @@ impl DataStorage {
     /// data.set(&"key", Value::HashSet(set));
     /// let members = data.smember("key".to_string());
     ///
-    pub fn smember(&self, key: String) -> Result<Vec<String>, &'static str> {
+    pub fn smember(&self, key: String) -> Result<Vec<String>, RedisError> {
         let value = self.get(&key);
         match value {
             Some(val) => match val {
-                Value::String(_) => Err("Not set value to that key"),
-                Value::Vec(_) => Err("Not set value to that key"),
+                Value::String(_) => Err(RedisError::WrongType),
+                Value::Vec(_) => Err(RedisError::WrongType),
+                Value::Geo(_) => Err(RedisError::WrongType),
                 Value::HashSet(set) => {
                     let vec = set.into_iter().collect();
                     Ok(vec)
@@ -1459,12 +1501,12 @@ impl DataStorage {
         key: String,
         first_index: i64,
         second_index: i64,
-    ) -> Result<Option<Vec<String>>, &'static str> {
+    ) -> Result<Option<Vec<String>>, RedisError> {
         let value = self.get(&key);
 
         match value {
             Some(val) => match val {
-                Value::String(_) => Err("Not list value to that key"),
+                Value::String(_) => Err(RedisError::WrongType),
                 Value::Vec(vector) => {
                     let mut result: Option<Vec<String>> = None;
                     if first_index >= 0 && second_index >= 0 {
@@ -1484,10 +1526,328 @@ impl DataStorage {
                     };
                     Ok(result)
                 }
-                Value::HashSet(_) => Err("Not list value to that key"),
+                Value::HashSet(_) => Err(RedisError::WrongType),
+                Value::Geo(_) => Err(RedisError::WrongType),
             },
-            None => Err("Not value to that key"),
+            None => Err(RedisError::NoSuchKey),
+        }
+    }
+
+    /// Adds the given members with their geohash scores to the geo index stored at key.
+    /// If key does not exist, a new geo index is created before adding the members.
+    /// Returns a tuple with the number of members added and the number of members
+    /// whose position changed. An error is returned when the value stored at key is not a geo index.
+    /// # Arguments
+    ///
+    /// * `key` - A string that holds the name of the key to get.
+    /// * `members` - A Vector of (member, geohash score) tuples to add.
+    /// * `nx` - If true, members that already exist are not updated.
+    /// * `xx` - If true, only members that already exist are updated.
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// data.geoadd("key".to_string(), vec![("Palermo".to_string(), 3479099956230698)], false, false);
+    /// ```
+    ///
+    pub fn geoadd(
+        &self,
+        key: String,
+        members: Vec<(String, u64)>,
+        nx: bool,
+        xx: bool,
+    ) -> Result<(i64, i64), RedisError> {
+        let mut lock = self.write();
+        let mut index = match self.get_entry(&key, &mut lock) {
+            Ok(Some(entry)) => match entry.value()? {
+                Value::Geo(index) => index,
+                _ => return Err(RedisError::WrongType),
+            },
+            _ => HashMap::new(),
+        };
+
+        let mut added = 0;
+        let mut changed = 0;
+        for (member, score) in members {
+            match index.get(&member) {
+                Some(old_score) => {
+                    if !nx && *old_score != score {
+                        index.insert(member, score);
+                        changed += 1;
+                    }
+                }
+                None => {
+                    if !xx {
+                        index.insert(member, score);
+                        added += 1;
+                    }
+                }
+            }
+        }
+
+        match self.get_entry(&key, &mut lock) {
+            Ok(Some(entry)) => entry.update_value(Value::Geo(index))?,
+            _ => {
+                if !index.is_empty() {
+                    self.do_set(&mut lock, &key, Value::Geo(index))?;
+                }
+            }
+        }
+        Ok((added, changed))
+    }
+
+    /// Returns the members of the geo index stored at key along with their geohash scores.
+    /// If key does not exist, an empty index is returned.
+    /// An error is returned when the value stored at key is not a geo index.
+    /// # Arguments
+    ///
+    /// * `key` - A string that holds the name of the key to get.
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let members = data.geo_members("key");
+    /// ```
+    ///
+    pub fn geo_members(&self, key: &str) -> Result<HashMap<String, u64>, RedisError> {
+        match self.get(key) {
+            Some(Value::Geo(index)) => Ok(index),
+            Some(_) => Err(RedisError::WrongType),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// Returns a copy of the entry stored at key without modifying its last access.
+    /// Returns None if the key does not exist or has expired.
+    /// # Arguments
+    ///
+    /// * `key` - A string that holds the name of the key to inspect.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let entry = data.peek_entry("key");
+    /// ```
+    ///
+    pub fn peek_entry(&self, key: &str) -> Option<Entry> {
+        let lock = self.read();
+        let entry = lock.get(key)?;
+        match entry.value() {
+            Ok(_) => Some(entry.clone()),
+            Err(_) => None,
+        }
+    }
+
+    /// Returns a random key from the ones that have not expired, or None if the database is empty.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let key = data.random_key();
+    /// ```
+    ///
+    pub fn random_key(&self) -> Option<String> {
+        let lock = self.read();
+        let keys: Vec<&String> = lock
+            .iter()
+            .filter(|(_, entry)| entry.value().is_ok())
+            .map(|(key, _)| key)
+            .collect();
+        if keys.is_empty() {
+            return None;
         }
+        let random = RandomState::new().build_hasher().finish() as usize;
+        Some(keys[random % keys.len()].clone())
+    }
+
+    /// Removes the given keys from the database and returns the values of the ones that
+    /// existed, so the caller can decide where to free them.
+    /// # Arguments
+    ///
+    /// * `keys` - A slice of strings that holds the names of the keys to remove.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let removed = data.unlink(&["key".to_string()]);
+    /// ```
+    ///
+    pub fn unlink(&self, keys: &[String]) -> Result<Vec<Value>, RedisError> {
+        let mut lock = self.write();
+        let mut removed = Vec::new();
+        for key in keys {
+            if let Some(entry) = lock.remove(key) {
+                if let Ok(value) = entry.value() {
+                    removed.push(value);
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Renames src to dst only if dst does not exist yet.
+    /// Returns true if the key was renamed and false if dst already existed.
+    /// An error is returned when src does not exist.
+    /// # Arguments
+    ///
+    /// * `src` - A string that holds the name of the key to rename.
+    /// * `dst` - A string that holds the new name of the key.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// data.renamenx(&"key", &"new_key");
+    /// ```
+    ///
+    pub fn renamenx(&self, src: &str, dst: &str) -> Result<bool, RedisError> {
+        let mut lock = self.write();
+        match self.get_entry(src, &mut lock) {
+            Ok(Some(_)) => {}
+            _ => return Err(RedisError::NoSuchKey),
+        }
+        if let Ok(Some(_)) = self.get_entry(dst, &mut lock) {
+            return Ok(false);
+        }
+        let entry = lock.remove(src).ok_or(RedisError::NoSuchKey)?;
+        lock.insert(dst.to_string(), entry);
+        Ok(true)
+    }
+
+    /// Stores a value at key with an optional expiration, as done by RESTORE.
+    /// If the key already exists and replace is false an error is returned.
+    /// # Arguments
+    ///
+    /// * `key` - A string that holds the name of the key to store.
+    /// * `value` - A Value that holds the value to store.
+    /// * `expiration` - An Option<Duration> that holds the expiration time since unix epoch.
+    /// * `replace` - A bool that indicates if an existing key can be overwritten.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// use redis_server::storage::data_storage::Value;
+    /// let data = DataStorage::new();
+    /// data.restore("key", Value::String("hola".to_string()), None, false);
+    /// ```
+    ///
+    pub fn restore(
+        &self,
+        key: &str,
+        value: Value,
+        expiration: Option<Duration>,
+        replace: bool,
+    ) -> Result<(), RedisError> {
+        let mut lock = self.write();
+        if !replace {
+            if let Ok(Some(_)) = self.get_entry(key, &mut lock) {
+                return Err(RedisError::BusyKey);
+            }
+        }
+        lock.insert(key.to_string(), Entry::new(now()?, expiration, value));
+        Ok(())
+    }
+
+    /// Returns the estimated amount of bytes used by key and its value, or None if the key
+    /// does not exist. Collections are estimated from `samples` elements, 0 samples all of them.
+    /// # Arguments
+    ///
+    /// * `key` - A string that holds the name of the key to measure.
+    /// * `samples` - A usize that holds how many elements of a collection to measure.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let usage = data.memory_usage("key", 5);
+    /// ```
+    ///
+    pub fn memory_usage(&self, key: &str, samples: usize) -> Option<usize> {
+        let entry = self.peek_entry(key)?;
+        Some(memory::entry_usage(key, &entry, samples))
+    }
+
+    /// Returns the estimated memory used by the whole database.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// let used_memory = data.memory_stats().unwrap().used_memory();
+    /// ```
+    ///
+    pub fn memory_stats(&self) -> Result<MemoryStats, RedisError> {
+        let lock = self.read();
+        Ok(memory::stats(&lock))
+    }
+
+    /// Returns how many reads found the key they were looking for.
+    pub fn keyspace_hits(&self) -> u64 {
+        self.keyspace_hits.load(Ordering::SeqCst)
+    }
+
+    /// Returns how many reads did not find the key they were looking for.
+    pub fn keyspace_misses(&self) -> u64 {
+        self.keyspace_misses.load(Ordering::SeqCst)
+    }
+
+    /// Returns how many keys were deleted because they expired.
+    pub fn expired_keys(&self) -> u64 {
+        self.expired_keys.load(Ordering::SeqCst)
+    }
+
+    /// Sets the keyspace hits, misses and expired keys counters back to zero.
+    pub fn reset_stats(&self) {
+        self.keyspace_hits.store(0, Ordering::SeqCst);
+        self.keyspace_misses.store(0, Ordering::SeqCst);
+        self.expired_keys.store(0, Ordering::SeqCst);
+    }
+
+    /// Returns the number of keys that have an expiration set and have not expired yet.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::data_storage::DataStorage;
+    /// let data = DataStorage::new();
+    /// assert_eq!(data.expires_count(), 0);
+    /// ```
+    ///
+    pub fn expires_count(&self) -> usize {
+        let lock = self.read();
+        lock.values()
+            .filter(|entry| matches!(entry.key_expiration(), Ok(Some(_))))
+            .count()
     }
 }
 
@@ -1561,11 +1921,7 @@ fn get_vector_positive_index(
     Some(result)
 }
 
-fn delete_last_values(
-    vector: &mut Vec<String>,
-    mut index: i64,
-    value: String,
-) -> (i64, Vec<String>) {
+fn delete_last_values(vector: &mut [String], mut index: i64, value: String) -> (i64, Vec<String>) {
     let mut new_vector: Vec<String> = vec![];
     for val in vector.iter().rev() {
         if (*val == value) && (index != 0) {
@@ -1577,11 +1933,7 @@ fn delete_last_values(
     (index, new_vector.into_iter().rev().collect())
 }
 
-fn delete_first_values(
-    vector: &mut Vec<String>,
-    mut index: i64,
-    value: String,
-) -> (i64, Vec<String>) {
+fn delete_first_values(vector: &mut [String], mut index: i64, value: String) -> (i64, Vec<String>) {
     let mut new_vector: Vec<String> = vec![];
     for val in vector.iter() {
         if *val == value && (index != 0) {
@@ -1593,7 +1945,7 @@ fn delete_first_values(
     (index, new_vector)
 }
 
-fn delete_all_values(vector: &mut Vec<String>, value: String) -> (i64, Vec<String>) {
+fn delete_all_values(vector: &mut [String], value: String) -> (i64, Vec<String>) {
     let mut index = 0;
     let mut new_vector: Vec<String> = vec![];
     for val in vector.iter() {
@@ -1606,17 +1958,16 @@ fn delete_all_values(vector: &mut Vec<String>, value: String) -> (i64, Vec<Strin
     (index, new_vector)
 }
 
-fn now() -> Result<Duration, &'static str> {
+fn now() -> Result<Duration, RedisError> {
     let _now = SystemTime::now().duration_since(UNIX_EPOCH);
 
     match _now {
         Ok(now) => Ok(now),
-        Err(_) => Err("Cannot get actual timestamp"),
+        Err(_) => Err(RedisError::Generic("Cannot get actual timestamp")),
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::env;
@@ -1642,6 +1993,49 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_recovers_from_a_panic_holding_the_lock() {
+        let data_storage = DataStorage::new();
+        data_storage
+            .set("key", Value::String("value".to_string()))
+            .unwrap();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _lock = data_storage.write();
+            panic!("comando fallido");
+        }));
+        assert!(result.is_err());
+        assert!(data_storage.data.is_poisoned());
+
+        assert_eq!(data_storage.get("key").unwrap().string().unwrap(), "value");
+        data_storage
+            .set("other", Value::String("1".to_string()))
+            .unwrap();
+        assert_eq!(data_storage.len().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_get_updates_last_access() {
+        let data_storage = DataStorage::new();
+        data_storage
+            .set("key", Value::String("value".to_string()))
+            .unwrap();
+        let accesses = data_storage.read().get("key").unwrap().access_count();
+
+        assert!(data_storage.get("key").is_some());
+        assert!(data_storage.get("key").is_some());
+        assert_eq!(
+            data_storage.read().get("key").unwrap().access_count(),
+            accesses + 2
+        );
+
+        data_storage
+            .set_expiration_to_key(Some(Duration::from_secs(1)), "key")
+            .unwrap();
+        assert!(data_storage.get("key").is_none());
+        assert!(data_storage.read().get("key").is_none());
+    }
+
     #[test]
     fn test_set_expiration_to_key() {
         let data_storage = DataStorage::new();
@@ -1670,8 +2064,8 @@ mod tests {
     #[test]
     fn test_load_string_data() {
         let dir = env::temp_dir();
-        let path_str = dir.to_str().unwrap().to_string() + &"/string_data.txt".to_string();
-        let path = dir.to_str().unwrap().to_string() + &"/string_data.txt".to_string();
+        let path_str = dir.to_str().unwrap().to_string() + "/string_data.txt";
+        let path = dir.to_str().unwrap().to_string() + "/string_data.txt";
 
         let mut file = File::create(path).expect("Not file created");
 
@@ -1696,8 +2090,8 @@ mod tests {
     #[test]
     fn test_load_vector_data() {
         let dir = env::temp_dir();
-        let path_str = dir.to_str().unwrap().to_string() + &"/vector_data.txt".to_string();
-        let path = dir.to_str().unwrap().to_string() + &"/vector_data.txt".to_string();
+        let path_str = dir.to_str().unwrap().to_string() + "/vector_data.txt";
+        let path = dir.to_str().unwrap().to_string() + "/vector_data.txt";
 
         let mut file = File::create(path).expect("Not file created");
 
@@ -1722,8 +2116,8 @@ mod tests {
     #[test]
     fn test_load_set_data() {
         let dir = env::temp_dir();
-        let path_str = dir.to_str().unwrap().to_string() + &"/set_data.txt".to_string();
-        let path = dir.to_str().unwrap().to_string() + &"/set_data.txt".to_string();
+        let path_str = dir.to_str().unwrap().to_string() + "/set_data.txt";
+        let path = dir.to_str().unwrap().to_string() + "/set_data.txt";
 
         let mut file = File::create(path).expect("Not file created");
 
@@ -0,0 +1,161 @@
+use crate::error::RedisError;
+use crate::storage::data_storage::Value;
+use std::collections::{HashMap, HashSet};
+
+static DUMP_VERSION: &str = "1";
+static STRING: char = 's';
+static LIST: char = 'l';
+static SET: char = 'S';
+static GEO: char = 'g';
+const PAYLOAD_ERROR: RedisError = RedisError::Generic("DUMP payload version or checksum are wrong");
+
+/// Serializes a value into the payload returned by DUMP.
+/// The payload is made of a type tag followed by every element prefixed with its length
+/// (`len:element`), the version of the format and a checksum of the previous content.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```
+/// use redis_server::storage::data_storage::Value;
+/// use redis_server::storage::dump;
+/// let payload = dump::serialize(&Value::String("hola".to_string()));
+/// ```
+///
+pub fn serialize(value: &Value) -> String {
+    let mut body = String::new();
+    match value {
+        Value::String(string) => {
+            body.push(STRING);
+            push_element(&mut body, string);
+        }
+        Value::Vec(vec) => {
+            body.push(LIST);
+            vec.iter().for_each(|x| push_element(&mut body, x));
+        }
+        Value::HashSet(set) => {
+            body.push(SET);
+            set.iter().for_each(|x| push_element(&mut body, x));
+        }
+        Value::Geo(index) => {
+            body.push(GEO);
+            for (member, score) in index.iter() {
+                push_element(&mut body, member);
+                push_element(&mut body, &score.to_string());
+            }
+        }
+    }
+    format!(
+        "{}|{}|{:016x}",
+        body,
+        DUMP_VERSION,
+        checksum(&body, DUMP_VERSION)
+    )
+}
+
+/// Deserializes a payload created by serialize back into a value.
+/// An error is returned if the payload is malformed or its checksum does not match.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```
+/// use redis_server::storage::data_storage::Value;
+/// use redis_server::storage::dump;
+/// let payload = dump::serialize(&Value::String("hola".to_string()));
+/// let value = dump::deserialize(&payload).unwrap();
+/// ```
+///
+pub fn deserialize(payload: &str) -> Result<Value, RedisError> {
+    let mut parts = payload.rsplitn(3, '|');
+    let sum = parts.next().ok_or(PAYLOAD_ERROR)?;
+    let version = parts.next().ok_or(PAYLOAD_ERROR)?;
+    let body = parts.next().ok_or(PAYLOAD_ERROR)?;
+    if version != DUMP_VERSION || sum != format!("{:016x}", checksum(body, version)) {
+        return Err(PAYLOAD_ERROR);
+    }
+
+    let mut chars = body.chars();
+    let tag = chars.next().ok_or(PAYLOAD_ERROR)?;
+    let elements = read_elements(chars.as_str())?;
+    if tag == STRING && elements.len() == 1 {
+        Ok(Value::String(elements[0].clone()))
+    } else if tag == LIST {
+        Ok(Value::Vec(elements))
+    } else if tag == SET {
+        Ok(Value::HashSet(elements.into_iter().collect::<HashSet<_>>()))
+    } else if tag == GEO && elements.len() % 2 == 0 {
+        let mut index = HashMap::new();
+        for pair in elements.chunks(2) {
+            let score = pair[1].parse::<u64>().ok().ok_or(PAYLOAD_ERROR)?;
+            index.insert(pair[0].clone(), score);
+        }
+        Ok(Value::Geo(index))
+    } else {
+        Err(PAYLOAD_ERROR)
+    }
+}
+
+fn push_element(body: &mut String, element: &str) {
+    body.push_str(&element.len().to_string());
+    body.push(':');
+    body.push_str(element);
+}
+
+fn read_elements(mut body: &str) -> Result<Vec<String>, RedisError> {
+    let mut elements = Vec::new();
+    while !body.is_empty() {
+        let (len, rest) = body.split_once(':').ok_or(PAYLOAD_ERROR)?;
+        let len = len.parse::<usize>().ok().ok_or(PAYLOAD_ERROR)?;
+        let element = rest.get(..len).ok_or(PAYLOAD_ERROR)?;
+        elements.push(element.to_string());
+        body = &rest[len..];
+    }
+    Ok(elements)
+}
+
+/// FNV-1a hash of the body and version, used to detect corrupted payloads.
+fn checksum(body: &str, version: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in body.bytes().chain(version.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_round_trip() {
+        let payload = serialize(&Value::String("hola|mundo:1".to_string()));
+        let value = deserialize(&payload).unwrap();
+        assert_eq!(value.string().unwrap(), "hola|mundo:1");
+    }
+
+    #[test]
+    fn test_list_round_trip() {
+        let list = vec!["a".to_string(), "".to_string(), "b,c;d".to_string()];
+        let payload = serialize(&Value::Vec(list.clone()));
+        assert_eq!(deserialize(&payload).unwrap().array().unwrap(), list);
+    }
+
+    #[test]
+    fn test_geo_round_trip() {
+        let mut index = HashMap::new();
+        index.insert("Palermo".to_string(), 3479099956230698);
+        let payload = serialize(&Value::Geo(index.clone()));
+        assert_eq!(deserialize(&payload).unwrap().geo().unwrap(), index);
+    }
+
+    #[test]
+    fn test_corrupted_payload_fails() {
+        let payload = serialize(&Value::String("hola".to_string())).replace("hola", "chau");
+        assert_eq!(deserialize(&payload).err(), Some(PAYLOAD_ERROR));
+        assert_eq!(deserialize("garbage").err(), Some(PAYLOAD_ERROR));
+    }
+}
@@ -9,12 +9,14 @@ use std::time::UNIX_EPOCH;
 /// * `last_access` - A Duration slice that holds the last access of the key.
 /// * `key_expiration` - A Option<Duration> slice that holds the key expiration or None in case it has not been set.
 /// * `value` - A Value that contains the member to store in the entry.
+/// * `access_count` - A u64 that holds how many times the key was accessed.
 ///
 #[derive(Clone)]
 pub struct Entry {
     last_access: Duration,
     key_expiration: Option<Duration>,
     value: Value,
+    access_count: u64,
 }
 
 impl Entry {
@@ -45,6 +47,7 @@ impl Entry {
             last_access,
             key_expiration,
             value,
+            access_count: 0,
         }
     }
 
@@ -201,10 +204,30 @@ impl Entry {
             Err("Key expired")
         } else {
             self.last_access = new_access;
+            self.access_count = self.access_count.saturating_add(1);
             Ok(())
         }
     }
 
+    ///Returns how many times the last access of the key was modified.
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use redis_server::storage::entry::Entry;
+    /// use redis_server::storage::data_storage::Value;
+    /// use std::time::SystemTime;
+    /// use std::time::UNIX_EPOCH;
+    /// let value = Value::String("hola".to_string());
+    /// let entry = Entry::new(SystemTime::now().duration_since(UNIX_EPOCH).unwrap(), None, value);
+    /// assert_eq!(entry.access_count(), 0);
+    /// ```
+    ///
+    pub fn access_count(&self) -> u64 {
+        self.access_count
+    }
+
     ///Modify the expiration of the key
     /// # Arguments
     ///
@@ -33,6 +33,7 @@ pub fn data_to_file(file: &str, data: String) {
     let mut file = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(false)
         .open(file)
         .expect("Unable to open");
 
@@ -0,0 +1,158 @@
+use crate::storage::data_storage::Value;
+use crate::storage::entry::Entry;
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+/// Number of elements sampled by default when estimating the size of a collection.
+pub const DEFAULT_SAMPLES: usize = 5;
+
+/// Summary of the memory used by the database, as reported by MEMORY STATS.
+///
+/// * `keys` - Number of keys stored.
+/// * `dataset_bytes` - Bytes used by the keys and their values.
+/// * `overhead_bytes` - Bytes used by the structure that indexes the keys.
+pub struct MemoryStats {
+    pub keys: usize,
+    pub dataset_bytes: usize,
+    pub overhead_bytes: usize,
+}
+
+impl MemoryStats {
+    /// Returns the total amount of bytes used by the database.
+    pub fn used_memory(&self) -> usize {
+        self.dataset_bytes + self.overhead_bytes
+    }
+}
+
+/// Estimates the bytes used by a value, including the space the enum takes inline.
+/// Collections bigger than `samples` elements are estimated by averaging the size of
+/// the first `samples` elements. A `samples` of 0 measures every element.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```
+/// use redis_server::storage::data_storage::Value;
+/// use redis_server::storage::memory::value_usage;
+/// let usage = value_usage(&Value::String("hola".to_string()), 5);
+/// ```
+///
+pub fn value_usage(value: &Value, samples: usize) -> usize {
+    size_of::<Value>()
+        + match value {
+            Value::String(string) => string.capacity(),
+            Value::Vec(vec) => {
+                vec.capacity() * size_of::<String>() + sampled(vec.iter(), vec.len(), samples)
+            }
+            Value::HashSet(set) => {
+                table_usage::<String>(set.capacity()) + sampled(set.iter(), set.len(), samples)
+            }
+            Value::Geo(index) => {
+                table_usage::<(String, u64)>(index.capacity())
+                    + sampled(index.keys(), index.len(), samples)
+            }
+        }
+}
+
+/// Estimates the bytes used by an entry stored at key, including the key itself.
+///
+/// # Example
+///
+/// Basic usage:
+///
+/// ```
+/// use redis_server::storage::data_storage::Value;
+/// use redis_server::storage::entry::Entry;
+/// use redis_server::storage::memory::entry_usage;
+/// use std::time::{SystemTime, UNIX_EPOCH};
+/// let value = Value::String("hola".to_string());
+/// let entry = Entry::new(SystemTime::now().duration_since(UNIX_EPOCH).unwrap(), None, value);
+/// let usage = entry_usage("key", &entry, 5);
+/// ```
+///
+pub fn entry_usage(key: &str, entry: &Entry, samples: usize) -> usize {
+    let value = match entry.value() {
+        Ok(value) => value_usage(&value, samples),
+        Err(_) => size_of::<Value>(),
+    };
+    size_of::<String>() + key.len() + size_of::<Entry>() - size_of::<Value>() + value
+}
+
+/// Computes the memory stats of every entry in the database.
+pub fn stats(data: &HashMap<String, Entry>) -> MemoryStats {
+    let dataset_bytes = data
+        .iter()
+        .filter(|(_, entry)| entry.value().is_ok())
+        .map(|(key, entry)| entry_usage(key, entry, 0))
+        .sum();
+    MemoryStats {
+        keys: data.values().filter(|entry| entry.value().is_ok()).count(),
+        dataset_bytes,
+        // The keys and entries themselves are already part of the dataset.
+        overhead_bytes: table_usage::<(String, Entry)>(data.capacity())
+            - data.len() * size_of::<(String, Entry)>(),
+    }
+}
+
+/// Bytes allocated by a hash table of the given capacity, one control byte per bucket.
+fn table_usage<T>(capacity: usize) -> usize {
+    size_of::<HashSet<T>>() + capacity * (size_of::<T>() + 1)
+}
+
+fn sampled<'a>(values: impl Iterator<Item = &'a String>, len: usize, samples: usize) -> usize {
+    if samples == 0 || len <= samples {
+        return values.map(|x| x.capacity()).sum();
+    }
+    let sample: usize = values.take(samples).map(|x| x.capacity()).sum();
+    sample * len / samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_string_usage_grows_with_length() {
+        let short = value_usage(&Value::String("a".to_string()), 0);
+        let long = value_usage(&Value::String("a".repeat(1000)), 0);
+        assert_eq!(long - short, 999);
+    }
+
+    #[test]
+    fn test_samples_extrapolate_size() {
+        let vec: Vec<String> = (0..100).map(|_| "a".repeat(10)).collect();
+        let value = Value::Vec(vec);
+        assert_eq!(value_usage(&value, 5), value_usage(&value, 0));
+    }
+
+    #[test]
+    fn test_entry_usage_includes_key() {
+        let entry = Entry::new(
+            Duration::from_secs(0),
+            None,
+            Value::String("value".to_string()),
+        );
+        assert_eq!(
+            entry_usage("long_key", &entry, 0) - entry_usage("key", &entry, 0),
+            5
+        );
+    }
+
+    #[test]
+    fn test_stats_counts_keys() {
+        let mut data = HashMap::new();
+        data.insert(
+            "key".to_string(),
+            Entry::new(Duration::from_secs(0), None, Value::String("a".to_string())),
+        );
+        let stats = stats(&data);
+        assert_eq!(stats.keys, 1);
+        assert_eq!(
+            stats.dataset_bytes,
+            entry_usage("key", data.get("key").unwrap(), 0)
+        );
+        assert!(stats.used_memory() > stats.dataset_bytes);
+    }
+}
@@ -5,8 +5,10 @@ use std::sync::RwLock;
 
 //MODULOS
 pub mod data_storage;
+pub mod dump;
 pub mod entry;
 mod file_reader;
+pub mod memory;
 pub mod parser;
 
 //TIPOS
@@ -8,6 +8,7 @@ use std::time::Duration;
 static LIST: &str = "|LISTA|";
 static SET: &str = "|SET|";
 static STRING: &str = "|STRING|";
+static GEO: &str = "|GEO|";
 
 /// Given a file and a data structure get the information from the file
 /// and stores it in the structure, respecting the contained data types.
@@ -32,7 +33,7 @@ pub fn parse_data(file: &str, data: &mut HashMap<String, Entry>) -> Result<(), &
     match file_reader::read_lines(file) {
         Ok(lines) => {
             for line in lines {
-                let vec: Vec<&str> = line.split(|c| c == ';').collect();
+                let vec: Vec<&str> = line.split(';').collect();
 
                 if vec[1].contains(LIST) {
                     let (key, entry) = get_vector_data(vec);
@@ -40,6 +41,9 @@ pub fn parse_data(file: &str, data: &mut HashMap<String, Entry>) -> Result<(), &
                 } else if vec[1].contains(SET) {
                     let (key, entry) = get_set_data(vec);
                     data.insert(key, entry);
+                } else if vec[1].contains(GEO) {
+                    let (key, entry) = get_geo_data(vec);
+                    data.insert(key, entry);
                 } else {
                     let (key, entry) = get_string_data(vec);
                     data.insert(key, entry);
@@ -71,12 +75,13 @@ pub fn parse_data(file: &str, data: &mut HashMap<String, Entry>) -> Result<(), &
 /// ```
 ///
 pub fn store_data(file: &str, data: &HashMap<String, Entry>) {
-    for (key, entry) in &*data {
+    for (key, entry) in data {
         match entry.value() {
             Ok(value) => match value {
                 Value::String(s) => save_string_data(file, key, entry, s),
                 Value::Vec(i) => save_vector_data(file, key, entry, &i),
                 Value::HashSet(j) => save_set_data(file, key, entry, &j),
+                Value::Geo(g) => save_geo_data(file, key, entry, &g),
             },
             Err(_) => continue,
         };
@@ -85,18 +90,17 @@ pub fn store_data(file: &str, data: &HashMap<String, Entry>) {
 
 /// Stores information as a string in the file 'file'.
 fn save_string_data(file: &str, key: &str, entry: &Entry, value: String) {
-    let save_data: String;
     let last_access_secs = entry.last_access().unwrap().as_secs();
 
-    if entry.key_expiration() != Ok(None) {
+    let save_data = if entry.key_expiration() != Ok(None) {
         let key_expiration_secs = entry.key_expiration().unwrap().unwrap().as_secs();
-        save_data = format!(
+        format!(
             "{};{};{};{};{}",
             key, STRING, last_access_secs, key_expiration_secs, value
-        );
+        )
     } else {
-        save_data = format!("{};{};{};{};{}", key, STRING, last_access_secs, 0, value);
-    }
+        format!("{};{};{};{};{}", key, STRING, last_access_secs, 0, value)
+    };
 
     file_reader::data_to_file(file, save_data);
 }
@@ -105,20 +109,19 @@ fn save_string_data(file: &str, key: &str, entry: &Entry, value: String) {
 fn save_vector_data(file: &str, key: &str, entry: &Entry, value: &[String]) {
     let values_joined = value.join(",");
     let last_access_secs = entry.last_access().unwrap().as_secs();
-    let save_data: String;
 
-    if entry.key_expiration() != Ok(None) {
+    let save_data = if entry.key_expiration() != Ok(None) {
         let key_expiration_secs = entry.key_expiration().unwrap().unwrap().as_secs();
-        save_data = format!(
+        format!(
             "{};{};{};{};{}",
             key, LIST, last_access_secs, key_expiration_secs, values_joined
-        );
+        )
     } else {
-        save_data = format!(
+        format!(
             "{};{};{};{};{}",
             key, LIST, last_access_secs, 0, values_joined
-        );
-    }
+        )
+    };
 
     file_reader::data_to_file(file, save_data);
 }
@@ -128,20 +131,45 @@ fn save_set_data(file: &str, key: &str, entry: &Entry, value: &HashSet<String>)
     let set = value.clone();
     let values_joined = set.into_iter().collect::<Vec<String>>().join(",");
     let last_access_secs = entry.last_access().unwrap().as_secs();
-    let save_data: String;
 
-    if entry.key_expiration() != Ok(None) {
+    let save_data = if entry.key_expiration() != Ok(None) {
         let key_expiration_secs = entry.key_expiration().unwrap().unwrap().as_secs();
-        save_data = format!(
+        format!(
             "{};{};{};{};{}",
             key, SET, last_access_secs, key_expiration_secs, values_joined
-        );
+        )
     } else {
-        save_data = format!(
+        format!(
             "{};{};{};{};{}",
             key, SET, last_access_secs, 0, values_joined
-        );
-    }
+        )
+    };
+
+    file_reader::data_to_file(file, save_data);
+}
+
+/// Stores information as a geo index in the file 'file'.
+/// Each member is saved next to its geohash score as `member:score`.
+fn save_geo_data(file: &str, key: &str, entry: &Entry, value: &HashMap<String, u64>) {
+    let values_joined = value
+        .iter()
+        .map(|(member, score)| format!("{}:{}", member, score))
+        .collect::<Vec<String>>()
+        .join(",");
+    let last_access_secs = entry.last_access().unwrap().as_secs();
+
+    let save_data = if entry.key_expiration() != Ok(None) {
+        let key_expiration_secs = entry.key_expiration().unwrap().unwrap().as_secs();
+        format!(
+            "{};{};{};{};{}",
+            key, GEO, last_access_secs, key_expiration_secs, values_joined
+        )
+    } else {
+        format!(
+            "{};{};{};{};{}",
+            key, GEO, last_access_secs, 0, values_joined
+        )
+    };
 
     file_reader::data_to_file(file, save_data);
 }
@@ -153,12 +181,11 @@ fn get_string_data(vec: Vec<&str>) -> (String, Entry) {
     let last_access_number = vec[2].parse::<u64>().unwrap();
     let last_access = Duration::from_secs(last_access_number);
     let key_expiration_number = vec[3].parse::<u64>().unwrap();
-    let key_expiration: Option<Duration>;
-    if key_expiration_number != 0 {
-        key_expiration = Some(Duration::from_secs(key_expiration_number));
+    let key_expiration: Option<Duration> = if key_expiration_number != 0 {
+        Some(Duration::from_secs(key_expiration_number))
     } else {
-        key_expiration = None;
-    }
+        None
+    };
 
     let value = vec[4].to_string();
 
@@ -180,16 +207,14 @@ fn get_vector_data(mut vec: Vec<&str>) -> (String, Entry) {
     vec.remove(0);
 
     let key_expiration_number = vec[0].parse::<u64>().unwrap();
-    let key_expiration: Option<Duration>;
-
-    if key_expiration_number != 0 {
-        key_expiration = Some(Duration::from_secs(key_expiration_number));
+    let key_expiration: Option<Duration> = if key_expiration_number != 0 {
+        Some(Duration::from_secs(key_expiration_number))
     } else {
-        key_expiration = None;
-    }
+        None
+    };
     vec.remove(0);
 
-    let values: Vec<&str> = vec[0].split(|c| c == ',').collect();
+    let values: Vec<&str> = vec[0].split(',').collect();
 
     for element in values {
         data.push(element.to_string());
@@ -213,16 +238,14 @@ fn get_set_data(mut vec: Vec<&str>) -> (String, Entry) {
     vec.remove(0);
 
     let key_expiration_number = vec[0].parse::<u64>().unwrap();
-    let key_expiration: Option<Duration>;
-
-    if key_expiration_number != 0 {
-        key_expiration = Some(Duration::from_secs(key_expiration_number));
+    let key_expiration: Option<Duration> = if key_expiration_number != 0 {
+        Some(Duration::from_secs(key_expiration_number))
     } else {
-        key_expiration = None;
-    }
+        None
+    };
     vec.remove(0);
 
-    let values: Vec<&str> = vec[0].split(|c| c == ',').collect();
+    let values: Vec<&str> = vec[0].split(',').collect();
 
     for element in values {
         data.insert(element.to_string());
@@ -233,3 +256,34 @@ fn get_set_data(mut vec: Vec<&str>) -> (String, Entry) {
         Entry::new(last_access, key_expiration, Value::HashSet(data)),
     )
 }
+
+/// Get the information in the form of a geo index from the file 'file'.
+fn get_geo_data(mut vec: Vec<&str>) -> (String, Entry) {
+    let mut data: HashMap<String, u64> = HashMap::new();
+    let key = vec[0].to_string();
+    vec.remove(0);
+    vec.remove(0);
+
+    let last_access_number = vec[0].parse::<u64>().unwrap();
+    let last_access = Duration::from_secs(last_access_number);
+    vec.remove(0);
+
+    let key_expiration_number = vec[0].parse::<u64>().unwrap();
+    let key_expiration: Option<Duration> = if key_expiration_number != 0 {
+        Some(Duration::from_secs(key_expiration_number))
+    } else {
+        None
+    };
+    vec.remove(0);
+
+    for element in vec[0].split(',').filter(|x| !x.is_empty()) {
+        if let Some((member, score)) = element.rsplit_once(':') {
+            data.insert(member.to_string(), score.parse::<u64>().unwrap());
+        }
+    }
+
+    (
+        key,
+        Entry::new(last_access, key_expiration, Value::Geo(data)),
+    )
+}
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -10,9 +11,9 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("ERR wrong number of arguments for 'append' command");
+        return Err(RedisError::WrongArity("append"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -25,12 +26,11 @@ pub fn run(
             builder.add(ProtocolType::Integer(s as i64));
             Ok(())
         }
-        Err(_i) => Err("string not appended"),
+        Err(_i) => Err(RedisError::Generic("string not appended")),
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
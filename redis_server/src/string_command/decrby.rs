@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,24 +12,23 @@ pub fn run(
     data: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("decrby"));
     }
 
     let key = arguments[0].clone().string()?;
-    let number = arguments[1].clone().integer()?;
-
-    match data.decrement_value(key, number) {
-        Ok(s) => builder.add(ProtocolType::Integer(s as i64)),
-        Err(j) => builder.add(ProtocolType::Error(j.to_string())),
-    }
+    let number = arguments[1]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
 
+    let value = data.decrement_value(key, number)?;
+    builder.add(ProtocolType::Integer(value));
     Ok(())
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
@@ -79,19 +79,15 @@ mod tests {
         let mut builder = ResponseBuilder::new();
         data.set("Key", Value::String("value".to_string())).unwrap();
 
-        run(
+        let result = run(
             data.clone(),
             vec![
                 ProtocolType::String("Key".to_string()),
                 ProtocolType::String("5".to_string()),
             ],
             &mut builder,
-        )
-        .unwrap();
-
-        assert_eq!(
-            "-Cant decrement a value to a not integer value\r\n",
-            builder.serialize()
         );
+
+        assert_eq!(result, Err(RedisError::NotInteger));
     }
 }
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -9,9 +10,9 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("ERR wrong number of arguments for 'get' command");
+        return Err(RedisError::WrongArity("get"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -29,12 +30,11 @@ pub fn run(
                 Ok(())
             }
         },
-        Err(_i) => Err("Key value not a string"),
+        Err(_i) => Err(RedisError::WrongType),
     }
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::Value;
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
@@ -11,9 +12,9 @@ pub fn run(
     builder: &mut ResponseBuilder,
     arguments: Vec<ProtocolType>,
     data: &Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("Wrong quantity of arguments");
+        return Err(RedisError::WrongArity("getset"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -25,7 +26,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
@@ -88,10 +88,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 
@@ -113,10 +110,7 @@ mod tests {
 
         match result {
             Ok(_) => assert_eq!(true, false),
-            Err(msg) => assert_eq!(
-                msg,
-                "WRONGTYPE Operation against a key holding the wrong kind of value"
-            ),
+            Err(msg) => assert_eq!(msg, RedisError::WrongType),
         }
     }
 }
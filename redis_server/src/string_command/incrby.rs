@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -11,13 +12,16 @@ pub fn run(
     data: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 2 {
-        return Err("ERR wrong number of arguments for 'incrby' command");
+        return Err(RedisError::WrongArity("incrby"));
     }
 
     let key = arguments[0].clone().string()?;
-    let number = arguments[1].clone().integer()?;
+    let number = arguments[1]
+        .clone()
+        .integer()
+        .map_err(|_| RedisError::NotInteger)?;
 
     match data.increment_value(key, number) {
         Ok(result) => builder.add(ProtocolType::Integer(result)),
@@ -28,7 +32,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use crate::storage::data_storage::DataStorage;
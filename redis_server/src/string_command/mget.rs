@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::DataStorage;
 use crate::storage::data_storage::Value;
 use redis_protocol::response::ResponseBuilder;
@@ -11,7 +12,7 @@ pub fn run(
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
     data: Arc<DataStorage>,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut response = Vec::new();
     for key in arguments.iter() {
         let string_key = key.clone().string()?;
@@ -22,6 +23,7 @@ pub fn run(
                 Value::String(string) => response.push(ProtocolType::String(string)),
                 Value::Vec(_) => response.push(ProtocolType::Nil()),
                 Value::HashSet(_) => response.push(ProtocolType::Nil()),
+                Value::Geo(_) => response.push(ProtocolType::Nil()),
             },
             None => response.push(ProtocolType::Nil()),
         }
@@ -32,7 +34,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
     use std::collections::HashSet;
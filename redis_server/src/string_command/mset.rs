@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::{DataStorage, Value};
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,7 +9,7 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     let mut names = vec![];
     let mut values = vec![];
 
@@ -28,7 +29,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::{DataStorage, Value};
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -10,9 +11,9 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() <= 1 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("set"));
     }
 
     let name = arguments[0].clone().string()?;
@@ -28,24 +29,42 @@ pub fn run(
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .ok()
-            .ok_or("Cannot cast time")?;
+            .ok_or(RedisError::Generic("Cannot cast time"))?;
         match &str.to_ascii_uppercase()[..] {
             "EX" => {
-                maybe_exp =
-                    Some(Duration::from_secs(arguments[i + 1].clone().integer()? as u64) + now)
+                maybe_exp = Some(
+                    Duration::from_secs(
+                        arguments[i + 1]
+                            .clone()
+                            .integer()
+                            .map_err(|_| RedisError::NotInteger)? as u64,
+                    ) + now,
+                )
             }
             "PX" => {
-                maybe_exp =
-                    Some(Duration::from_millis(arguments[i + 1].clone().integer()? as u64) + now)
+                maybe_exp = Some(
+                    Duration::from_millis(
+                        arguments[i + 1]
+                            .clone()
+                            .integer()
+                            .map_err(|_| RedisError::NotInteger)? as u64,
+                    ) + now,
+                )
             }
             "EXAT" => {
                 maybe_exp = Some(Duration::from_secs(
-                    arguments[i + 1].clone().integer()? as u64
+                    arguments[i + 1]
+                        .clone()
+                        .integer()
+                        .map_err(|_| RedisError::NotInteger)? as u64,
                 ))
             }
             "PXAT" => {
                 maybe_exp = Some(Duration::from_millis(
-                    arguments[i + 1].clone().integer()? as u64
+                    arguments[i + 1]
+                        .clone()
+                        .integer()
+                        .map_err(|_| RedisError::NotInteger)? as u64,
                 ))
             }
             "NX" => nx = true,
@@ -56,7 +75,10 @@ pub fn run(
         }
     }
 
-    let old = db.get_with_expiration(&name);
+    // A write is not a keyspace lookup, so peek instead of counting a hit or miss.
+    let old = db
+        .peek_entry(&name)
+        .and_then(|entry| Some((entry.key_expiration().ok()?, entry.value().ok()?)));
     if old.is_none() && nx || old.is_some() && xx || !nx && !xx {
         db.set(&name, value)?;
         if let Some((old_exp, v)) = old {
@@ -82,7 +104,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -1,3 +1,4 @@
+use crate::error::RedisError;
 use crate::storage::data_storage::{DataStorage, Value};
 use redis_protocol::response::ResponseBuilder;
 use redis_protocol::types::ProtocolType;
@@ -8,9 +9,9 @@ pub fn run(
     db: Arc<DataStorage>,
     arguments: Vec<ProtocolType>,
     builder: &mut ResponseBuilder,
-) -> Result<(), &'static str> {
+) -> Result<(), RedisError> {
     if arguments.len() != 1 {
-        return Err("Wrong number of arguments");
+        return Err(RedisError::WrongArity("strlen"));
     }
 
     let key = arguments[0].clone().string()?;
@@ -18,8 +19,9 @@ pub fn run(
     if let Some(val) = maybe_val {
         match val {
             Value::String(s) => builder.add(ProtocolType::Integer(s.len() as i64)),
-            Value::HashSet(_) => return Err("Stored value is a hashset"),
-            Value::Vec(_) => return Err("Stored value is a list"),
+            Value::HashSet(_) => return Err(RedisError::WrongType),
+            Value::Vec(_) => return Err(RedisError::WrongType),
+            Value::Geo(_) => return Err(RedisError::WrongType),
         }
     } else {
         builder.add(ProtocolType::Integer(0));
@@ -28,7 +30,6 @@ pub fn run(
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
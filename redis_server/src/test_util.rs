@@ -0,0 +1,35 @@
+use crate::client::Client;
+use redis_protocol::command::Command;
+use redis_protocol::types::ProtocolType;
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Connects a client to the listener, returning the stream of the other end of the socket.
+/// Reads from the stream time out after a few seconds, so a test waiting for a reply
+/// fails instead of hanging.
+pub fn new_client(listener: &TcpListener) -> (TcpStream, Arc<Client>) {
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let (socket, _) = listener.accept().unwrap();
+    (stream, Arc::new(Client::new(socket)))
+}
+
+/// Client on a socket of its own, for tests which do not use the other end.
+pub fn client() -> Arc<Client> {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    new_client(&listener).1
+}
+
+/// Command with the given name and arguments, like `command(&["SET", "k", "v"])`.
+pub fn command(values: &[&str]) -> Command {
+    Command::new(
+        values[0].to_string(),
+        values[1..]
+            .iter()
+            .map(|x| ProtocolType::String(x.to_string()))
+            .collect(),
+    )
+}
@@ -0,0 +1,176 @@
+use redis::{Client, Connection, RedisError};
+use redis_server::server::Server;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+mod common;
+
+const GOSSIP_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn setup_node() -> (Server, u16, Client) {
+    let (server, port) = common::setup_server_with(|config| config.set_cluster_enabled(true));
+    (server, port, common::setup_client(port))
+}
+
+/// Waits until the reply of a command satisfies the condition
+fn wait_until(client: &Client, cmd: &str, condition: impl Fn(&str) -> bool) {
+    let start = Instant::now();
+    loop {
+        let reply: String = common::query_string(client, cmd);
+        if condition(&reply) {
+            return;
+        }
+        if start.elapsed() > GOSSIP_TIMEOUT {
+            panic!("Unexpected reply to {}: {}", cmd, reply);
+        }
+        sleep(Duration::from_millis(20));
+    }
+}
+
+fn query_error(connection: &mut Connection, cmd: &str) -> RedisError {
+    let mut command = redis::cmd("");
+    for (i, arg) in cmd.split(' ').enumerate() {
+        if i == 0 {
+            command = redis::cmd(arg);
+        } else {
+            command.arg(arg);
+        }
+    }
+    command.query::<redis::Value>(connection).unwrap_err()
+}
+
+fn node_id(client: &Client) -> String {
+    common::query_string(client, "CLUSTER MYID")
+}
+
+#[test]
+/// Integration test of the redirection of keys between two nodes sharing the slots
+fn test_cluster_redirects_keys_to_their_node() {
+    let (_first_server, _, first) = setup_node();
+    let (_second_server, second_port, second) = setup_node();
+
+    let _: () = common::query_string(&first, "CLUSTER ADDSLOTSRANGE 0 8191");
+    let _: () = common::query_string(&second, "CLUSTER ADDSLOTSRANGE 8192 16383");
+    let _: () = common::query(
+        &first,
+        "CLUSTER",
+        &["MEET", "127.0.0.1", &second_port.to_string()],
+    );
+    for node in [&first, &second].iter() {
+        wait_until(node, "CLUSTER INFO", |x| x.contains("cluster_state:ok"));
+    }
+
+    let keyslot: i64 = common::query_string(&first, "CLUSTER KEYSLOT foo");
+    assert_eq!(keyslot, 12182);
+    let error = query_error(&mut first.get_connection().unwrap(), "SET foo bar");
+    assert_eq!(error.code(), Some("MOVED"));
+    assert!(error
+        .to_string()
+        .contains(&format!("12182 127.0.0.1:{}", second_port)));
+
+    let _: () = common::query_string(&second, "SET foo bar");
+    let value: String = common::query_string(&second, "GET foo");
+    assert_eq!(value, "bar");
+    let error = query_error(&mut second.get_connection().unwrap(), "MSET foo 1 bar 2");
+    assert_eq!(error.code(), Some("CROSSSLOT"));
+    let _: () = common::query_string(&second, "MSET {foo}a 1 {foo}b 2");
+    let keys: i64 = common::query_string(&second, "CLUSTER COUNTKEYSINSLOT 12182");
+    assert_eq!(keys, 3);
+
+    let slots: Vec<Vec<redis::Value>> = common::query_string(&first, "CLUSTER SLOTS");
+    assert_eq!(slots.len(), 2);
+    assert_eq!(slots[1][0], redis::Value::Int(8192));
+    assert_eq!(slots[1][1], redis::Value::Int(16383));
+    assert_eq!(
+        slots[1][2],
+        redis::Value::Bulk(vec![
+            redis::Value::Data(b"127.0.0.1".to_vec()),
+            redis::Value::Int(second_port as i64),
+            redis::Value::Data(node_id(&second).into_bytes()),
+        ])
+    );
+}
+
+#[test]
+/// Integration test of a cluster of three nodes that learn each other through the gossip,
+/// and migrate a slot between two of them
+fn test_cluster_discovers_nodes_and_migrates_slot() {
+    let (_first_server, _, first) = setup_node();
+    let (_second_server, second_port, second) = setup_node();
+    let (_third_server, third_port, third) = setup_node();
+
+    let _: () = common::query_string(&first, "CLUSTER ADDSLOTSRANGE 0 16383");
+    let _: () = common::query(
+        &first,
+        "CLUSTER",
+        &["MEET", "127.0.0.1", &second_port.to_string()],
+    );
+    let _: () = common::query(
+        &second,
+        "CLUSTER",
+        &["MEET", "127.0.0.1", &third_port.to_string()],
+    );
+    for node in [&first, &second, &third].iter() {
+        wait_until(node, "CLUSTER NODES", |x| {
+            x.lines().count() == 3 && !x.contains("handshake") && !x.contains("disconnected")
+        });
+        wait_until(node, "CLUSTER INFO", |x| x.contains("cluster_state:ok"));
+    }
+
+    let (first_id, third_id) = (node_id(&first), node_id(&third));
+    let _: () = common::query_string(&first, "SET foo bar");
+    let _: () = common::query(
+        &third,
+        "CLUSTER",
+        &["SETSLOT", "12182", "IMPORTING", &first_id],
+    );
+    let _: () = common::query(
+        &first,
+        "CLUSTER",
+        &["SETSLOT", "12182", "MIGRATING", &third_id],
+    );
+
+    let value: String = common::query_string(&first, "GET foo");
+    assert_eq!(value, "bar");
+    let error = query_error(&mut first.get_connection().unwrap(), "GET {foo}new");
+    assert_eq!(error.code(), Some("ASK"));
+    assert!(error
+        .to_string()
+        .contains(&format!("12182 127.0.0.1:{}", third_port)));
+
+    let mut connection = third.get_connection().unwrap();
+    let error = query_error(&mut connection, "SET {foo}new 1");
+    assert_eq!(error.code(), Some("MOVED"));
+    let dump: Vec<u8> = common::query_string(&first, "DUMP foo");
+    let _: () = redis::cmd("ASKING").query(&mut connection).unwrap();
+    let _: () = redis::cmd("RESTORE")
+        .arg("foo")
+        .arg(0)
+        .arg(dump)
+        .query(&mut connection)
+        .unwrap();
+    let _: () = redis::cmd("ASKING").query(&mut connection).unwrap();
+    let _: () = redis::cmd("SET")
+        .arg("{foo}new")
+        .arg(1)
+        .query(&mut connection)
+        .unwrap();
+    let _: i64 = common::query_string(&first, "DEL foo");
+
+    let _: () = common::query(&third, "CLUSTER", &["SETSLOT", "12182", "NODE", &third_id]);
+    let _: () = common::query(&first, "CLUSTER", &["SETSLOT", "12182", "NODE", &third_id]);
+    let value: String = common::query_string(&third, "GET foo");
+    assert_eq!(value, "bar");
+    let start = Instant::now();
+    loop {
+        let error = query_error(&mut second.get_connection().unwrap(), "GET foo");
+        if error
+            .to_string()
+            .contains(&format!("127.0.0.1:{}", third_port))
+        {
+            break;
+        }
+        assert!(start.elapsed() < GOSSIP_TIMEOUT, "{}", error);
+        sleep(Duration::from_millis(20));
+    }
+}
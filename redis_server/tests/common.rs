@@ -2,6 +2,8 @@ use redis::{Client, FromRedisValue};
 use redis_server::config::configuration::Configuration;
 use redis_server::logging::logger::Logger;
 use redis_server::server::Server;
+use std::io::Read;
+use std::net::TcpStream;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -10,10 +12,16 @@ static PORT: AtomicU16 = AtomicU16::new(10001);
 const TIMEOUT: u64 = 5;
 
 pub fn setup_server() -> (Server, u16) {
+    setup_server_with(|_| {})
+}
+
+/// Starts a server with a configuration changed by the given function
+pub fn setup_server_with(configure: impl FnOnce(&mut Configuration)) -> (Server, u16) {
     let port = PORT.fetch_add(1, Ordering::SeqCst);
 
     let mut config = Configuration::new();
     config.set_port(port);
+    configure(&mut config);
     let logger: Arc<Logger> = Arc::new(Logger::new(config.get_logfile()).unwrap());
 
     let mut sv = Server::new(config, logger);
@@ -51,3 +59,28 @@ pub fn query<T: FromRedisValue>(client: &Client, cmd: &str, args: &[&str]) -> T
     }
     cmd.query(&mut client.get_connection().unwrap()).unwrap()
 }
+
+/// Reads from the socket until the given number of bytes arrive or the connection is closed
+#[allow(dead_code)]
+pub fn read_response(stream: &mut TcpStream, length: usize) -> String {
+    let mut response = vec![0; length];
+    let mut read = 0;
+    while read < length {
+        match stream.read(&mut response[read..]).unwrap() {
+            0 => break,
+            n => read += n,
+        }
+    }
+    String::from_utf8_lossy(&response[..read]).to_string()
+}
+
+/// Reads from the socket until the response ends with the given suffix
+#[allow(dead_code)]
+pub fn read_until(stream: &mut TcpStream, suffix: &str) -> String {
+    let mut response = Vec::new();
+    let mut byte = [0];
+    while !response.ends_with(suffix.as_bytes()) && stream.read(&mut byte).unwrap() == 1 {
+        response.push(byte[0]);
+    }
+    String::from_utf8_lossy(&response).to_string()
+}
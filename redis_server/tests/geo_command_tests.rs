@@ -0,0 +1,82 @@
+mod common;
+
+#[test]
+/// Integration test to test the correct flow of the GEOADD and GEODIST commands
+fn test_geoadd_and_geodist() {
+    let (_server, client) = common::setup();
+    let added: i64 = common::query_string(
+        &client,
+        "GEOADD Sicily 13.361389 38.115556 Palermo 15.087269 37.502669 Catania",
+    );
+    assert_eq!(added, 2);
+
+    let meters: String = common::query_string(&client, "GEODIST Sicily Palermo Catania");
+    assert_eq!(meters, "166274.1516");
+    let kilometers: String = common::query_string(&client, "GEODIST Sicily Palermo Catania km");
+    assert_eq!(kilometers, "166.2742");
+    let missing: Option<String> = common::query_string(&client, "GEODIST Sicily Palermo Rome");
+    assert_eq!(missing, None);
+
+    let key_type: String = common::query_string(&client, "TYPE Sicily");
+    assert_eq!(key_type, "zset");
+}
+
+#[test]
+/// Integration test to test the correct flow of the GEOPOS and GEOHASH commands
+fn test_geopos_and_geohash() {
+    let (_server, client) = common::setup();
+    let _: i64 = common::query_string(
+        &client,
+        "GEOADD Sicily 13.361389 38.115556 Palermo 15.087269 37.502669 Catania",
+    );
+
+    let positions: Vec<Option<(f64, f64)>> =
+        common::query_string(&client, "GEOPOS Sicily Palermo NonExisting");
+    let (longitude, latitude) = positions[0].unwrap();
+    assert!((longitude - 13.361389).abs() < 0.00001);
+    assert!((latitude - 38.115556).abs() < 0.00001);
+    assert_eq!(positions[1], None);
+
+    let hashes: Vec<String> = common::query_string(&client, "GEOHASH Sicily Palermo Catania");
+    assert_eq!(hashes, vec!["sqc8b49rny0", "sqdtr74hyu0"]);
+}
+
+#[test]
+/// Integration test to test the correct flow of the GEOSEARCH command
+fn test_geosearch() {
+    let (_server, client) = common::setup();
+    let _: i64 = common::query_string(
+        &client,
+        "GEOADD Sicily 13.361389 38.115556 Palermo 15.087269 37.502669 Catania",
+    );
+    let _: i64 = common::query_string(
+        &client,
+        "GEOADD Sicily 12.758489 38.788135 edge1 17.241510 38.788135 edge2",
+    );
+
+    let by_radius: Vec<String> = common::query_string(
+        &client,
+        "GEOSEARCH Sicily FROMLONLAT 15 37 BYRADIUS 200 km ASC",
+    );
+    assert_eq!(by_radius, vec!["Catania", "Palermo"]);
+
+    let by_box: Vec<Vec<String>> = common::query_string(
+        &client,
+        "GEOSEARCH Sicily FROMLONLAT 15 37 BYBOX 400 400 km ASC WITHDIST",
+    );
+    assert_eq!(
+        by_box,
+        vec![
+            vec!["Catania", "56.4413"],
+            vec!["Palermo", "190.4424"],
+            vec!["edge2", "279.7403"],
+            vec!["edge1", "279.7405"],
+        ]
+    );
+
+    let counted: Vec<String> = common::query_string(
+        &client,
+        "GEOSEARCH Sicily FROMMEMBER Palermo BYRADIUS 500 km COUNT 1",
+    );
+    assert_eq!(counted, vec!["Palermo"]);
+}
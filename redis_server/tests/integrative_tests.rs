@@ -1,3 +1,9 @@
+use redis_protocol::client::{Connection, Pool};
+use std::io::Write;
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::Duration;
+
 mod common;
 
 #[test]
@@ -146,3 +152,84 @@ fn test_5() {
     let lrange_val: Vec<String> = common::query_string(&client, "LRANGE key1 1 4");
     assert_eq!(lrange_val, ["4", "3", "2", "1"]);
 }
+
+#[test]
+/// Integration test to test requests split between reads and bulk strings containing CRLF.
+fn test_raw_protocol() {
+    let (_server, port) = common::setup_server();
+    let mut stream = TcpStream::connect(format!("127.0.0.1:{}", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    stream
+        .write_all(b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$4\r\na\r")
+        .unwrap();
+    sleep(Duration::from_millis(50));
+    stream
+        .write_all(b"\nb\r\n*2\r\n$3\r\nGET\r\n$1\r\nk\r\n")
+        .unwrap();
+    assert_eq!(
+        common::read_response(&mut stream, 15),
+        "+OK\r\n$4\r\na\r\nb\r\n"
+    );
+
+    stream.write_all(b"*1\r\n$x\r\n").unwrap();
+    let expected = "-ERR Protocol error: invalid bulk length\r\n";
+    assert_eq!(
+        common::read_response(&mut stream, expected.len() + 1),
+        expected
+    );
+}
+
+#[test]
+/// Integration test to test inline commands, as typed in a telnet session.
+fn test_inline_commands() {
+    let (_server, port) = common::setup_server();
+    let mut stream = TcpStream::connect(format!("127.0.0.1:{}", port)).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    stream
+        .write_all(b"PING\r\nSET foo \"hello world\"\nGET foo\r\n")
+        .unwrap();
+    let expected = "$4\r\nPONG\r\n+OK\r\n$11\r\nhello world\r\n";
+    assert_eq!(common::read_response(&mut stream, expected.len()), expected);
+
+    stream.write_all(b"SET foo \"bar\r\n").unwrap();
+    let expected = "-ERR Protocol error: unbalanced quotes in request\r\n";
+    assert_eq!(
+        common::read_response(&mut stream, expected.len() + 1),
+        expected
+    );
+}
+
+#[test]
+/// Integration test to test the RESP client library against the server.
+fn test_protocol_client() {
+    let (_server, port) = common::setup_server();
+    let addr = format!("127.0.0.1:{}", port);
+    let pool = Pool::new(&addr, 2);
+
+    let mut connection = pool.get().unwrap();
+    let mut pipeline = connection.pipeline();
+    pipeline
+        .command(&["SET", "key", "a value"])
+        .command(&["APPEND", "key", "!"])
+        .command(&["LPUSH", "key", "x"])
+        .command(&["GET", "key"]);
+    let replies = pipeline.execute().unwrap();
+    assert_eq!(replies[0].to_string(), "OK");
+    assert_eq!(replies[1].integer().unwrap(), 8);
+    assert!(replies[2].clone().error().unwrap().starts_with("WRONGTYPE"));
+    assert_eq!(replies[3].to_string(), "a value!");
+    drop(connection);
+
+    let mut pubsub = Connection::connect(&addr).unwrap().into_pubsub();
+    pubsub.subscribe(&["news"]).unwrap();
+    let published = pool.get().unwrap().query(&["PUBLISH", "news", "hi"]);
+    assert_eq!(published.unwrap().integer().unwrap(), 1);
+    assert_eq!(pubsub.next_message().unwrap().payload, "hi");
+    assert_eq!(pool.idle(), 1);
+}
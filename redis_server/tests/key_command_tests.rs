@@ -147,29 +147,60 @@ fn test_sort() {
 
     let val3: Vec<i64> = common::query_string(&client, "SORT my_key1");
     let val4: Vec<i64> = common::query_string(&client, "SORT my_key2");
-    let ok = vec![2, 3, 6];
+    let ok = [2, 3, 6];
     for i in 0..2 {
         assert_eq!(ok[i], val3[i]);
     }
 
-    let ok2 = vec![3, 6, 7, 9];
+    let ok2 = [3, 6, 7, 9];
     for i in 0..3 {
         assert_eq!(ok2[i], val4[i]);
     }
 
     let val5: Vec<i64> = common::query_string(&client, "SORT my_key1 desc");
     let val6: Vec<i64> = common::query_string(&client, "SORT my_key2 desc");
-    let ok3 = vec![6, 3, 2];
+    let ok3 = [6, 3, 2];
     for i in 0..2 {
         assert_eq!(ok3[i], val5[i]);
     }
 
-    let ok4 = vec![9, 7, 6, 3];
+    let ok4 = [9, 7, 6, 3];
     for i in 0..3 {
         assert_eq!(ok4[i], val6[i]);
     }
 }
 
+#[test]
+/// Integration test to test the correct flow of the SORT command with BY, GET, LIMIT, ALPHA and STORE
+fn test_sort_with_options() {
+    let (_server, client) = common::setup();
+    let _: i64 = common::query_string(&client, "RPUSH ids 1 2 3");
+    let _: String = common::query_string(&client, "MSET weight_1 30 weight_2 10 weight_3 20");
+    let _: String = common::query_string(&client, "MSET name_1 c name_2 a name_3 b");
+
+    let by: Vec<String> = common::query_string(&client, "SORT ids BY weight_* GET # GET name_*");
+    assert_eq!(by, vec!["2", "a", "3", "b", "1", "c"]);
+
+    let alpha: Vec<String> =
+        common::query_string(&client, "SORT ids BY name_* ALPHA DESC LIMIT 0 2");
+    assert_eq!(alpha, vec!["1", "3"]);
+
+    let nosort: Vec<String> = common::query_string(&client, "SORT ids BY nosort GET name_*");
+    assert_eq!(nosort, vec!["c", "a", "b"]);
+
+    let stored: i64 = common::query_string(&client, "SORT ids DESC STORE destination");
+    assert_eq!(stored, 3);
+    let destination: Vec<String> = common::query_string(&client, "LRANGE destination 0 2");
+    assert_eq!(destination, vec!["3", "2", "1"]);
+
+    let missing: Vec<String> = common::query_string(&client, "SORT missing");
+    assert!(missing.is_empty());
+    let stored: i64 = common::query_string(&client, "SORT missing STORE destination");
+    assert_eq!(stored, 0);
+    let exists: i64 = common::query_string(&client, "EXISTS destination");
+    assert_eq!(exists, 0);
+}
+
 #[test]
 /// Integration test to test the correct flow of the TTL command
 fn test_ttl() {
@@ -180,3 +211,52 @@ fn test_ttl() {
     assert_eq!(result, 1);
     assert!(val > 6 && val <= 10);
 }
+
+#[test]
+/// Integration test to test the correct flow of the OBJECT, RANDOMKEY, UNLINK and RENAMENX commands
+fn test_key_introspection() {
+    let (_server, client) = common::setup();
+    let _: String = common::query_string(&client, "SET counter 10");
+    let _: i64 = common::query_string(&client, "RPUSH list a b c");
+
+    let encoding: String = common::query_string(&client, "OBJECT ENCODING counter");
+    assert_eq!(encoding, "int");
+    let idle: i64 = common::query_string(&client, "OBJECT IDLETIME list");
+    assert_eq!(idle, 0);
+    let _: String = common::query_string(&client, "GET counter");
+    let freq: i64 = common::query_string(&client, "OBJECT FREQ counter");
+    assert_eq!(freq, 1);
+
+    let random: String = common::query_string(&client, "RANDOMKEY");
+    assert!(random == "counter" || random == "list");
+
+    let renamed: i64 = common::query_string(&client, "RENAMENX counter list");
+    assert_eq!(renamed, 0);
+    let renamed: i64 = common::query_string(&client, "RENAMENX counter other");
+    assert_eq!(renamed, 1);
+
+    let unlinked: i64 = common::query_string(&client, "UNLINK other list missing");
+    assert_eq!(unlinked, 2);
+    let random: Option<String> = common::query_string(&client, "RANDOMKEY");
+    assert_eq!(random, None);
+}
+
+#[test]
+/// Integration test to test copying a key between two servers with DUMP and RESTORE
+fn test_dump_restore_between_servers() {
+    let (_source_server, source) = common::setup();
+    let (_target_server, target) = common::setup();
+    let _: i64 = common::query_string(&source, "RPUSH list a b c");
+
+    let payload: String = common::query_string(&source, "DUMP list");
+    let _: String = common::query(&target, "RESTORE", &["list", "0", &payload]);
+    let restored: Vec<String> = common::query_string(&target, "LRANGE list 0 2");
+    assert_eq!(restored, vec!["a", "b", "c"]);
+
+    let busy: Result<String, _> = redis::cmd("RESTORE")
+        .arg("list")
+        .arg("0")
+        .arg(&payload)
+        .query(&mut target.get_connection().unwrap());
+    assert!(busy.is_err());
+}
@@ -11,11 +11,42 @@ fn test_monitor() {
 
     let mut monitor_conn = client1.get_connection().unwrap();
     let _: () = redis::cmd("MONITOR").query(&mut monitor_conn).unwrap();
-    let _: () = common::query_string(&client2, "KEYS *");
+    let mut conn = client2.get_connection().unwrap();
+    let _: () = redis::cmd("KEYS").arg("*").query(&mut conn).unwrap();
+    let _: () = redis::cmd("SET")
+        .arg("key")
+        .arg("say \"hi\"\n")
+        .query(&mut conn)
+        .unwrap();
 
-    let msg1 = (&mut monitor_conn).recv_response().unwrap();
+    let msg1 = String::from_redis_value(&monitor_conn.recv_response().unwrap()).unwrap();
+    let msg2 = String::from_redis_value(&monitor_conn.recv_response().unwrap()).unwrap();
 
-    assert_eq!(String::from_redis_value(&msg1).unwrap(), "KEYS *");
+    let (time, command) = msg1.split_once(' ').unwrap();
+    let (secs, micros) = time.split_once('.').unwrap();
+    assert!(secs.parse::<u64>().is_ok());
+    assert_eq!(micros.len(), 6);
+    assert!(command.starts_with("[0 127.0.0.1:"));
+    assert!(command.ends_with("] \"KEYS\" \"*\""));
+    assert!(msg2.ends_with("] \"SET\" \"key\" \"say \\\"hi\\\"\\n\""));
 
     let _: () = common::query_string(&client1, "QUIT");
 }
+
+#[test]
+/// Integration test of a monitor that disconnects, which must not affect the commands of other clients
+fn test_monitor_disconnected() {
+    let (_server, port) = common::setup_server();
+    let client1 = common::setup_client(port);
+    let client2 = common::setup_client(port);
+
+    let mut monitor_conn = client1.get_connection().unwrap();
+    let _: () = redis::cmd("MONITOR").query(&mut monitor_conn).unwrap();
+    drop(monitor_conn);
+
+    for _ in 0..3 {
+        let _: () = common::query_string(&client2, "SET key value");
+    }
+    let value: String = common::query_string(&client2, "GET key");
+    assert_eq!(value, "value");
+}
@@ -62,10 +62,7 @@ fn test_subscribe_and_publish() {
 /// with multiple clients
 fn test_multiple_clients() {
     let (_server, port) = common::setup_server();
-    let clients: Vec<Client> = vec![0; 3]
-        .iter()
-        .map(|_x| common::setup_client(port))
-        .collect();
+    let clients: Vec<Client> = [0; 3].iter().map(|_x| common::setup_client(port)).collect();
 
     let mut conn1 = clients[0].get_connection().unwrap();
     let mut conn2 = clients[1].get_connection().unwrap();
@@ -0,0 +1,105 @@
+use redis::Client;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+mod common;
+
+const SYNC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Waits until the replica holds the given value at key
+fn wait_for_value(replica: &Client, key: &str, value: &str) {
+    let start = Instant::now();
+    loop {
+        let current: Option<String> = common::query(replica, "GET", &[key]);
+        if current.as_deref() == Some(value) {
+            return;
+        }
+        if start.elapsed() > SYNC_TIMEOUT {
+            panic!("The replica did not receive {} = {}", key, value);
+        }
+        sleep(Duration::from_millis(20));
+    }
+}
+
+fn info_replication(client: &Client) -> String {
+    common::query_string(client, "INFO replication")
+}
+
+#[test]
+/// Integration test of a full sync followed by the stream of write commands
+fn test_replicaof_syncs_and_streams_writes() {
+    let (_master_server, master_port) = common::setup_server();
+    let (_replica_server, replica_port) = common::setup_server();
+    let master = common::setup_client(master_port);
+    let replica = common::setup_client(replica_port);
+
+    let _: () = common::query_string(&master, "SET before sync");
+    let _: i64 = common::query_string(&master, "LPUSH list a b c");
+    let _: () = common::query_string(&replica, "SET stale value");
+
+    let reply: String = common::query(
+        &replica,
+        "REPLICAOF",
+        &["127.0.0.1", &master_port.to_string()],
+    );
+    assert_eq!(reply, "OK");
+    wait_for_value(&replica, "before", "sync");
+    let stale: Option<String> = common::query_string(&replica, "GET stale");
+    assert_eq!(stale, None);
+    let list: Vec<String> = common::query_string(&replica, "LRANGE list 0 2");
+    assert_eq!(list, ["c", "b", "a"]);
+
+    let _: () = common::query_string(&master, "SET after sync");
+    let _: i64 = common::query_string(&master, "DEL before");
+    let _: i64 = common::query_string(&master, "SADD set x y");
+    wait_for_value(&replica, "after", "sync");
+    let deleted: i64 = common::query_string(&replica, "EXISTS before");
+    let members: i64 = common::query_string(&replica, "SCARD set");
+    assert_eq!((deleted, members), (0, 2));
+
+    let error = redis::cmd("SET")
+        .arg("key")
+        .arg("value")
+        .query::<()>(&mut replica.get_connection().unwrap())
+        .unwrap_err();
+    assert_eq!(error.code(), Some("READONLY"));
+
+    let info = info_replication(&replica);
+    assert!(info.contains("role:slave"));
+    assert!(info.contains("master_link_status:up"));
+    let role: redis::Value = common::query_string(&master, "ROLE");
+    let role = format!("{:?}", role);
+    assert!(role.contains("master"));
+    assert!(role.contains(&replica_port.to_string()));
+    assert!(info_replication(&master).contains("connected_slaves:1"));
+}
+
+#[test]
+/// Integration test of a replica that reconnects after losing the link, and is then promoted
+fn test_replica_reconnects_and_is_promoted() {
+    let (_master_server, master_port) = common::setup_server();
+    let (_replica_server, replica_port) = common::setup_server();
+    let master = common::setup_client(master_port);
+    let replica = common::setup_client(replica_port);
+
+    let _: String = common::query(
+        &replica,
+        "SLAVEOF",
+        &["127.0.0.1", &master_port.to_string()],
+    );
+    let _: () = common::query_string(&master, "SET first 1");
+    wait_for_value(&replica, "first", "1");
+
+    let killed: i64 = common::query_string(&master, "CLIENT KILL TYPE replica");
+    assert_eq!(killed, 1);
+    let _: () = common::query_string(&master, "SET second 2");
+    wait_for_value(&replica, "second", "2");
+    let first: String = common::query_string(&replica, "GET first");
+    assert_eq!(first, "1");
+
+    let reply: String = common::query_string(&replica, "REPLICAOF NO ONE");
+    assert_eq!(reply, "OK");
+    let _: () = common::query_string(&replica, "SET own write");
+    wait_for_value(&replica, "own", "write");
+    assert!(info_replication(&replica).contains("role:master"));
+}
@@ -76,6 +76,18 @@ fn test_incrby_ok() {
     assert_eq!(val4, 20);
 }
 
+#[test]
+/// Integration test to test that a non integer increment is rejected with the error of redis
+fn test_incrby_not_integer() {
+    let (_server, client) = common::setup();
+    let mut conn = client.get_connection().unwrap();
+    let result: redis::RedisResult<i64> = redis::cmd("INCRBY").arg("key").arg("a").query(&mut conn);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "An error was signalled by the server: value is not an integer or out of range"
+    );
+}
+
 #[test]
 /// Integration test to test the correct flow of the SET command
 fn test_set() {
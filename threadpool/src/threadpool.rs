@@ -1,3 +1,4 @@
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -20,8 +21,12 @@ pub enum Message {
     Terminate,
 }
 
+type Receiver = Arc<Mutex<mpsc::Receiver<Message>>>;
+
+/// Pool of worker threads which run the jobs sent with `spawn`.
+/// A worker whose job panics is replaced by a new one, so the pool keeps its size.
 pub struct ThreadPool {
-    workers: Vec<Worker>,
+    workers: Arc<Mutex<Vec<Worker>>>,
     sender: mpsc::Sender<Message>,
 }
 
@@ -33,10 +38,11 @@ impl ThreadPool {
 
         let receiver = Arc::new(Mutex::new(receiver));
 
-        let mut workers = Vec::with_capacity(size);
+        let workers = Arc::new(Mutex::new(Vec::with_capacity(size)));
 
         for _ in 0..size {
-            workers.push(Worker::new(Arc::clone(&receiver)));
+            let worker = Worker::new(Arc::clone(&receiver), Arc::clone(&workers));
+            workers.lock().unwrap().push(worker);
         }
 
         ThreadPool { workers, sender }
@@ -54,13 +60,23 @@ impl ThreadPool {
 
 impl Drop for ThreadPool {
     fn drop(&mut self) {
-        for _ in &mut self.workers {
-            self.sender.send(Message::Terminate).unwrap();
-        }
+        // Workers replaced while terminating are added to the list, so it is drained until empty.
+        loop {
+            let workers: Vec<Worker> = self.workers.lock().unwrap().drain(..).collect();
+            if workers.is_empty() {
+                break;
+            }
+
+            // Counted before sending, as a worker may take the message sent for another one and finish.
+            let running = workers.iter().filter(|x| !x.is_finished()).count();
+            for _ in 0..running {
+                self.sender.send(Message::Terminate).unwrap();
+            }
 
-        for worker in &mut self.workers {
-            if let Some(thread) = worker.thread.take() {
-                thread.join().unwrap();
+            for mut worker in workers {
+                if let Some(thread) = worker.thread.take() {
+                    let _ = thread.join();
+                }
             }
         }
     }
@@ -71,13 +87,20 @@ pub struct Worker {
 }
 
 impl Worker {
-    pub fn new(receiver: Arc<Mutex<mpsc::Receiver<Message>>>) -> Worker {
+    /// Spawns a thread which runs the jobs received until it gets a `Terminate` message.
+    /// If a job panics the thread finishes and a new worker is added to `workers` in its place.
+    pub fn new(receiver: Receiver, workers: Arc<Mutex<Vec<Worker>>>) -> Worker {
         let thread = thread::spawn(move || loop {
             let message = receiver.lock().unwrap().recv().unwrap();
 
             match message {
                 Message::NewJob(job) => {
-                    job.call_box();
+                    if panic::catch_unwind(AssertUnwindSafe(|| job.call_box())).is_err() {
+                        let mut lock = workers.lock().unwrap();
+                        lock.retain(|x| !x.is_finished());
+                        lock.push(Worker::new(Arc::clone(&receiver), Arc::clone(&workers)));
+                        break;
+                    }
                 }
                 Message::Terminate => {
                     break;
@@ -89,12 +112,17 @@ impl Worker {
             thread: Some(thread),
         }
     }
+
+    fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|x| x.is_finished())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::sync::mpsc::channel;
+    use std::time::Duration;
 
     #[test]
     fn test_execute_jobs() {
@@ -118,6 +146,20 @@ mod tests {
         assert_eq!(count, job_count);
     }
 
+    #[test]
+    fn test_replace_panicked_worker() {
+        let pool = ThreadPool::new(1);
+        let (tx, rx) = channel();
+
+        pool.spawn(|| panic!("job failed"));
+        pool.spawn(move || {
+            tx.send(1).unwrap();
+        });
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert_eq!(pool.workers.lock().unwrap().len(), 2);
+    }
+
     #[test]
     #[should_panic]
     fn test_empty_threadpool() {
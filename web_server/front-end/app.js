@@ -6,8 +6,17 @@ function main () {
                 if (command == "" ){
                     return;
                 }
-                let request = createRequest(command);
                 appendCommand(command);
+                let request;
+                let auth = parseAuth(command);
+                if (auth != null){
+                    // Las credenciales se guardan para la sesion y se envian con cada comando
+                    sessionStorage.setItem("redis-user", auth.user);
+                    sessionStorage.setItem("redis-password", auth.password);
+                    request = createRequest("PING");
+                } else {
+                    request = createRequest(command);
+                }
 
                 element.value = "";
                 setTimeout(() => {
@@ -27,10 +36,26 @@ function main () {
 function createRequest(command){
     let request = new XMLHttpRequest();
     request.open("POST", "http://localhost:8080/eval", true);
+    let password = sessionStorage.getItem("redis-password");
+    if (password != null){
+        request.setRequestHeader("Redis-User", sessionStorage.getItem("redis-user"));
+        request.setRequestHeader("Redis-Password", password);
+    }
     request.send(command);
     return request;
 }
 
+function parseAuth(command){
+    let words = command.trim().split(/\s+/);
+    if (words[0].toLowerCase() != "auth" || words.length < 2 || words.length > 3){
+        return null;
+    }
+    if (words.length == 2){
+        return { user: "default", password: words[1] };
+    }
+    return { user: words[1], password: words[2] };
+}
+
 function appendResponse(response){
     let element = document.createElement("p");
     element.classList += "responseMessage";
@@ -1,3 +1,5 @@
+use std::fmt;
+
 pub enum Method {
     Get,
     Post,
@@ -16,13 +18,13 @@ impl Method {
     }
 }
 
-impl ToString for Method {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
             Method::Get => "GET",
             Method::Post => "POST",
-        }
-        .to_string()
+        };
+        write!(f, "{}", name)
     }
 }
 
@@ -1,6 +1,7 @@
 use crate::http::method::Method;
 use core::str::SplitInclusive;
 use std::collections::HashMap;
+use std::fmt;
 
 pub struct Request<'a> {
     method: Method,
@@ -22,7 +23,7 @@ impl<'a> Request<'a> {
         self.body
     }
 
-    pub fn parse(request_str: &'a str) -> Result<Request, &'static str> {
+    pub fn parse(request_str: &'a str) -> Result<Request<'a>, &'static str> {
         let mut lines = request_str.split_inclusive("\r\n");
         let mut offset = 0;
         let (method, endpoint) = Request::parse_method_and_endpoint(&mut lines, &mut offset)?;
@@ -45,7 +46,6 @@ impl<'a> Request<'a> {
         })
     }
 
-    #[allow(dead_code)]
     pub fn headers(&self) -> &HashMap<&str, &str> {
         &self.headers
     }
@@ -94,17 +94,25 @@ impl<'a> Request<'a> {
     }
 }
 
-impl ToString for Request<'_> {
-    fn to_string(&self) -> String {
+impl fmt::Display for Request<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut headers = self
             .headers
             .iter()
-            .map(|x| format!("{}: {}", x.0.to_owned(), x.1.to_owned()))
+            .map(|(key, value)| {
+                // The password of redis sent by the page is never printed
+                if key.eq_ignore_ascii_case("Redis-Password") {
+                    format!("{}: (redacted)", key)
+                } else {
+                    format!("{}: {}", key, value)
+                }
+            })
             .collect::<Vec<String>>();
         headers.sort();
-        format!(
+        write!(
+            f,
             "{} {}\n{}\n{}",
-            self.method.to_string(),
+            self.method,
             self.endpoint,
             headers.join("\n"),
             self.body
@@ -59,7 +59,7 @@ impl Listener {
 
         println!("Received HTTP request");
         let request = http::request::Request::parse(&request_str)?;
-        println!("{}", request.to_string());
+        println!("{}", request);
         let response = handler.handle(&request);
 
         println!("Writing response to socket...");
@@ -6,13 +6,21 @@ mod server;
 use redis_server::config::configuration::Configuration;
 use redis_server::logging::logger::Logger;
 use redis_server::server::Server;
+use std::env;
 use std::sync::Arc;
 
 const REDIS_PORT: u16 = 10003;
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
     let mut config = Configuration::new();
     let logger: Arc<Logger> = Arc::new(Logger::new(config.get_logfile()).unwrap());
+    if args.len() > 1 {
+        if let Err(msj) = config.set_config(&args[1]) {
+            println!("{}", msj);
+            return;
+        }
+    }
     config.set_port(REDIS_PORT);
     let mut redis_sv = Server::new(config, logger);
     redis_sv.run();
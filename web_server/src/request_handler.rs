@@ -2,18 +2,33 @@ use crate::client;
 use crate::http::method::Method;
 use crate::http::request::Request;
 use crate::http::response::Response;
+use redis_protocol::client::Pool;
+use redis_protocol::request::split_arguments;
 use std::fs::File;
 use std::io::Read;
+use std::time::Duration;
+
+/// Idle connections to redis kept open between requests
+const POOL_SIZE: usize = 8;
+/// Time to wait for redis to reply
+const REDIS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Headers with the credentials of the redis user, sent by the page after an AUTH
+const USER_HEADER: &str = "Redis-User";
+const PASSWORD_HEADER: &str = "Redis-Password";
 
 pub struct RequestHandler {
-    port: u16,
+    pool: Pool,
 }
 
 const INVALID_COMMAND_MSG: &str = "I'm sorry, I don't recognize that command.";
 
 impl RequestHandler {
+    /// Creates a handler whose connections to redis are not authenticated:
+    /// each request must carry the credentials of its user if redis requires them.
     pub fn new(port: u16) -> Self {
-        RequestHandler { port }
+        let pool =
+            Pool::new(&format!("127.0.0.1:{}", port), POOL_SIZE).with_timeout(Some(REDIS_TIMEOUT));
+        RequestHandler { pool }
     }
 
     pub fn handle(&self, request: &Request) -> Response {
@@ -38,12 +53,10 @@ impl RequestHandler {
     }
 
     fn handle_eval_request(&self, request: &Request) -> Response {
-        let connection_port = "127.0.0.1:".to_string() + &self.port.to_string();
-
         if request.endpoint() == "/eval" {
             if Self::valid_command(request.body()) {
                 let body = request.body();
-                let response = client::send_request(connection_port, body);
+                let response = client::send_request(&self.pool, body, Self::credentials(request));
                 match response {
                     Ok(resp) => Response::new().with_status(200).with_body(resp),
                     Err(_) => Response::new()
@@ -62,18 +75,57 @@ impl RequestHandler {
         }
     }
 
+    /// Returns the user and password sent in the headers of the request.
+    /// The user is `default` if only the password is sent.
+    fn credentials<'a>(request: &'a Request) -> Option<(&'a str, &'a str)> {
+        let header = |name: &str| {
+            request
+                .headers()
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| *value)
+        };
+        let password = header(PASSWORD_HEADER)?;
+        Some((header(USER_HEADER).unwrap_or("default"), password))
+    }
+
+    /// Rejects the commands that change the state of the connection, or that are answered
+    /// with more than one reply, since connections are shared between requests
     fn valid_command(body: &str) -> bool {
         let commands = [
             "unsubscribe",
             "subscribe",
-            "publish",
+            "psubscribe",
             "punsubscribe",
+            "ssubscribe",
+            "sunsubscribe",
+            "publish",
             "pubsub",
             "monitor",
             "quit",
+            "auth",
+            "hello",
+            "client",
+            "select",
+            "reset",
+            "multi",
+            "exec",
+            "discard",
+            "watch",
+            "unwatch",
+            "readonly",
+            "readwrite",
+            "asking",
+            "replconf",
+            "psync",
+            "sync",
         ];
-        let cmd: &str = body.split_whitespace().next().unwrap_or("");
-        !commands.iter().any(|e| *e == cmd)
+        let cmd = split_arguments(body)
+            .ok()
+            .and_then(|x| x.into_iter().next())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        !commands.contains(&cmd.as_str())
     }
 
     fn read_lines(filename: &str) -> Result<Vec<u8>, &'static str> {
@@ -88,3 +140,41 @@ impl RequestHandler {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_command() {
+        assert!(RequestHandler::valid_command("GET key"));
+        assert!(RequestHandler::valid_command("set subscribe monitor"));
+        assert!(!RequestHandler::valid_command("SUBSCRIBE channel"));
+        assert!(!RequestHandler::valid_command("Hello 3"));
+        assert!(!RequestHandler::valid_command("client setname web"));
+        assert!(!RequestHandler::valid_command("  select 1"));
+        assert!(!RequestHandler::valid_command("\"monitor\""));
+    }
+
+    #[test]
+    fn test_credentials() {
+        let request = Request::parse("POST /eval HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(RequestHandler::credentials(&request), None);
+
+        let request =
+            Request::parse("POST /eval HTTP/1.1\r\nredis-password: secreto\r\n\r\n").unwrap();
+        assert_eq!(
+            RequestHandler::credentials(&request),
+            Some(("default", "secreto"))
+        );
+
+        let request = Request::parse(
+            "POST /eval HTTP/1.1\r\nRedis-User: alice\r\nRedis-Password: pass\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(
+            RequestHandler::credentials(&request),
+            Some(("alice", "pass"))
+        );
+    }
+}